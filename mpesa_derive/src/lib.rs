@@ -2,7 +2,6 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn;
 
 #[proc_macro_derive(MpesaSecurity)]
 pub fn mpesa_security_derive(input: TokenStream) -> TokenStream {
@@ -23,21 +22,47 @@ fn impl_mpesa_security(ast: &syn::DeriveInput) -> TokenStream {
 
         impl MpesaSecurity for #name {
             fn gen_security_credentials(&self) -> Result<String, Box<dyn Error>> {
-                let pem = self.environment.get_certificate().as_bytes();
-                let cert = X509::from_pem(pem).expect("error extracting X509 from pem");
+                // a credential computed outside the crate, e.g. in an HSM, is sent as is
+                if let Some(credentials) = self.security_credential.as_ref() {
+                    return Ok(credentials.clone());
+                }
+
+                // the password and certificate never change, so encrypt once per client
+                let mut security_credentials = self.security_credentials.lock().unwrap();
+                if let Some(credentials) = security_credentials.as_ref() {
+                    return Ok(credentials.clone());
+                }
+
+                let initiator_password = self.initiator_password.as_ref()
+                    .ok_or(crate::MpesaError::MissingField("initiator_password"))?;
+
+                // a certificate supplied through the builder or downloaded by
+                // refresh_security_certificate overrides the environment's default,
+                // custom environments have none and must supply one
+                let certificate = self.certificate.lock().unwrap();
+                let bytes = match certificate.as_ref() {
+                    Some(certificate) => certificate.as_slice(),
+                    None => self.environment.certificate()
+                        .ok_or("custom environments require a security certificate")?
+                        .as_bytes(),
+                };
+                let cert = X509::from_pem(bytes).or_else(|_| X509::from_der(bytes))?;
                 // getting the public and rsa keys
-                let pub_key = cert.public_key().expect("error getting public key");
-                let rsa_key = pub_key.rsa().expect("error getting rsa key from pub_key");
+                let pub_key = cert.public_key()?;
+                let rsa_key = pub_key.rsa()?;
                 // configuring the buffer
                 let buf_len = pub_key.size();
                 let mut buffer = vec![0; buf_len];
 
                 rsa_key.public_encrypt(
-                    self.initiator_password.as_bytes(),
+                    initiator_password.as_bytes(),
                     &mut buffer,
                     Padding::PKCS1,
                 )?;
-                Ok(encode(buffer))
+
+                let credentials = encode(buffer);
+                *security_credentials = Some(credentials.clone());
+                Ok(credentials)
             }
         }
     };
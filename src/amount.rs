@@ -0,0 +1,74 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display,Formatter,Result as FmtResult};
+use serde::{Serialize,Serializer};
+
+/// A transaction amount in whole Kenyan shillings.
+///
+/// M-Pesa only moves whole shillings, rejects zero amounts and caps a single
+/// transaction at `Amount::MAX`, so an `Amount` is always within those limits.
+///
+/// ## Example
+/// ```
+/// use mpesa::Amount;
+///
+/// let amount = Amount::try_new(1000).unwrap();
+/// assert_eq!(amount.to_string(), "1000");
+/// assert!(Amount::try_new(0).is_err());
+/// assert!(Amount::try_new(Amount::MAX + 1).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Amount(u32);
+
+impl Amount {
+    /// Smallest amount accepted by the API
+    pub const MIN: u32 = 1;
+    /// Largest amount allowed in a single M-Pesa transaction
+    pub const MAX: u32 = 250_000;
+
+    /// Validates that `amount` is within `Amount::MIN..=Amount::MAX`
+    pub fn try_new(amount: u32) -> Result<Self, AmountError> {
+        if !(Self::MIN..=Self::MAX).contains(&amount) {
+            return Err(AmountError(amount));
+        }
+
+        Ok(Self(amount))
+    }
+
+    /// The amount in shillings
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl TryFrom<u32> for Amount {
+    type Error = AmountError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::try_new(value)
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+/// Error returned when an amount is zero or above `Amount::MAX`
+#[derive(Debug)]
+pub struct AmountError(u32);
+
+impl Display for AmountError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "invalid amount: {} (must be between {} and {})", self.0, Amount::MIN, Amount::MAX)
+    }
+}
+
+impl Error for AmountError {}
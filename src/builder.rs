@@ -0,0 +1,625 @@
+use std::env;
+use std::fmt::{Debug,Formatter,Result as FmtResult};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use openssl::x509::X509;
+use reqwest::Proxy;
+use reqwest::header::{HeaderMap,HeaderName,HeaderValue};
+
+use crate::{Environment, Mpesa, MpesaAsync, MpesaError};
+use crate::clock::Clock;
+use crate::retry::RetryPolicy;
+use crate::validation::Report;
+use crate::limits::Limits;
+use crate::metrics::{MetricsHook,RequestMetrics};
+use crate::masked::Masked;
+
+/// Total request timeout used unless `MpesaBuilder::timeout` is set
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long idle connections are kept alive for reuse unless `MpesaBuilder::pool_idle_timeout` is set
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// `User-Agent` sent unless `MpesaBuilder::user_agent` is set, e.g. `mpesa-rust/0.1.5`
+pub const DEFAULT_USER_AGENT: &str = concat!("mpesa-rust/", env!("CARGO_PKG_VERSION"));
+
+/// Builder for the `Mpesa` and `MpesaAsync` clients.
+/// The credentials and environment are required; `build` fails with `MpesaError::MissingField`
+/// naming the field that was not set or is empty, or with `MpesaError::Validation` listing
+/// every such field if there are several.
+///
+/// Requests time out after `DEFAULT_TIMEOUT` (30 seconds) unless `timeout` is set.
+/// No connect timeout is applied unless `connect_timeout` is set.
+/// Failed requests are not retried unless `max_retries` is set.
+/// Idle connections are kept alive for `DEFAULT_POOL_IDLE_TIMEOUT` (90 seconds) unless
+/// `pool_idle_timeout` is set, with no limit on their number unless `pool_max_idle_per_host` is set.
+/// Requests connect directly (or through the proxies set in the system's environment
+/// variables) unless `proxy` is set.
+/// Requests are sent with `DEFAULT_USER_AGENT` unless `user_agent` is set.
+/// Amounts are checked against `Limits::default()` unless `limits` is set.
+/// No request metrics are reported unless `on_request_complete` is set.
+/// Security credentials are generated with the environment's certificate unless
+/// `security_certificate` or `security_certificate_path` is set.
+/// Access tokens are requested from the environment's base url unless `auth_base_url` is set.
+/// STK push timestamps are taken from the system's clock unless `clock` is set.
+///
+/// # Example
+/// ```
+/// use mpesa::{Mpesa, Environment};
+///
+/// let client = Mpesa::builder()
+///     .client_key("your_client_key")
+///     .client_secret("your_client_secret")
+///     .environment(Environment::Sandbox)
+///     .initiator_password("your_initiator_password")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct MpesaBuilder {
+    client_key: Option<String>,
+    client_secret: Option<String>,
+    environment: Option<Environment>,
+    initiator_password: Option<String>,
+    security_credential: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    retry_policy: RetryPolicy,
+    limits: Limits,
+    metrics: Option<MetricsHook>,
+    certificate: Option<Certificate>,
+    certificate_url: Option<String>,
+    auth_base_url: Option<String>,
+    clock: Option<Arc<dyn Clock>>,
+    proxies: Vec<Proxy>,
+    user_agent: Option<String>,
+    headers: HeaderMap,
+    http_client: Option<reqwest::blocking::Client>,
+    async_http_client: Option<reqwest::Client>,
+    record_requests: bool,
+    dry_run: bool,
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    accept_invalid_certs: bool,
+}
+
+impl Debug for MpesaBuilder {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        // secrets only show whether they are set
+        let secret = |value: &Option<String>| value.as_ref().map(|_| "****");
+
+        f.debug_struct("MpesaBuilder")
+            .field("client_key", &self.client_key.as_deref().map(Masked))
+            .field("client_secret", &secret(&self.client_secret))
+            .field("environment", &self.environment)
+            .field("initiator_password", &secret(&self.initiator_password))
+            .field("security_credential", &secret(&self.security_credential))
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("retry_policy", &self.retry_policy)
+            .field("limits", &self.limits)
+            .field("certificate_url", &self.certificate_url)
+            .field("auth_base_url", &self.auth_base_url)
+            .field("record_requests", &self.record_requests)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// Where to load the certificate used to generate security credentials from
+#[derive(Debug)]
+enum Certificate {
+    Bytes(Vec<u8>),
+    Path(PathBuf),
+}
+
+/// The builder's fields once validated, shared by `build` and `build_async`
+struct ClientOptions {
+    client_key: String,
+    client_secret: String,
+    environment: Environment,
+    initiator_password: Option<String>,
+    security_credential: Option<String>,
+    certificate: Option<Vec<u8>>,
+    certificate_url: Option<String>,
+    auth_base_url: Option<String>,
+    clock: Option<Arc<dyn Clock>>,
+    retry_policy: RetryPolicy,
+    limits: Limits,
+    metrics: Option<MetricsHook>,
+    timeout: Duration,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Duration,
+    pool_max_idle_per_host: Option<usize>,
+    proxies: Vec<Proxy>,
+    user_agent: String,
+    headers: HeaderMap,
+    record_requests: bool,
+    dry_run: bool,
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    accept_invalid_certs: bool,
+}
+
+/// Builds a `$client` from `ClientOptions`, with `$http_client` if one was set or a new
+/// `$http` client otherwise. The blocking and async reqwest clients and builders share no
+/// trait, so `build` and `build_async` expand this instead of calling a function.
+macro_rules! build_client {
+    ($client:ident, $http:path, $http_client:expr, $options:expr) => {{
+        let options = $options;
+        let http_client = match $http_client {
+            Some(http_client) => http_client,
+            None => {
+                let mut http_client = <$http>::builder()
+                    .timeout(options.timeout)
+                    .pool_idle_timeout(options.pool_idle_timeout)
+                    .user_agent(options.user_agent)
+                    .default_headers(options.headers);
+                if let Some(connect_timeout) = options.connect_timeout {
+                    http_client = http_client.connect_timeout(connect_timeout);
+                }
+                if let Some(pool_max_idle_per_host) = options.pool_max_idle_per_host {
+                    http_client = http_client.pool_max_idle_per_host(pool_max_idle_per_host);
+                }
+                #[cfg(feature = "danger-accept-invalid-certs")]
+                {
+                    if options.accept_invalid_certs {
+                        check_accept_invalid_certs(&options.environment)?;
+                        http_client = http_client.danger_accept_invalid_certs(true);
+                    }
+                }
+                for proxy in options.proxies {
+                    http_client = http_client.proxy(proxy);
+                }
+                http_client.build()?
+            }
+        };
+
+        let client = $client::with_client(
+            options.client_key,
+            options.client_secret,
+            options.environment,
+            options.initiator_password,
+            options.certificate,
+            http_client,
+            options.retry_policy,
+        )
+            .with_limits(options.limits)
+            .with_metrics(options.metrics)
+            .with_certificate_url(options.certificate_url)
+            .with_auth_base_url(options.auth_base_url)
+            .with_security_credential(options.security_credential)
+            .with_clock(options.clock);
+
+        let client = if options.dry_run { client.dry_run() } else { client };
+
+        Ok(if options.record_requests { client.recording() } else { client })
+    }};
+}
+
+impl MpesaBuilder {
+    /// Constructs a new, empty `MpesaBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the client key from the Safaricom developer portal
+    pub fn client_key<S: Into<String>>(mut self, client_key: S) -> Self {
+        self.client_key = Some(client_key.into());
+        self
+    }
+
+    /// Sets the client secret from the Safaricom developer portal
+    pub fn client_secret<S: Into<String>>(mut self, client_secret: S) -> Self {
+        self.client_secret = Some(client_secret.into());
+        self
+    }
+
+    /// Sets the environment the client will send requests to
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Sets the initiator password used to generate security credentials.
+    /// Required unless a `security_credential` is set.
+    pub fn initiator_password<S: Into<String>>(mut self, initiator_password: S) -> Self {
+        self.initiator_password = Some(initiator_password.into());
+        self
+    }
+
+    /// Sets a `SecurityCredential` computed outside the crate, e.g. by an HSM or a signing
+    /// service, sent with every request that needs one instead of encrypting the initiator
+    /// password, so that the password never reaches the application. The initiator password
+    /// is optional when it is set.
+    ///
+    /// The credential must be encrypted with the certificate of the environment the client
+    /// sends requests to, and replaced when Safaricom rotates its certificates.
+    pub fn security_credential<S: Into<String>>(mut self, security_credential: S) -> Self {
+        self.security_credential = Some(security_credential.into());
+        self
+    }
+
+    /// Sets the total timeout of each request, from connecting until the response
+    /// body has been read. Defaults to `DEFAULT_TIMEOUT`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets how long an idle connection is kept alive for reuse by later requests.
+    /// Defaults to `DEFAULT_POOL_IDLE_TIMEOUT`.
+    pub fn pool_idle_timeout(mut self, pool_idle_timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(pool_idle_timeout);
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept alive to the API host, e.g. to
+    /// match the number of concurrent requests of a payout service. Unlimited by default;
+    /// `0` disables connection reuse.
+    pub fn pool_max_idle_per_host(mut self, pool_max_idle_per_host: usize) -> Self {
+        self.pool_max_idle_per_host = Some(pool_max_idle_per_host);
+        self
+    }
+
+    /// Sets how many times a request is retried after a transient failure.
+    ///
+    /// Server errors (5xx) and failed connections are retried for every request;
+    /// other network errors are only retried when requesting an access token, so that
+    /// a payment that may have reached the API is never submitted twice.
+    /// Rate limited requests (429) are retried after the delay asked for in their
+    /// `Retry-After` header, if any. A delay of more than a minute is not waited for;
+    /// the request fails with `MpesaError::RateLimited` carrying it instead.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the base delay between retries, doubled after every attempt and jittered.
+    /// Defaults to 500 milliseconds.
+    pub fn base_backoff(mut self, base_backoff: Duration) -> Self {
+        self.retry_policy.base_backoff = base_backoff;
+        self
+    }
+
+    /// Sets the per-transaction amount limits of each API, e.g. the limits agreed with
+    /// Safaricom for your organization. Requests with an amount outside the limits fail
+    /// with `MpesaError::AmountOutOfRange` before they are sent.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Calls `hook` with the endpoint, duration, status and retry count of every request,
+    /// including OAuth requests, e.g. to feed dashboards or alert on failure rates.
+    /// No metrics are collected unless a hook is set.
+    ///
+    /// # Example
+    /// ```
+    /// use mpesa::{Mpesa, Environment};
+    ///
+    /// let client = Mpesa::builder()
+    ///     .client_key("your_client_key")
+    ///     .client_secret("your_client_secret")
+    ///     .environment(Environment::Sandbox)
+    ///     .initiator_password("your_initiator_password")
+    ///     .on_request_complete(|metrics| {
+    ///         println!("{} {:?} in {:?}", metrics.endpoint, metrics.status, metrics.duration);
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn on_request_complete<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RequestMetrics) + Send + Sync + 'static,
+    {
+        self.metrics = Some(MetricsHook::new(hook));
+        self
+    }
+
+    /// Routes requests, including OAuth requests, through `proxy`.
+    /// May be called several times, e.g. to set separate `http` and `https` proxies.
+    ///
+    /// # Example
+    /// ```
+    /// use mpesa::{Mpesa, Environment, Proxy};
+    ///
+    /// let client = Mpesa::builder()
+    ///     .client_key("your_client_key")
+    ///     .client_secret("your_client_secret")
+    ///     .environment(Environment::Sandbox)
+    ///     .initiator_password("your_initiator_password")
+    ///     .proxy(Proxy::all("http://127.0.0.1:8080").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request, including OAuth requests.
+    /// Defaults to `DEFAULT_USER_AGENT`.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Adds a header sent with every request, including OAuth requests, e.g. an api key
+    /// required by a gateway in front of the M-Pesa API.
+    ///
+    /// # Example
+    /// ```
+    /// use mpesa::{Mpesa, Environment, HeaderName, HeaderValue};
+    ///
+    /// let client = Mpesa::builder()
+    ///     .client_key("your_client_key")
+    ///     .client_secret("your_client_secret")
+    ///     .environment(Environment::Sandbox)
+    ///     .initiator_password("your_initiator_password")
+    ///     .default_header(HeaderName::from_static("x-api-key"), HeaderValue::from_static("your_api_key"))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Sends requests through `http_client`, e.g. one configured with an organization's
+    /// TLS roots, instead of a client built by the crate. Used by `build`.
+    ///
+    /// The options configuring the crate's own client, i.e. `timeout`, `connect_timeout`,
+    /// `pool_idle_timeout`, `pool_max_idle_per_host`, `proxy`, `user_agent`,
+    /// `default_header` and `danger_accept_invalid_certs`, are ignored when it is set. Retries, the token cache and
+    /// every other option still apply.
+    pub fn http_client(mut self, http_client: reqwest::blocking::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Sends requests through `http_client` instead of a client built by the crate,
+    /// like `http_client` does for `build`. Used by `build_async`.
+    pub fn async_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.async_http_client = Some(http_client);
+        self
+    }
+
+    /// Builds a client for tests that records the url and JSON body of every API call
+    /// instead of sending it. No access token is requested, and every call returns an
+    /// accepted response with empty ids. Use `last_request` to inspect what was sent.
+    ///
+    /// # Example
+    /// ```
+    /// use mpesa::{Amount, CommandId, Environment, Mpesa, PhoneNumber, ShortCode};
+    ///
+    /// let client = Mpesa::builder()
+    ///     .client_key("client_key")
+    ///     .client_secret("client_secret")
+    ///     .environment(Environment::Sandbox)
+    ///     .initiator_password("initiator_password")
+    ///     .record_requests()
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// client.b2c()
+    ///     .initiator_name("testapi496")
+    ///     .command_id(CommandId::BusinessPayment)
+    ///     .amount(Amount::try_new(1000).unwrap())
+    ///     .party_a(&ShortCode::new("600496").unwrap())
+    ///     .party_b(&PhoneNumber::new("254708374149").unwrap())
+    ///     .remarks("gg")
+    ///     .queue_timeout_url("https://muriuki.dev/api/a")
+    ///     .result_url("https://muriuki.dev/api/b")
+    ///     .occasion("Test")
+    ///     .send()
+    ///     .unwrap();
+    ///
+    /// let request = client.last_request().unwrap();
+    /// assert_eq!(request.body["PartyA"], "600496");
+    /// assert_eq!(request.body["CommandID"], "BusinessPayment");
+    /// ```
+    pub fn record_requests(mut self) -> Self {
+        self.record_requests = true;
+        self
+    }
+
+    /// Builds a client that logs the url and JSON body of every API call instead of
+    /// sending it, e.g. to review what a staging deployment would send.
+    ///
+    /// **No transaction takes place and nothing reaches Safaricom.** No access token is
+    /// requested, so no real credentials are needed, and every call returns an accepted
+    /// response with generated ids. Requests are logged as `tracing` debug events, with
+    /// security credentials and passwords redacted, when the `tracing` feature is enabled.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Disables TLS certificate verification, e.g. to test against a local mock server
+    /// with a self-signed certificate. Requires the `danger-accept-invalid-certs` feature.
+    ///
+    /// # Warning
+    /// This accepts any certificate, including expired ones and ones for other hosts,
+    /// leaving every request open to interception. It must never be used in production:
+    /// `build` fails with a `MpesaError::EnvironmentError` if it is set together with
+    /// `Environment::Production`.
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    pub fn danger_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Sets the M-Pesa public key certificate, PEM or DER encoded, used to generate
+    /// security credentials instead of the environment's default.
+    pub fn security_certificate<B: Into<Vec<u8>>>(mut self, certificate: B) -> Self {
+        self.certificate = Some(Certificate::Bytes(certificate.into()));
+        self
+    }
+
+    /// Reads the M-Pesa public key certificate, PEM or DER encoded, used to generate
+    /// security credentials instead of the environment's default from `path`.
+    pub fn security_certificate_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.certificate = Some(Certificate::Path(path.into()));
+        self
+    }
+
+    /// Sets the url `refresh_security_certificate` downloads the M-Pesa public key
+    /// certificate, PEM or DER encoded, from.
+    pub fn security_certificate_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.certificate_url = Some(url.into());
+        self
+    }
+
+    /// Requests access tokens from `url`, e.g. `https://auth.partner.example`, instead of the
+    /// environment's base url, for deployments where the OAuth host differs from the API host.
+    /// The trailing slash, if any, is ignored.
+    pub fn auth_base_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.auth_base_url = Some(url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Takes the current time from `clock` instead of the system's clock, e.g. a
+    /// `clock::FixedClock` to assert the exact `Timestamp` and `Password` of STK push requests.
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Reads `CLIENT_KEY`, `CLIENT_SECRET`, `INIT_PASSWORD` and `MPESA_ENVIRONMENT`
+    /// (`sandbox` or `production`) into a new builder.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::EnvironmentError` if a variable is not set or
+    /// `MPESA_ENVIRONMENT` is not a valid environment
+    pub fn from_env() -> Result<Self, MpesaError> {
+        let environment = env_var("MPESA_ENVIRONMENT")?
+            .parse::<Environment>()
+            .map_err(|e| MpesaError::EnvironmentError(e.to_string()))?;
+
+        Ok(Self::new()
+            .client_key(env_var("CLIENT_KEY")?)
+            .client_secret(env_var("CLIENT_SECRET")?)
+            .environment(environment)
+            .initiator_password(env_var("INIT_PASSWORD")?))
+    }
+
+    /// Builds a blocking `Mpesa` client.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if a field was not set or is empty
+    /// (a `MpesaError::Validation` if several were not),
+    /// a `MpesaError::EncryptionError` if the security certificate could not be loaded,
+    /// or a `MpesaError::NetworkError` if the http client could not be built
+    pub fn build(mut self) -> Result<Mpesa, MpesaError> {
+        let http_client = self.http_client.take();
+        let options = self.options()?;
+
+        build_client!(Mpesa, reqwest::blocking::Client, http_client, options)
+    }
+
+    /// Builds an asynchronous `MpesaAsync` client.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if a field was not set or is empty
+    /// (a `MpesaError::Validation` if several were not),
+    /// a `MpesaError::EncryptionError` if the security certificate could not be loaded,
+    /// or a `MpesaError::NetworkError` if the http client could not be built
+    pub fn build_async(mut self) -> Result<MpesaAsync, MpesaError> {
+        let http_client = self.async_http_client.take();
+        let options = self.options()?;
+
+        build_client!(MpesaAsync, reqwest::Client, http_client, options)
+    }
+
+    /// Takes the options shared by `build` and `build_async`, loading the certificate and
+    /// validating the required fields
+    fn options(self) -> Result<ClientOptions, MpesaError> {
+        let certificate = self.load_certificate()?;
+
+        let mut report = Report::default();
+        let client_key = report.required("client_key", non_empty(self.client_key));
+        let client_secret = report.required("client_secret", non_empty(self.client_secret));
+        let environment = report.required("environment", self.environment);
+        // a security credential replaces the initiator password
+        let (initiator_password, security_credential) = match self.security_credential {
+            Some(security_credential) => (
+                non_empty(self.initiator_password),
+                report.required("security_credential", non_empty(Some(security_credential))),
+            ),
+            None => (report.required("initiator_password", non_empty(self.initiator_password)), None),
+        };
+        report.finish()?;
+
+        Ok(ClientOptions {
+            client_key: client_key.expect("reported as missing otherwise"),
+            client_secret: client_secret.expect("reported as missing otherwise"),
+            environment: environment.expect("reported as missing otherwise"),
+            initiator_password,
+            security_credential,
+            certificate,
+            certificate_url: self.certificate_url,
+            auth_base_url: self.auth_base_url,
+            clock: self.clock,
+            retry_policy: self.retry_policy,
+            limits: self.limits,
+            metrics: self.metrics,
+            timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            connect_timeout: self.connect_timeout,
+            pool_idle_timeout: self.pool_idle_timeout.unwrap_or(DEFAULT_POOL_IDLE_TIMEOUT),
+            pool_max_idle_per_host: self.pool_max_idle_per_host,
+            proxies: self.proxies,
+            user_agent: self.user_agent.unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            headers: self.headers,
+            record_requests: self.record_requests,
+            dry_run: self.dry_run,
+            #[cfg(feature = "danger-accept-invalid-certs")]
+            accept_invalid_certs: self.accept_invalid_certs,
+        })
+    }
+
+    /// Reads the security certificate, if any, checking that it is a valid X509 certificate
+    fn load_certificate(&self) -> Result<Option<Vec<u8>>, MpesaError> {
+        let certificate = match &self.certificate {
+            Some(Certificate::Bytes(bytes)) => bytes.clone(),
+            Some(Certificate::Path(path)) => fs::read(path).map_err(|e| {
+                MpesaError::EncryptionError(format!("error reading {}: {}", path.display(), e))
+            })?,
+            None => return Ok(None),
+        };
+
+        X509::from_pem(&certificate)
+            .or_else(|_| X509::from_der(&certificate))
+            .map_err(|e| MpesaError::EncryptionError(format!("invalid certificate: {}", e)))?;
+
+        Ok(Some(certificate))
+    }
+}
+
+/// Refuses to disable TLS certificate verification against production
+#[cfg(feature = "danger-accept-invalid-certs")]
+fn check_accept_invalid_certs(environment: &Environment) -> Result<(), MpesaError> {
+    match environment {
+        Environment::Production => Err(MpesaError::EnvironmentError(
+            "invalid certificates cannot be accepted in production".to_string()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Treats an empty builder field as missing
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+fn env_var(key: &str) -> Result<String, MpesaError> {
+    env::var(key).map_err(|_| MpesaError::EnvironmentError(format!("{} is not set", key)))
+}
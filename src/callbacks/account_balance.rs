@@ -0,0 +1,118 @@
+use serde::Deserialize;
+use super::result::{amount_digits,parse_cents,ParameterValue,ResultParameters};
+use crate::ResultCode;
+
+#[derive(Debug, Deserialize)]
+#[serde(from = "AccountBalanceCallbackBody")]
+/// Result posted by Safaricom to the `ResultURL` of an account balance request
+/// See https://developer.safaricom.co.ke/docs#account-balance-api for a
+/// detailed description of each field.
+///
+/// The delimited `AccountBalance` result parameter is parsed into `AccountBalance`,
+/// which is empty when the request failed.
+pub struct AccountBalanceCallback {
+    pub ResultType: i32,
+    pub ResultCode: i32,
+    pub ResultDesc: String,
+    pub OriginatorConversationID: String,
+    pub ConversationID: String,
+    pub TransactionID: String,
+    pub AccountBalance: Vec<AccountBalanceEntry>,
+    pub BOCompletedTime: Option<String>,
+    pub ResultParameters: ResultParameters,
+}
+
+impl AccountBalanceCallback {
+    /// The parsed `ResultCode`
+    pub fn result_code(&self) -> ResultCode {
+        ResultCode::from_code(&self.ResultCode.to_string())
+    }
+
+    /// Looks up the value of the result parameter named `key`
+    pub fn parameter(&self, key: &str) -> Option<&ParameterValue> {
+        self.ResultParameters.get(key)
+    }
+
+    /// Looks up the balance of the account named `name`, e.g. `"Working Account"`
+    pub fn balance(&self, name: &str) -> Option<&AccountBalanceEntry> {
+        self.AccountBalance.iter().find(|entry| entry.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The balance of a single account of an organization
+pub struct AccountBalanceEntry {
+    /// Account name, e.g. `"Working Account"` or `"Utility Account"`
+    pub name: String,
+    /// ISO currency code, e.g. `"KES"`
+    pub currency: String,
+    pub amount: f64,
+    /// The exact amount in cents, e.g. `70000000` for `700000.00`
+    pub amount_cents: i64,
+    /// The account as sent, e.g. `"Working Account|KES|700000.00|700000.00|0.00|0.00"`
+    pub raw: String,
+}
+
+#[derive(Deserialize)]
+struct AccountBalanceCallbackBody {
+    Result: AccountBalanceResult,
+}
+
+#[derive(Deserialize)]
+struct AccountBalanceResult {
+    ResultType: i32,
+    ResultCode: i32,
+    ResultDesc: String,
+    OriginatorConversationID: String,
+    ConversationID: String,
+    #[serde(default)]
+    TransactionID: String,
+    #[serde(default)]
+    ResultParameters: ResultParameters,
+}
+
+impl From<AccountBalanceCallbackBody> for AccountBalanceCallback {
+    fn from(body: AccountBalanceCallbackBody) -> Self {
+        let body = body.Result;
+        let parameters = body.ResultParameters;
+
+        Self {
+            ResultType: body.ResultType,
+            ResultCode: body.ResultCode,
+            ResultDesc: body.ResultDesc,
+            OriginatorConversationID: body.OriginatorConversationID,
+            ConversationID: body.ConversationID,
+            TransactionID: body.TransactionID,
+            AccountBalance: parameters.get_string("AccountBalance")
+                .map(|balances| parse_balances(&balances))
+                .unwrap_or_default(),
+            BOCompletedTime: parameters.get_string("BOCompletedTime"),
+            ResultParameters: parameters,
+        }
+    }
+}
+
+/// Parses balances of the form `Working Account|KES|700000.00|700000.00|0.00|0.00&Float Account|KES|0.00|...`:
+/// accounts are separated by `&` and each starts with its name, currency and amount.
+/// Whitespace around fields and thousands separators in amounts are ignored.
+/// Malformed accounts are skipped.
+fn parse_balances(balances: &str) -> Vec<AccountBalanceEntry> {
+    balances.split('&')
+        .map(str::trim)
+        .filter_map(|account| {
+            let mut fields = account.split('|').map(str::trim);
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let currency = fields.next()?;
+            let amount = fields.next()?;
+            let amount_cents = parse_cents(amount)?;
+
+            Some(AccountBalanceEntry {
+                name: name.to_string(),
+                currency: currency.to_string(),
+                amount: amount_digits(amount)?.parse().ok()?,
+                amount_cents,
+                raw: account.to_string(),
+            })
+        })
+        .collect()
+}
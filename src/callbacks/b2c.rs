@@ -0,0 +1,139 @@
+use serde::{Deserialize,Deserializer};
+use serde_json::Value;
+use super::result::{ParameterValue,ResultParameters};
+use crate::ResultCode;
+use crate::payloads::code_string;
+
+#[derive(Debug, Deserialize)]
+#[serde(from = "B2cCallbackBody")]
+/// Result posted by Safaricom to the `ResultURL` of a b2c payment request
+/// See https://developer.safaricom.co.ke/docs#b2c-api for a
+/// detailed description of each field.
+///
+/// The known `ResultParameters` are flattened into fields, all of which are
+/// `None` when the transaction failed.
+pub struct B2cCallback {
+    pub ResultType: i32,
+    pub ResultCode: i32,
+    pub ResultDesc: String,
+    pub OriginatorConversationID: String,
+    pub ConversationID: String,
+    pub TransactionID: String,
+    pub TransactionReceipt: Option<String>,
+    pub TransactionAmount: Option<f64>,
+    pub B2CWorkingAccountAvailableFunds: Option<f64>,
+    pub B2CUtilityAccountAvailableFunds: Option<f64>,
+    pub B2CChargesPaidAccountAvailableFunds: Option<f64>,
+    pub TransactionCompletedDateTime: Option<String>,
+    pub ReceiverPartyPublicName: Option<String>,
+    pub B2CRecipientIsRegisteredCustomer: Option<bool>,
+    pub ResultParameters: ResultParameters,
+}
+
+impl B2cCallback {
+    /// The parsed `ResultCode`
+    pub fn result_code(&self) -> ResultCode {
+        ResultCode::from_code(&self.ResultCode.to_string())
+    }
+
+    /// Looks up the value of the result parameter named `key`
+    pub fn parameter(&self, key: &str) -> Option<&ParameterValue> {
+        self.ResultParameters.get(key)
+    }
+}
+
+#[derive(Deserialize)]
+struct B2cCallbackBody {
+    Result: B2cResult,
+}
+
+#[derive(Deserialize)]
+struct B2cResult {
+    ResultType: i32,
+    ResultCode: i32,
+    ResultDesc: String,
+    OriginatorConversationID: String,
+    ConversationID: String,
+    #[serde(default)]
+    TransactionID: String,
+    #[serde(default)]
+    ResultParameters: ResultParameters,
+}
+
+impl From<B2cCallbackBody> for B2cCallback {
+    fn from(body: B2cCallbackBody) -> Self {
+        let body = body.Result;
+        let parameters = body.ResultParameters;
+
+        Self {
+            ResultType: body.ResultType,
+            ResultCode: body.ResultCode,
+            ResultDesc: body.ResultDesc,
+            OriginatorConversationID: body.OriginatorConversationID,
+            ConversationID: body.ConversationID,
+            TransactionID: body.TransactionID,
+            TransactionReceipt: parameters.get_string("TransactionReceipt"),
+            TransactionAmount: parameters.get_f64("TransactionAmount"),
+            B2CWorkingAccountAvailableFunds: parameters.get_f64("B2CWorkingAccountAvailableFunds"),
+            B2CUtilityAccountAvailableFunds: parameters.get_f64("B2CUtilityAccountAvailableFunds"),
+            B2CChargesPaidAccountAvailableFunds: parameters.get_f64("B2CChargesPaidAccountAvailableFunds"),
+            TransactionCompletedDateTime: parameters.get_string("TransactionCompletedDateTime"),
+            ReceiverPartyPublicName: parameters.get_string("ReceiverPartyPublicName"),
+            B2CRecipientIsRegisteredCustomer: parameters.get_string("B2CRecipientIsRegisteredCustomer")
+                .map(|registered| registered == "Y"),
+            ResultParameters: parameters,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+/// Notification posted by Safaricom to the `QueueTimeOutURL` of a b2c payment request
+/// when the request expired in the queue before it was processed.
+///
+/// Unlike a `B2cCallback`, the body is not wrapped in a `Result` object and carries
+/// no transaction details, so the payment should be treated as not sent.
+pub struct B2cTimeoutCallback {
+    #[serde(default)]
+    pub OriginatorConversationID: Option<String>,
+    #[serde(default)]
+    pub ConversationID: Option<String>,
+    #[serde(default, deserialize_with = "optional_code")]
+    pub ResultCode: Option<String>,
+    #[serde(default)]
+    pub ResultDesc: Option<String>,
+}
+
+#[derive(Debug)]
+/// Either of the bodies Safaricom may post for a b2c payment request
+pub enum B2cCallbackKind {
+    /// The request was processed and its result posted to the `ResultURL`
+    Result(B2cCallback),
+    /// The request expired in the queue and was posted to the `QueueTimeOutURL`
+    Timeout(B2cTimeoutCallback),
+}
+
+impl B2cCallbackKind {
+    /// Parses a callback body, picking `Result` when the body has a top level
+    /// `Result` object and `Timeout` otherwise
+    ///
+    /// # Errors
+    /// Returns a `serde_json::Error` if the body is not JSON or does not match
+    /// the shape of the picked variant
+    pub fn from_json(body: &str) -> Result<Self, serde_json::Error> {
+        let value: Value = serde_json::from_str(body)?;
+
+        if value.get("Result").is_some() {
+            serde_json::from_value(value).map(B2cCallbackKind::Result)
+        } else {
+            serde_json::from_value(value).map(B2cCallbackKind::Timeout)
+        }
+    }
+}
+
+/// Reads a result code sent as a string, a number or `null`
+fn optional_code<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(code_string(&Value::deserialize(deserializer)?))
+}
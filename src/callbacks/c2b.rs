@@ -0,0 +1,116 @@
+use serde::{Deserialize,Serialize};
+
+#[derive(Deserialize)]
+/// Request sent by M-Pesa to the validation URL registered via `c2b_register`
+/// See more here: https://developer.safaricom.co.ke/docs?shell#c2b-api
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// request data
+pub struct C2bValidationRequest {
+    pub TransactionType: String,
+    pub TransID: String,
+    pub TransTime: String,
+    pub TransAmount: String,
+    pub BusinessShortCode: String,
+    #[serde(default)]
+    pub BillRefNumber: String,
+    #[serde(default)]
+    pub InvoiceNumber: String,
+    #[serde(default)]
+    pub OrgAccountBalance: String,
+    #[serde(default)]
+    pub ThirdPartyTransID: String,
+    pub MSISDN: String,
+    #[serde(default)]
+    pub FirstName: String,
+    #[serde(default)]
+    pub MiddleName: String,
+    #[serde(default)]
+    pub LastName: String,
+}
+
+masked_debug!(C2bValidationRequest {
+    TransactionType,
+    TransID,
+    TransTime,
+    TransAmount,
+    BusinessShortCode,
+    BillRefNumber: masked,
+    InvoiceNumber,
+    OrgAccountBalance,
+    ThirdPartyTransID,
+    MSISDN: masked,
+    FirstName,
+    MiddleName,
+    LastName,
+});
+
+#[derive(Deserialize)]
+/// Request sent by M-Pesa to the confirmation URL registered via `c2b_register`
+/// once a payment has completed
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// request data
+pub struct C2bConfirmationRequest {
+    pub TransactionType: String,
+    pub TransID: String,
+    pub TransTime: String,
+    pub TransAmount: String,
+    pub BusinessShortCode: String,
+    #[serde(default)]
+    pub BillRefNumber: String,
+    #[serde(default)]
+    pub InvoiceNumber: String,
+    #[serde(default)]
+    pub OrgAccountBalance: String,
+    #[serde(default)]
+    pub ThirdPartyTransID: String,
+    pub MSISDN: String,
+    #[serde(default)]
+    pub FirstName: String,
+    #[serde(default)]
+    pub MiddleName: String,
+    #[serde(default)]
+    pub LastName: String,
+}
+
+masked_debug!(C2bConfirmationRequest {
+    TransactionType,
+    TransID,
+    TransTime,
+    TransAmount,
+    BusinessShortCode,
+    BillRefNumber: masked,
+    InvoiceNumber,
+    OrgAccountBalance,
+    ThirdPartyTransID,
+    MSISDN: masked,
+    FirstName,
+    MiddleName,
+    LastName,
+});
+
+#[derive(Debug, Serialize)]
+/// Response the 3rd party returns to M-Pesa from the validation URL
+/// to accept or reject a payment
+pub struct C2bValidationResponse {
+    pub ResultCode: String,
+    pub ResultDesc: String,
+}
+
+impl C2bValidationResponse {
+    /// Accepts the payment
+    pub fn accepted() -> Self {
+        Self {
+            ResultCode: String::from("0"),
+            ResultDesc: String::from("Accepted"),
+        }
+    }
+
+    /// Rejects the payment with one of the documented error codes,
+    /// e.g. `C2B00012` for an invalid account number
+    pub fn rejected(result_code: &str) -> Self {
+        Self {
+            ResultCode: result_code.to_string(),
+            ResultDesc: String::from("Rejected"),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use serde_json::Value;
+use super::{AccountBalanceCallback,B2cCallback,B2cTimeoutCallback,C2bConfirmationRequest,TransactionStatusCallback};
+use crate::MpesaError;
+
+#[derive(Debug)]
+/// Any of the bodies Safaricom posts to a callback url, as told apart by `parse_callback`
+pub enum CallbackKind {
+    /// Result of a b2c payment request, posted to its `ResultURL`
+    B2c(B2cCallback),
+    /// A b2c payment request expired in the queue, posted to its `QueueTimeOutURL`
+    B2cTimeout(B2cTimeoutCallback),
+    /// Result of an account balance request, posted to its `ResultURL`
+    AccountBalance(AccountBalanceCallback),
+    /// Result of a transaction status query, posted to its `ResultURL`
+    TransactionStatus(TransactionStatusCallback),
+    /// A C2B payment, posted to the validation or confirmation url registered via
+    /// `c2b_register`. Both have the same shape: the url it was posted to tells them apart.
+    C2b(C2bConfirmationRequest),
+    /// A body of another shape, e.g. an STK push callback
+    Unknown(Value),
+}
+
+/// Parses the body of a request posted by Safaricom to a callback url, sniffing its shape
+/// to pick the matching callback, e.g. to serve every callback from a single route.
+///
+/// A failed result carries no result parameters, so a failed account balance or transaction
+/// status result cannot be told apart from a failed b2c result and is returned as `CallbackKind::B2c`;
+/// either way only its codes and description are set.
+///
+/// # Example
+/// ```
+/// use mpesa::callbacks::{parse_callback, CallbackKind};
+///
+/// let body = br#"{
+///     "TransactionType": "Pay Bill",
+///     "TransID": "RKTQDM7W6S",
+///     "TransTime": "20191122063845",
+///     "TransAmount": "10",
+///     "BusinessShortCode": "600638",
+///     "BillRefNumber": "invoice008",
+///     "MSISDN": "25470****149"
+/// }"#;
+///
+/// match parse_callback(body).unwrap() {
+///     CallbackKind::C2b(payment) => assert_eq!(payment.TransID, "RKTQDM7W6S"),
+///     callback => panic!("unexpected callback {:?}", callback),
+/// }
+/// ```
+///
+/// # Errors
+/// Returns a `MpesaError::ParseError` if the body is not JSON or does not match the
+/// shape of the picked callback
+pub fn parse_callback(body: &[u8]) -> Result<CallbackKind, MpesaError> {
+    let value: Value = serde_json::from_slice(body)?;
+
+    let callback = if let Some(result) = value.get("Result") {
+        if has_parameter(result, "AccountBalance") {
+            CallbackKind::AccountBalance(serde_json::from_value(value)?)
+        } else if has_parameter(result, "DebitPartyName") {
+            CallbackKind::TransactionStatus(serde_json::from_value(value)?)
+        } else {
+            CallbackKind::B2c(serde_json::from_value(value)?)
+        }
+    } else if value.get("TransID").is_some() {
+        CallbackKind::C2b(serde_json::from_value(value)?)
+    } else if value.get("OriginatorConversationID").is_some() {
+        CallbackKind::B2cTimeout(serde_json::from_value(value)?)
+    } else {
+        CallbackKind::Unknown(value)
+    };
+
+    Ok(callback)
+}
+
+/// Whether the `ResultParameters` of a result hold a parameter named `key`,
+/// sent either as a list of parameters or as a single one
+fn has_parameter(result: &Value, key: &str) -> bool {
+    match result.pointer("/ResultParameters/ResultParameter") {
+        Some(Value::Array(parameters)) => parameters.iter().any(|parameter| parameter["Key"] == key),
+        Some(parameter) => parameter["Key"] == key,
+        None => false,
+    }
+}
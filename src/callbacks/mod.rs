@@ -0,0 +1,16 @@
+#![allow(non_snake_case)]
+mod result;
+mod b2c;
+mod account_balance;
+mod c2b;
+mod kind;
+mod trust;
+mod transaction_status;
+
+pub use result::{ParameterValue,ResultParameter,ResultParameters};
+pub use b2c::{B2cCallback,B2cCallbackKind,B2cTimeoutCallback};
+pub use account_balance::{AccountBalanceCallback,AccountBalanceEntry};
+pub use c2b::{C2bValidationRequest,C2bConfirmationRequest,C2bValidationResponse};
+pub use kind::{parse_callback,CallbackKind};
+pub use trust::{secret_matches,CallbackAllowList,ParseNetworkError,SAFARICOM_CALLBACK_IPS};
+pub use transaction_status::TransactionStatusCallback;
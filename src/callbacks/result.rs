@@ -0,0 +1,147 @@
+use serde::{Deserialize,Deserializer};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+/// A single `Key`/`Value` entry of a callback's `ResultParameters`
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// callback data
+pub struct ResultParameter {
+    pub Key: String,
+    #[serde(default)]
+    pub Value: Value,
+}
+
+/// The value of a result parameter, sent by Safaricom as either a string or a number
+pub type ParameterValue = Value;
+
+#[derive(Debug, Default, Deserialize)]
+/// The `ResultParameters` object of a callback result: a list of `Key`/`Value` pairs
+/// shared by the results of every asynchronous M-Pesa API.
+///
+/// Values may be sent as strings or numbers, so the typed accessors accept both.
+///
+/// # Example
+/// ```
+/// use mpesa::callbacks::ResultParameters;
+///
+/// let parameters: ResultParameters = serde_json::from_str(r#"{
+///     "ResultParameter": [
+///         { "Key": "TransactionReceipt", "Value": "NLJ41HAY6Q" },
+///         { "Key": "TransactionAmount", "Value": "10" }
+///     ]
+/// }"#).unwrap();
+///
+/// assert_eq!(parameters.get_str("TransactionReceipt"), Some("NLJ41HAY6Q"));
+/// assert_eq!(parameters.get_i64("TransactionAmount"), Some(10));
+/// ```
+pub struct ResultParameters {
+    #[serde(deserialize_with = "one_or_many")]
+    pub ResultParameter: Vec<ResultParameter>,
+}
+
+impl ResultParameters {
+    /// Looks up the value of the parameter named `key`
+    pub fn get(&self, key: &str) -> Option<&ParameterValue> {
+        self.ResultParameter.iter()
+            .find(|parameter| parameter.Key == key)
+            .map(|parameter| &parameter.Value)
+    }
+
+    /// Looks up a parameter named `key` sent as a string
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    /// Looks up a parameter named `key` sent as a string or a number, as a string
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.get(key).and_then(|value| match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+    }
+
+    /// Looks up an integer parameter named `key`, which may be sent as a number or a string
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(|value| {
+            value.as_i64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+        })
+    }
+
+    /// Looks up a numeric parameter named `key`, which may be sent as a number or a string.
+    /// Amounts sent as strings may carry a currency code and thousands separators,
+    /// e.g. `"KES 1,000.00"`.
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(|value| {
+            value.as_f64().or_else(|| value.as_str().and_then(amount_digits).and_then(|s| s.parse().ok()))
+        })
+    }
+
+    /// Iterates over the parameters in the order they were sent
+    pub fn iter(&self) -> impl Iterator<Item = &ResultParameter> {
+        self.ResultParameter.iter()
+    }
+
+    /// The number of parameters
+    pub fn len(&self) -> usize {
+        self.ResultParameter.len()
+    }
+
+    /// Whether there are no parameters, as in the result of a failed request
+    pub fn is_empty(&self) -> bool {
+        self.ResultParameter.is_empty()
+    }
+}
+
+/// Safaricom sends a lone object instead of an array when there is a single parameter
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<ResultParameter>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ResultParameter),
+        Many(Vec<ResultParameter>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(parameter) => vec![parameter],
+        OneOrMany::Many(parameters) => parameters,
+    })
+}
+
+/// Strips the currency code, whitespace and thousands separators around an amount,
+/// e.g. `" KES 1,000.00 "` to `"1000.00"`
+pub(crate) fn amount_digits(amount: &str) -> Option<String> {
+    let digits: String = amount
+        .trim_matches(|c: char| c.is_ascii_alphabetic() || c.is_whitespace())
+        .chars()
+        .filter(|&c| c != ',')
+        .collect();
+
+    if digits.is_empty() { None } else { Some(digits) }
+}
+
+/// Parses an amount into cents without rounding through a float, e.g. `"-1540.5"` to `-154050`.
+/// Amounts with more than two decimals are rejected.
+pub(crate) fn parse_cents(amount: &str) -> Option<i64> {
+    let digits = amount_digits(amount)?;
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, digits.as_str()),
+    };
+
+    let mut parts = digits.splitn(2, '.');
+    let units = parts.next()?;
+    let fraction = parts.next().unwrap_or("");
+    if units.is_empty() || fraction.len() > 2
+        || !units.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let cents = units.parse::<i64>().ok()?.checked_mul(100)?
+        .checked_add(format!("{:0<2}", fraction).parse::<i64>().ok()?)?;
+
+    Some(if negative { -cents } else { cents })
+}
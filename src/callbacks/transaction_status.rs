@@ -0,0 +1,108 @@
+use serde::Deserialize;
+use super::result::{ParameterValue,ResultParameters};
+use crate::ResultCode;
+
+#[derive(Debug, Deserialize)]
+#[serde(from = "TransactionStatusCallbackBody")]
+/// Result posted by Safaricom to the `ResultURL` of a transaction status query
+/// See https://developer.safaricom.co.ke/APIs/TransactionStatus for a
+/// detailed description of each field.
+///
+/// The known `ResultParameters` are flattened into fields, all of which are
+/// `None` when the query failed.
+pub struct TransactionStatusCallback {
+    pub ResultType: i32,
+    pub ResultCode: i32,
+    pub ResultDesc: String,
+    pub OriginatorConversationID: String,
+    pub ConversationID: String,
+    pub TransactionID: String,
+    pub ReceiptNo: Option<String>,
+    pub Amount: Option<f64>,
+    /// e.g. `"Completed"`
+    pub TransactionStatus: Option<String>,
+    pub ReasonType: Option<String>,
+    /// The party the funds were sent from, e.g. `"600310 - Safaricom333"`
+    pub DebitPartyName: Option<String>,
+    /// The party the funds were sent to, e.g. `"254708374149 - John Doe"`
+    pub CreditPartyName: Option<String>,
+    pub InitiatedTime: Option<String>,
+    pub FinalisedTime: Option<String>,
+    pub ResultParameters: ResultParameters,
+}
+
+impl TransactionStatusCallback {
+    /// The parsed `ResultCode`
+    pub fn result_code(&self) -> ResultCode {
+        ResultCode::from_code(&self.ResultCode.to_string())
+    }
+
+    /// Looks up the value of the result parameter named `key`
+    pub fn parameter(&self, key: &str) -> Option<&ParameterValue> {
+        self.ResultParameters.get(key)
+    }
+
+    /// The name the paying party is registered under, e.g. `"Safaricom333"` for a
+    /// `DebitPartyName` of `"600310 - Safaricom333"`
+    pub fn debit_party_registered_name(&self) -> Option<&str> {
+        self.DebitPartyName.as_deref().map(registered_name)
+    }
+
+    /// The name the receiving party is registered under, e.g. `"ACME LTD"` for a
+    /// `CreditPartyName` of `"600000 - ACME LTD"`. Confirms who a short code, till
+    /// or phone number belongs to before paying it again.
+    pub fn credit_party_registered_name(&self) -> Option<&str> {
+        self.CreditPartyName.as_deref().map(registered_name)
+    }
+}
+
+/// Strips the short code or phone number a party name is prefixed with, if any
+fn registered_name(party: &str) -> &str {
+    match party.find(" - ") {
+        Some(index) => party[index + 3..].trim(),
+        None => party.trim(),
+    }
+}
+
+#[derive(Deserialize)]
+struct TransactionStatusCallbackBody {
+    Result: TransactionStatusResult,
+}
+
+#[derive(Deserialize)]
+struct TransactionStatusResult {
+    ResultType: i32,
+    ResultCode: i32,
+    ResultDesc: String,
+    OriginatorConversationID: String,
+    ConversationID: String,
+    #[serde(default)]
+    TransactionID: String,
+    #[serde(default)]
+    ResultParameters: ResultParameters,
+}
+
+impl From<TransactionStatusCallbackBody> for TransactionStatusCallback {
+    fn from(body: TransactionStatusCallbackBody) -> Self {
+        let body = body.Result;
+        let parameters = body.ResultParameters;
+
+        Self {
+            ResultType: body.ResultType,
+            ResultCode: body.ResultCode,
+            ResultDesc: body.ResultDesc,
+            OriginatorConversationID: body.OriginatorConversationID,
+            ConversationID: body.ConversationID,
+            TransactionID: body.TransactionID,
+            ReceiptNo: parameters.get_string("ReceiptNo"),
+            Amount: parameters.get_f64("Amount"),
+            TransactionStatus: parameters.get_string("TransactionStatus"),
+            ReasonType: parameters.get_string("ReasonType"),
+            DebitPartyName: parameters.get_string("DebitPartyName"),
+            CreditPartyName: parameters.get_string("CreditPartyName"),
+            InitiatedTime: parameters.get_string("InitiatedTime"),
+            FinalisedTime: parameters.get_string("FinalisedTime"),
+            ResultParameters: parameters,
+        }
+    }
+}
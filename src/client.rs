@@ -1,52 +1,465 @@
-use std::collections::HashMap;
-use reqwest::blocking::{Client, Response};
-use serde_json::json;
+use std::fmt::{Debug,Formatter,Result as FmtResult};
+use std::sync::{mpsc,Arc,Mutex};
+use std::sync::atomic::{AtomicUsize,Ordering};
+use std::thread;
+use std::time::{Duration,Instant};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap,ACCEPT,CONTENT_TYPE};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use chrono::NaiveDate;
 use mpesa_derive::MpesaSecurity;
 
 use super::environment::Environment;
 use crate::{CommandId, IdentifierTypes};
 use super::payloads::{B2bResponse,B2cResponse,AuthResponse,C2bRegisterResponse,C2bSimulateResponse};
-use crate::payloads::{B2bPayload,B2cPayload,C2bRegisterPayload,C2bSimulatePayload};
-use crate::payloads::ResponseType;
+use crate::payloads::{B2bPayload,B2cBuilder,C2bRegisterPayload,C2bSimulatePayload};
+use crate::payloads::{assign_originator_conversation_ids,B2cBatchResult,B2cRequest};
+use crate::payloads::{C2bRegistration,ResponseType};
 use crate::payloads::{AccountBalanceResponse,AccountBalancePayload};
+use crate::payloads::{ExpressRequestPayload,ExpressResponse,ExpressQueryPayload,ExpressQueryResponse};
+use crate::payloads::{B2bExpressCheckoutPayload,B2bExpressCheckoutResponse};
+use crate::payloads::{DynamicQrPayload,QrResponse,QrTransactionType};
+use crate::payloads::{TaxRemittancePayload,TaxRemittanceResponse};
+use crate::payloads::{TransactionStatusPayload,TransactionStatusResponse};
+use crate::payloads::{ReceiptReversal,ReversalPayload,ReversalResponse};
+use crate::callbacks::TransactionStatusCallback;
+use crate::payloads::{Frequency,StandingOrderPayload,StandingOrderResponse,StandingOrderTransactionType};
+use crate::payloads::{BillManager,BillManagerOptInPayload,BillManagerOptInResponse,BillManagerResponse};
+use crate::payloads::{InvoiceItem,InvoicePayload,ReconciliationPayload};
+use crate::payloads::{code_string,is_success_code,MpesaErrorResponse};
+use crate::endpoints::Endpoint;
 use crate::mpesa_security::MpesaSecurity;
+use crate::{Amount,MpesaBuilder,MpesaError,PhoneNumber,ShortCode};
+use crate::builder::{DEFAULT_TIMEOUT,DEFAULT_USER_AGENT};
+use crate::retry::{self,RetryPolicy};
+use crate::validation;
+use crate::ids;
+use crate::security;
+use crate::clock::{Clock,SystemClock};
+use crate::AccessToken;
+use crate::recording::{dry_run_response,recorded_response,RecordedRequest};
+use crate::SignedRequest;
+use crate::limits::Limits;
+use crate::metrics::{MetricsHook,RequestMetrics};
+#[cfg(feature = "tracing")]
+use crate::logging::redact_body;
+use crate::masked::Masked;
 
 /// Mpesa client that will facilitate communication with the Safaricom API
-#[derive(Debug, MpesaSecurity)]
+///
+/// Cloning is cheap: clones share the underlying http connection pool, the cached
+/// access token and security credentials, so a single client can be stored in web
+/// framework state and handed to every request handler.
+#[derive(Clone, MpesaSecurity)]
 pub struct Mpesa {
     client_key: String,
     client_secret: String,
     environment: Environment,
-    initiator_password: String,
+    initiator_password: Option<String>,
+    /// A `SecurityCredential` computed outside the crate, see `MpesaBuilder::security_credential`
+    security_credential: Option<String>,
+    certificate: Arc<Mutex<Option<Vec<u8>>>>,
+    certificate_url: Option<String>,
+    /// Overrides the environment's base url for access tokens, see `MpesaBuilder::auth_base_url`
+    auth_base_url: Option<String>,
+    http_client: Client,
+    retry_policy: RetryPolicy,
+    access_token: Arc<Mutex<Option<AccessToken>>>,
+    security_credentials: Arc<Mutex<Option<String>>>,
+    recorded_requests: Option<Arc<Mutex<Vec<RecordedRequest>>>>,
+    dry_run: bool,
+    limits: Limits,
+    metrics: Option<MetricsHook>,
+    /// Tells the time STK push requests are signed at, see `MpesaBuilder::clock`
+    clock: Arc<dyn Clock>,
+}
+
+/// Only shows the environment and the client key masked with `Masked`, so that logging a client
+/// never leaks its credentials
+impl Debug for Mpesa {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Mpesa")
+            .field("client_key", &Masked(&self.client_key))
+            .field("environment", &self.environment)
+            .finish()
+    }
 }
 
 impl Mpesa {
     /// Constructs a new `Mpesa` instance.
+    /// Requests time out after `DEFAULT_TIMEOUT`; use `Mpesa::builder` to configure timeouts.
     pub fn new(client_key: String, client_secret: String, environment: Environment, initiator_password: String) -> Self {
+        let http_client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .expect("error building http client");
+
+        Self::with_client(client_key, client_secret, environment, Some(initiator_password), None, http_client, RetryPolicy::default())
+    }
+
+    /// Constructs a new `Mpesa` instance that sends requests through `http_client`,
+    /// retrying transient failures according to `retry_policy`.
+    /// Security credentials are generated with `certificate` if set, or the environment's
+    /// default certificate otherwise.
+    pub(crate) fn with_client(
+        client_key: String,
+        client_secret: String,
+        environment: Environment,
+        initiator_password: Option<String>,
+        certificate: Option<Vec<u8>>,
+        http_client: Client,
+        retry_policy: RetryPolicy,
+    ) -> Self {
         Self {
             client_key,
             client_secret,
             environment,
             initiator_password,
+            security_credential: None,
+            certificate: Arc::new(Mutex::new(certificate)),
+            certificate_url: None,
+            auth_base_url: None,
+            http_client,
+            retry_policy,
+            access_token: Arc::new(Mutex::new(None)),
+            security_credentials: Arc::new(Mutex::new(None)),
+            recorded_requests: None,
+            dry_run: false,
+            limits: Limits::default(),
+            metrics: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Checks amounts against `limits` instead of the defaults, see `MpesaBuilder::limits`.
+    pub(crate) fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Downloads the security certificate from `certificate_url`, see `MpesaBuilder::security_certificate_url`.
+    pub(crate) fn with_certificate_url(mut self, certificate_url: Option<String>) -> Self {
+        self.certificate_url = certificate_url;
+        self
+    }
+
+    /// Requests access tokens from `auth_base_url`, see `MpesaBuilder::auth_base_url`.
+    pub(crate) fn with_auth_base_url(mut self, auth_base_url: Option<String>) -> Self {
+        self.auth_base_url = auth_base_url;
+        self
+    }
+
+    /// Sends `security_credential` instead of encrypting the initiator password, see
+    /// `MpesaBuilder::security_credential`.
+    pub(crate) fn with_security_credential(mut self, security_credential: Option<String>) -> Self {
+        self.security_credential = security_credential;
+        self
+    }
+
+    /// Takes the current time from `clock`, if set, see `MpesaBuilder::clock`.
+    pub(crate) fn with_clock(mut self, clock: Option<Arc<dyn Clock>>) -> Self {
+        if let Some(clock) = clock {
+            self.clock = clock;
+        }
+        self
+    }
+
+    /// Reports the metrics of every request to `metrics`, see `MpesaBuilder::on_request_complete`.
+    pub(crate) fn with_metrics(mut self, metrics: Option<MetricsHook>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Records requests instead of sending them, see `MpesaBuilder::record_requests`.
+    pub(crate) fn recording(mut self) -> Self {
+        self.recorded_requests = Some(Arc::new(Mutex::new(Vec::new())));
+        self
+    }
+
+    /// Logs requests instead of sending them, see `MpesaBuilder::dry_run`.
+    pub(crate) fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Whether API calls reach the network, i.e. the client neither records requests nor
+    /// is a dry run
+    fn sends_requests(&self) -> bool {
+        self.recorded_requests.is_none() && !self.dry_run
+    }
+
+    /// The last request recorded by a client built with `MpesaBuilder::record_requests`,
+    /// or `None` if nothing was recorded.
+    pub fn last_request(&self) -> Option<RecordedRequest> {
+        self.recorded_requests()?.pop()
+    }
+
+    /// Every request recorded by a client built with `MpesaBuilder::record_requests`,
+    /// oldest first, or `None` if the client does not record requests.
+    pub fn recorded_requests(&self) -> Option<Vec<RecordedRequest>> {
+        self.recorded_requests.as_ref().map(|requests| requests.lock().unwrap().clone())
+    }
+
+    /// The environment the client sends requests to
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// The base url access tokens are requested from: the url set with
+    /// `MpesaBuilder::auth_base_url`, or the environment's base url
+    fn auth_base_url(&self) -> &str {
+        self.auth_base_url.as_deref().unwrap_or_else(|| self.environment.base_url())
+    }
+
+    /// Returns a client with the same credentials and http configuration that sends
+    /// requests to `environment`, e.g. to run a sandbox and a production integration side by side.
+    ///
+    /// Access tokens and security credentials are only valid for the environment they
+    /// were issued for, so the new client starts with empty caches that are not shared
+    /// with `self`. A certificate set with `MpesaBuilder::security_certificate` or its download
+    /// url are not carried over either; the new client uses `environment`'s certificate.
+    /// Neither is the url set with `MpesaBuilder::auth_base_url`; access tokens are requested
+    /// from `environment`. A credential set with `MpesaBuilder::security_credential` was encrypted
+    /// for the previous environment and is dropped, so the new client needs an initiator password
+    /// to generate security credentials.
+    pub fn with_environment(&self, environment: Environment) -> Self {
+        Self {
+            environment,
+            certificate: Arc::new(Mutex::new(None)),
+            certificate_url: None,
+            auth_base_url: None,
+            security_credential: None,
+            access_token: Arc::new(Mutex::new(None)),
+            security_credentials: Arc::new(Mutex::new(None)),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a `MpesaBuilder` to configure a new `Mpesa` instance.
+    pub fn builder() -> MpesaBuilder {
+        MpesaBuilder::new()
+    }
+
+    /// Constructs a new `Mpesa` instance from the `CLIENT_KEY`, `CLIENT_SECRET`,
+    /// `INIT_PASSWORD` and `MPESA_ENVIRONMENT` environment variables.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError` if a variable is not set or is empty
+    pub fn from_env() -> Result<Self, MpesaError> {
+        MpesaBuilder::from_env()?.build()
+    }
+
+    /// Returns an OAuth access token, along with its expiry.
+    ///
+    /// The token is cached and reused by every request until it is about to expire,
+    /// after which a new one is requested from Safaricom oauth. Only one token is
+    /// requested at a time: concurrent callers, including clones, wait for it.
+    /// The OAuth access token expires after an hour.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::Authentication` if the client key or secret is rejected
+    /// or a `MpesaError::NetworkError` if a new token could not be obtained
+    pub fn auth(&self) -> Result<AccessToken, MpesaError> {
+        // The lock is held while refreshing, so that threads sharing an expired token
+        // wait for a single refresh instead of each requesting a new token
+        let mut cached = self.access_token.lock().unwrap();
+        if let Some(access_token) = cached.as_ref() {
+            if !access_token.is_expired() {
+                return Ok(access_token.clone());
+            }
+        }
+
+        let access_token = self.generate_access_token()?;
+        *cached = Some(access_token.clone());
+
+        Ok(access_token)
+    }
+
+    /// Seeds the token cache with a token obtained elsewhere, e.g. shared
+    /// between several instances. It is used until `expires_in` has elapsed.
+    pub fn set_access_token(&self, token: String, expires_in: Duration) {
+        *self.access_token.lock().unwrap() = Some(AccessToken::new(token, expires_in));
+    }
+
+    /// Checks that the client is usable before it processes payments, e.g. as a readiness
+    /// probe: requests an access token, populating the token cache, and generates the
+    /// security credentials from the initiator password and certificate.
+    ///
+    /// The initiator password itself is only checked by M-Pesa when it processes a request,
+    /// whose result is posted to a callback url, so a wrong password is not detected here.
+    /// Clients built with `MpesaBuilder::record_requests` do not request a token.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::Authentication` if the client key or secret is rejected,
+    /// a `MpesaError::NetworkError` if a token could not be requested,
+    /// or a `MpesaError::EncryptionError` if the security credentials could not be generated
+    pub fn validate_credentials(&self) -> Result<(), MpesaError> {
+        if self.sends_requests() {
+            self.auth()?;
+        }
+        self.gen_security_credentials()?;
+
+        Ok(())
+    }
+
+    /// Checks that Safaricom can be reached and accepts the client key and secret, e.g. as a
+    /// Kubernetes liveness or readiness probe.
+    ///
+    /// Cheap enough to call on every probe: the cached access token is reused until it is
+    /// about to expire, so Safaricom is only contacted when a new token is due. Clients that
+    /// do not send requests, see `MpesaBuilder::dry_run`, are always healthy.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::Authentication` if the client key or secret is rejected, or an
+    /// error for which `MpesaError::is_unavailable` is true, e.g. a `MpesaError::NetworkError`,
+    /// if Safaricom could not be reached or is not serving requests
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "health_check", skip_all))]
+    pub fn health_check(&self) -> Result<(), MpesaError> {
+        if self.sends_requests() {
+            self.auth()?;
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the security certificate from the url set with
+    /// `MpesaBuilder::security_certificate_url` and generates security credentials with it
+    /// from then on, e.g. at startup, so that credentials keep working when Safaricom
+    /// rotates its certificates.
+    ///
+    /// If the download fails or does not return a valid certificate, the client keeps
+    /// using its current certificate: the one set with `MpesaBuilder::security_certificate`,
+    /// if any, or the environment's bundled certificate.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if no certificate url was set, a
+    /// `MpesaError::NetworkError` or `MpesaError::UnexpectedResponse` if the certificate
+    /// could not be downloaded, or a `MpesaError::EncryptionError` if it is not a valid
+    /// PEM or DER encoded certificate
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "refresh_security_certificate", skip_all))]
+    pub fn refresh_security_certificate(&self) -> Result<(), MpesaError> {
+        let url = self.certificate_url.as_ref().ok_or(MpesaError::MissingField("certificate_url"))?;
+
+        let response = self.send(url, || self.http_client.get(url), true)?;
+        let status = response.status();
+        let certificate = response.bytes()?.to_vec();
+        if !status.is_success() {
+            return Err(MpesaError::UnexpectedResponse {
+                status: status.as_u16(),
+                body: String::from_utf8_lossy(&certificate).into_owned(),
+            });
         }
+
+        X509::from_pem(&certificate)
+            .or_else(|_| X509::from_der(&certificate))
+            .map_err(|e| MpesaError::EncryptionError(format!("invalid certificate: {}", e)))?;
+
+        *self.certificate.lock().unwrap() = Some(certificate);
+        // credentials encrypted with the previous certificate are stale
+        *self.security_credentials.lock().unwrap() = None;
+        debug!("security certificate refreshed");
+
+        Ok(())
     }
 
     /// Generates an access token
     /// Sends `GET` request to Safaricom oauth to acquire token for token authentication
-    /// The OAuth access token expires after an hour, after which, you will need to generate another access token
-    fn auth(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let url = format!("{}/oauth/v1/generate?grant_type=client_credentials", self.environment.base_url());
+    fn generate_access_token(&self) -> Result<AccessToken, MpesaError> {
+        let url = Endpoint::Auth.url(self.auth_base_url());
+
+        let resp: AuthResponse = parse_response(self.send(&url, || {
+            self.http_client.get(&url)
+                .header(ACCEPT, JSON_MEDIA_TYPE)
+                .query(&[("grant_type", GRANT_TYPE)])
+                .basic_auth(&self.client_key, Some(&self.client_secret))
+        }, true)?).map_err(authentication_error)?;
 
-        let resp: AuthResponse = Client::new().get(&url)
-            .basic_auth(&self.client_key, Some(&self.client_secret))
-            .send()?
-            .json()?;
+        debug!(expires_in = resp.expires_in, "access token refreshed");
 
-        Ok(resp.access_token)
+        Ok(AccessToken::new(resp.access_token, Duration::from_secs(resp.expires_in)))
+    }
+
+    /// Sends the request to `url` built by `request`, retrying transient failures according to
+    /// the client's `RetryPolicy`. `idempotent` requests are also retried on any network error.
+    fn send<F>(&self, url: &str, request: F, idempotent: bool) -> Result<Response, MpesaError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let started = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            let result = request().send();
+            let outcome = result.as_ref().map(Response::status);
+            let retry_after = result.as_ref().ok().and_then(|response| retry::retry_after(response.headers()));
+
+            if !self.retry_policy.should_retry(attempt, outcome, retry_after, idempotent) {
+                if let Some(metrics) = &self.metrics {
+                    let status = outcome.ok();
+                    metrics.report(&RequestMetrics {
+                        endpoint: url
+                            .trim_start_matches(self.environment.base_url())
+                            .trim_start_matches(self.auth_base_url())
+                            .to_string(),
+                        duration: started.elapsed(),
+                        status: status.map(|status| status.as_u16()),
+                        success: matches!(status, Some(status) if status.is_success()),
+                        retries: attempt,
+                    });
+                }
+                let response = result?;
+                if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    warn!(retry_after = ?retry_after, "rate limited");
+                    return Err(MpesaError::RateLimited { retry_after });
+                }
+                return Ok(response);
+            }
+
+            let backoff = retry_after.unwrap_or_else(|| self.retry_policy.backoff(attempt));
+            warn!(attempt = attempt + 1, backoff_ms = backoff.as_millis() as u64, "retrying request");
+            thread::sleep(backoff);
+            attempt += 1;
+        }
+    }
+
+    /// Sends `payload` to `url` with a fresh access token and deserializes the response
+    /// Clients built with `MpesaBuilder::record_requests` record the request instead
+    fn post<P, R>(&self, url: &str, payload: &P) -> Result<R, MpesaError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        if let Some(recorded_requests) = &self.recorded_requests {
+            recorded_requests.lock().unwrap().push(RecordedRequest {
+                url: url.to_string(),
+                body: serde_json::to_value(payload)?,
+            });
+            return parse_value(recorded_response());
+        }
+
+        if self.dry_run {
+            let body = serde_json::to_value(payload)?;
+            debug!(url = %url, body = %redact_body(&body), "dry run: request not sent");
+            return parse_value(dry_run_response(&body));
+        }
+
+        let access_token = self.auth()?;
+        let response = self.send(url, || {
+            self.http_client.post(url)
+                .header(ACCEPT, JSON_MEDIA_TYPE)
+                .bearer_auth(access_token.token())
+                .json(payload)
+        }, false)?;
+
+        parse_response(response)
     }
 
     /// # B2C API
-    /// Sends b2c payment request.
+    /// Returns a `B2cBuilder` to configure a b2c payment request.
     ///
     /// This API enables Business to Customer (B2C) transactions between a company and
     /// customers who are the end-users of its products or services. Use of this API requires a
@@ -64,69 +477,149 @@ impl Mpesa {
     ///    std::env::var("INIT_PASSWORD").unwrap(),
     /// );
     ///
-    /// let response = client.b2c(
-    ///         "testapi496",
-    ///         mpesa::CommandId::BusinessPayment,
-    ///         1000,
-    ///         "600496",
-    ///         "254708374149",
-    ///         "gg",
-    ///         "https://muriuki.dev/api/a",
-    ///         "https://muriuki.dev/api/b",
-    ///         "Test",
-    ///     ).unwrap();
+    /// let response = client.b2c()
+    ///     .initiator_name("testapi496")
+    ///     .command_id(mpesa::CommandId::BusinessPayment)
+    ///     .amount(mpesa::Amount::try_new(1000).unwrap())
+    ///     .party_a(&mpesa::ShortCode::new("600496").unwrap())
+    ///     .party_b(&mpesa::PhoneNumber::new("254708374149").unwrap())
+    ///     .remarks("gg")
+    ///     .queue_timeout_url("https://muriuki.dev/api/a")
+    ///     .result_url("https://muriuki.dev/api/b")
+    ///     .occasion("Test")
+    ///     .send()
+    ///     .unwrap();
     /// ```
     ///
-    /// # Errors
-    /// TODO
-    pub fn b2c(
-        &self,
-        initiator_name: &str,
-        command_id: CommandId,
-        amount: u32,
-        party_a: &str,
-        party_b: &str,
-        remarks: &str,
-        queue_timeout_url: &str,
-        result_url: &str,
-        occasion: &str
-    ) -> Result<B2cResponse, Box<dyn std::error::Error>> {
-        let url = format!("{}/mpesa/b2c/v1/paymentrequest", self.environment.base_url());
-        let credentials = self.gen_security_credentials()?;
+    pub fn b2c(&self) -> B2cBuilder<'_, Mpesa> {
+        B2cBuilder::new(self)
+    }
 
-        let payload = B2cPayload {
-            initiator_name,
-            security_credentials: &credentials,
-            command_id,
-            amount,
-            party_a,
-            party_b,
-            remarks,
-            queue_timeout_url,
-            result_url,
-            occasion,
-        };
+    /// # B2C API
+    /// Pays a salary to an employee. Salary payments can be sent to unregistered customers too.
+    ///
+    /// Returns a `B2cBuilder` with the command id already set to `CommandId::SalaryPayment`.
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let response = client.pay_salary()
+    ///     .initiator_name("testapi496")
+    ///     .amount(mpesa::Amount::try_new(1000).unwrap())
+    ///     .party_a(&mpesa::ShortCode::new("600496").unwrap())
+    ///     .party_b(&mpesa::PhoneNumber::new("254708374149").unwrap())
+    ///     .remarks("gg")
+    ///     .queue_timeout_url("https://muriuki.dev/api/a")
+    ///     .result_url("https://muriuki.dev/api/b")
+    ///     .occasion("Test")
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn pay_salary(&self) -> B2cBuilder<'_, Mpesa> {
+        self.b2c().command_id(CommandId::SalaryPayment)
+    }
 
-        let data = json!({
-            "InitiatorName": payload.initiator_name,
-            "SecurityCredential": payload.security_credentials,
-            "CommandID": payload.command_id.to_string(),
-            "Amount": payload.amount,
-            "PartyA": payload.party_a,
-            "PartyB": payload.party_b,
-            "Remarks": payload.remarks,
-            "QueueTimeOutURL": payload.queue_timeout_url,
-            "ResultURL": payload.result_url,
-            "Occasion": payload.occasion,
-        });
+    /// # B2C API
+    /// Pays a promotion or bonus, e.g. a lottery winning or a loyalty reward, to a customer.
+    ///
+    /// Returns a `B2cBuilder` with the command id already set to `CommandId::PromotionPayment`.
+    pub fn pay_promotion(&self) -> B2cBuilder<'_, Mpesa> {
+        self.b2c().command_id(CommandId::PromotionPayment)
+    }
+
+    /// # B2C API
+    /// Makes a normal business to customer payment, e.g. a refund or a supplier payment.
+    ///
+    /// Returns a `B2cBuilder` with the command id already set to `CommandId::BusinessPayment`.
+    pub fn business_payment(&self) -> B2cBuilder<'_, Mpesa> {
+        self.b2c().command_id(CommandId::BusinessPayment)
+    }
+
+    /// # B2C API
+    /// Sends a batch of b2c payments, e.g. a payroll, with at most `concurrency` requests
+    /// in flight at once.
+    ///
+    /// Every payment without an originator conversation id is assigned one before the batch
+    /// is sent, so that a failed payment can be retried with the same id without paying
+    /// twice. A failed payment does not stop the others; the returned `B2cBatchResult` holds
+    /// the response or error of every payment.
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let payouts = vec!["254708374149", "254708374150"].into_iter().map(|phone_number| mpesa::payloads::B2cRequest {
+    ///     originator_conversation_id: None,
+    ///     initiator_name: String::from("testapi496"),
+    ///     command_id: mpesa::CommandId::SalaryPayment,
+    ///     amount: mpesa::Amount::try_new(1000).unwrap(),
+    ///     party_a: mpesa::ShortCode::new("600496").unwrap(),
+    ///     party_b: mpesa::PhoneNumber::new(phone_number).unwrap(),
+    ///     remarks: None,
+    ///     queue_timeout_url: String::from("https://muriuki.dev/api/a"),
+    ///     result_url: String::from("https://muriuki.dev/api/b"),
+    ///     occasion: None,
+    /// });
+    ///
+    /// let result = client.b2c_batch(payouts, 4);
+    /// for (index, error) in &result.failures {
+    ///     println!("{} failed: {}", result.originator_conversation_ids[*index], error);
+    /// }
+    /// ```
+    pub fn b2c_batch<I>(&self, payouts: I, concurrency: usize) -> B2cBatchResult
+    where
+        I: IntoIterator<Item = B2cRequest>,
+    {
+        let payouts = Arc::new(assign_originator_conversation_ids(payouts));
+        let originator_conversation_ids = payouts.iter()
+            .filter_map(|payout| payout.originator_conversation_id.clone())
+            .collect();
+
+        // Request the token once so that every worker reuses it; a failure is reported
+        // by every payment instead
+        if self.sends_requests() {
+            let _ = self.auth();
+        }
+
+        let next = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+
+        for _ in 0..concurrency.max(1).min(payouts.len()) {
+            let client = self.clone();
+            let payouts = payouts.clone();
+            let next = next.clone();
+            let sender = sender.clone();
 
-        let response: B2cResponse = Client::new().post(&url)
-            .bearer_auth(self.auth()?)
-            .json(&data)
-            .send()?
-            .json()?;
+            thread::spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let payout = match payouts.get(index) {
+                    Some(payout) => payout,
+                    None => break,
+                };
 
-        Ok(response)
+                let result = payout.builder(&client).send();
+                let _ = sender.send((index, result));
+            });
+        }
+        drop(sender);
+
+        // Workers finish out of order; each result carries the index of its payment
+        B2cBatchResult::new(originator_conversation_ids, receiver.iter().collect())
     }
 
     /// # B2B API
@@ -137,6 +630,11 @@ impl Mpesa {
     /// business initiating the transaction and the both businesses involved in the transaction
     /// See more at https://developer.safaricom.co.ke/docs?shell#b2b-api
     ///
+    /// `originator_conversation_id` uniquely identifies the request and is used by M-Pesa
+    /// to detect duplicate requests. When `None`, a random UUID v4 is generated, so every
+    /// call is a new payment. To retry a payment that may have gone through without paying
+    /// twice, store the id before the first attempt and pass the same id on every retry.
+    ///
     /// # Example
     /// ```
     /// dotenv::dotenv().ok();
@@ -151,37 +649,58 @@ impl Mpesa {
     /// let b2b_response = client.b2b(
     ///         "testapi496",
     ///         mpesa::CommandId::BusinessToBusinessTransfer,
-    ///         1000,
+    ///         mpesa::Amount::try_new(1000).unwrap(),
     ///         "600496",
-    ///         4,
+    ///         mpesa::IdentifierTypes::Shortcode,
     ///         "600000",
-    ///         4,
+    ///         mpesa::IdentifierTypes::Shortcode,
     ///         "gg",
     ///         "https://muriuki.dev/api/a",
     ///         "https://muriuki.dev/api/b",
     ///         "254708374149",
+    ///         None,
     ///     ).unwrap();
     /// ```
     /// # Errors
-    /// TODO
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`,
+    /// a `MpesaError::InvalidUrl` if a callback url is not a valid https url,
+    /// a `MpesaError::InvalidCommand` if `command_id` is not a b2b command id,
+    /// a `MpesaError::InvalidOriginatorId` if `originator_conversation_id` is not a valid id,
+    /// or a `MpesaError::AmountOutOfRange` if `amount` is outside the b2b limits
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "b2b",
+        skip_all,
+        fields(endpoint = Endpoint::B2b.path(), party_a = %Masked(party_a), party_b = %Masked(party_b))
+    ))]
     pub fn b2b(
         &self,
         initiator_name: &str,
         command_id: CommandId,
-        amount: u32,
+        amount: Amount,
         party_a: &str,
-        sender_id: u32,
+        sender_id: IdentifierTypes,
         party_b: &str,
-        receiver_id: u32,
+        receiver_id: IdentifierTypes,
         remarks: &str,
         queue_timeout_url: &str,
         result_url: &str,
         account_ref: &str,
-    ) -> Result<B2bResponse,Box<dyn std::error::Error>> {
-        let url = format!("{}/mpesa/b2b/v1/paymentrequest", self.environment.base_url());
+        originator_conversation_id: Option<&str>,
+    ) -> Result<B2bResponse,MpesaError> {
+        if let Some(originator_conversation_id) = originator_conversation_id {
+            validation::originator_id(originator_conversation_id)?;
+        }
+        validation::command_id(command_id, CommandId::is_b2b)?;
+        validation::amount(amount, self.limits.b2b)?;
+        validation::callback_urls(&self.environment, &[queue_timeout_url, result_url])?;
+
+        let url = Endpoint::B2b.url(self.environment.base_url());
         let credentials = self.gen_security_credentials()?;
 
         let payload = B2bPayload {
+            originator_conversation_id: originator_conversation_id
+                .map(String::from)
+                .unwrap_or_else(ids::new_originator_id),
             initiator_name,
             security_credentials: &credentials,
             command_id,
@@ -196,28 +715,7 @@ impl Mpesa {
             account_ref,
         };
 
-        let data = json!({
-            "Initiator": payload.initiator_name,
-            "SecurityCredential": payload.security_credentials,
-            "CommandID": payload.command_id.to_string(),
-            "SenderIdentifierType": payload.sender_id,
-            "RecieverIdentifierType": payload.receiver_id,
-            "Amount": payload.amount,
-            "PartyA": payload.party_a,
-            "PartyB": payload.party_b,
-            "AccountReference": payload.account_ref,
-            "Remarks": payload.remarks,
-            "QueueTimeOutURL": payload.queue_timeout_url,
-            "ResultURL": payload.result_url,
-        });
-
-        let response: B2bResponse = Client::new().post(&url)
-            .bearer_auth(self.auth()?)
-            .json(&data)
-            .send()?
-            .json()?;
-
-        Ok(response)
+        self.post(&url, &payload)
     }
 
     /// Registers the the 3rd party’s confirmation and validation URLs to M-Pesa
@@ -243,46 +741,96 @@ impl Mpesa {
     ///         "https://muriuki.dev/api",
     ///         "https://muriuki.dev/verify",
     ///         mpesa::ResponseType::Complete,
-    ///         "600496"
+    ///         &mpesa::ShortCode::new("600496").unwrap(),
     ///     ).unwrap();
     /// ```
     ///
     /// # Errors
-    /// TODO
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "c2b_register",
+        skip_all,
+        fields(endpoint = Endpoint::C2bRegister.path(), short_code = %Masked(short_code.as_str()))
+    ))]
     pub fn c2b_register(
         &self,
         validation_url: &str,
         confirmation_url: &str,
         response_type: ResponseType,
-        short_code: &str,
-    ) -> Result<Response, Box<dyn std::error::Error>> {
-        let url = format!("{}/mpesa/c2b/v1/registerurl", self.environment.base_url());
+        short_code: &ShortCode,
+    ) -> Result<C2bRegisterResponse, MpesaError> {
+        validation::callback_urls(&self.environment, &[validation_url, confirmation_url])?;
+
+        let url = Endpoint::C2bRegister.url(self.environment.base_url());
 
         let payload = C2bRegisterPayload {
             validation_url,
             confirmation_url,
             response_type,
-            short_code,
+            short_code: short_code.as_str(),
         };
 
-        let data = json!({
-            "ValidationURL": payload.validation_url,
-            "ConfirmationURL": payload.confirmation_url,
-            "ResponseType": payload.response_type.to_string(),
-            "ShortCode": payload.short_code,
-        });
-
-        let response = Client::new().post(&url)
-            .bearer_auth(self.auth()?)
-            .json(&data)
-            .send()?;
+        self.post(&url, &payload)
+    }
 
-        Ok(response)
+    /// Registers the confirmation and validation urls of `short_code` unless urls are
+    /// already registered for it.
+    ///
+    /// M-Pesa has no API to update or remove registered urls. In the sandbox, registering
+    /// again overwrites them. In production, urls can only be registered once: registering
+    /// again fails with an error saying they are already registered, which is returned as
+    /// `C2bRegistration::AlreadyRegistered` rather than an error, so that this method can be
+    /// called on every deployment. To change production urls, ask Safaricom to delete the
+    /// registered ones, e.g. through the Daraja portal, then register the new ones.
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let registration = client.c2b_register_once(
+    ///         "https://muriuki.dev/api",
+    ///         "https://muriuki.dev/verify",
+    ///         mpesa::ResponseType::Complete,
+    ///         &mpesa::ShortCode::new("600496").unwrap(),
+    ///     ).unwrap();
+    ///
+    /// if !registration.is_new() {
+    ///     println!("kept the registered urls");
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with any other error body or a non-zero
+    /// `ResponseCode` or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    pub fn c2b_register_once(
+        &self,
+        validation_url: &str,
+        confirmation_url: &str,
+        response_type: ResponseType,
+        short_code: &ShortCode,
+    ) -> Result<C2bRegistration, MpesaError> {
+        match self.c2b_register(validation_url, confirmation_url, response_type, short_code) {
+            Ok(response) => Ok(C2bRegistration::Registered(response)),
+            Err(MpesaError::ApiError { ref message, .. }) if is_already_registered(message) => {
+                debug!(message = %message, "c2b urls already registered");
+                Ok(C2bRegistration::AlreadyRegistered(message.clone()))
+            }
+            Err(error) => Err(error),
+        }
     }
 
     /// Make payment requests from Client to Business
     ///
     /// This enables you to receive the payment requests in real time.
+    /// Simulating payments is only available in the sandbox.
     /// See more here: https://developer.safaricom.co.ke/c2b/apis/post/simulate
     ///
     /// # Example
@@ -298,51 +846,122 @@ impl Mpesa {
     ///
     /// let c2b_simulate_response = client.c2b_simulate(
     ///         mpesa::CommandId::CustomerPayBillOnline,
-    ///         1,
-    ///         "254705583540",
+    ///         mpesa::Amount::try_new(1).unwrap(),
+    ///         &mpesa::PhoneNumber::new("254705583540").unwrap(),
     ///         "123abc",
-    ///         "600496"
+    ///         &mpesa::ShortCode::new("600496").unwrap(),
     ///     ).unwrap();
     /// ```
     ///
     /// # Errors
-    /// TODO
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`,
+    /// a `MpesaError::SandboxOnly` if the client is configured for `Environment::Production`,
+    /// a `MpesaError::InvalidCommand` if `command_id` is not a c2b command id,
+    /// or a `MpesaError::AmountOutOfRange` if `amount` is outside the c2b limits
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "c2b_simulate",
+        skip_all,
+        fields(endpoint = Endpoint::C2bSimulate.path(), short_code = %Masked(short_code.as_str()), msisdn = %Masked(msisdn.as_str()))
+    ))]
     pub fn c2b_simulate(
         &self,
         command_id: CommandId,
-        amount: u32,
-        msisdn: &str,
+        amount: Amount,
+        msisdn: &PhoneNumber,
         bill_ref_number: &str,
-        short_code: &str,
-    ) -> Result<C2bSimulateResponse, Box<dyn std::error::Error>> {
-        let url = format!("{}/mpesa/c2b/v1/simulate", self.environment.base_url());
+        short_code: &ShortCode,
+    ) -> Result<C2bSimulateResponse, MpesaError> {
+        validation::sandbox_only(&self.environment, "c2b_simulate")?;
+        validation::command_id(command_id, CommandId::is_c2b)?;
+        validation::amount(amount, self.limits.c2b)?;
+
+        let url = Endpoint::C2bSimulate.url(self.environment.base_url());
 
         let payload = C2bSimulatePayload {
             command_id,
             amount,
-            msisdn,
+            msisdn: msisdn.as_str(),
             bill_ref_number,
-            short_code
+            short_code: short_code.as_str(),
         };
 
-        let data = json!({
-            "CommandID": payload.command_id.to_string(),
-            "Amount": payload.amount,
-            "Msisdn": payload.msisdn,
-            "BillRefNumber": payload.bill_ref_number,
-            "ShortCode": short_code,
-        });
+        self.post(&url, &payload)
+    }
+
+    /// Simulates many C2B payments, e.g. to load test a validation url in the sandbox.
+    ///
+    /// Sends a `c2b_simulate` request for every `(amount, msisdn, bill_ref_number)` in
+    /// `simulations`, with at most `concurrency` requests in flight at once so the sandbox
+    /// is not overwhelmed. Returns the result of every simulation, in the same order.
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let simulations = (1..=10).map(|amount| (
+    ///     mpesa::Amount::try_new(amount).unwrap(),
+    ///     mpesa::PhoneNumber::new("254705583540").unwrap(),
+    ///     format!("bill-{}", amount),
+    /// ));
+    ///
+    /// let c2b_simulate_responses = client.c2b_simulate_batch(
+    ///         mpesa::CommandId::CustomerPayBillOnline,
+    ///         &mpesa::ShortCode::new("600496").unwrap(),
+    ///         simulations,
+    ///         4,
+    ///     );
+    /// ```
+    pub fn c2b_simulate_batch<I>(
+        &self,
+        command_id: CommandId,
+        short_code: &ShortCode,
+        simulations: I,
+        concurrency: usize,
+    ) -> Vec<Result<C2bSimulateResponse, MpesaError>>
+    where
+        I: IntoIterator<Item = (Amount, PhoneNumber, String)>,
+    {
+        let simulations: Arc<Vec<_>> = Arc::new(simulations.into_iter().collect());
+        let next = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+
+        for _ in 0..concurrency.max(1).min(simulations.len()) {
+            let client = self.clone();
+            let short_code = short_code.clone();
+            let simulations = simulations.clone();
+            let next = next.clone();
+            let sender = sender.clone();
 
-        let response: C2bSimulateResponse = Client::new().post(&url)
-            .bearer_auth(self.auth()?)
-            .json(&data)
-            .send()?
-            .json()?;
+            thread::spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let (amount, msisdn, bill_ref_number) = match simulations.get(index) {
+                    Some(simulation) => simulation,
+                    None => break,
+                };
 
-        Ok(response)
+                let result = client.c2b_simulate(command_id, *amount, msisdn, bill_ref_number, &short_code);
+                let _ = sender.send((index, result));
+            });
+        }
+        drop(sender);
+
+        // Workers finish out of order; put the results back in the order of `simulations`
+        let mut results: Vec<_> = receiver.iter().collect();
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
     }
 
-    /// Enquire the balance on an M-Pesa BuyGoods (Till Number).
+    /// Enquire the balance on an M-Pesa short code or BuyGoods till number.
+    ///
+    /// `identifier_type` identifies `party_a`: use `IdentifierTypes::Shortcode` for a
+    /// paybill short code and `IdentifierTypes::TillNumber` for a BuyGoods till.
     ///
     /// # Example
     /// ```
@@ -356,7 +975,8 @@ impl Mpesa {
     /// );
     ///
     /// let account_balance_response = client.account_balance(
-    ///         "600496",
+    ///         &mpesa::ShortCode::new("600496").unwrap(),
+    ///         mpesa::IdentifierTypes::Shortcode,
     ///         "none",
     ///         "collins",
     ///         "https://hell.world/api",
@@ -365,22 +985,31 @@ impl Mpesa {
     /// ```
     ///
     /// # Errors
-    /// TODO
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "account_balance",
+        skip_all,
+        fields(endpoint = Endpoint::AccountBalance.path(), party_a = %Masked(party_a.as_str()))
+    ))]
     pub fn account_balance(
         &self,
-        party_a: &str,
+        party_a: &ShortCode,
+        identifier_type: IdentifierTypes,
         remarks: &str,
         initiator_name: &str,
         queue_timeout_url: &str,
         result_url: &str,
-    ) -> Result<AccountBalanceResponse, Box<dyn std::error::Error>> {
-        let url = format!("{}/mpesa/accountbalance/v1/query", self.environment.base_url());
+    ) -> Result<AccountBalanceResponse, MpesaError> {
+        validation::callback_urls(&self.environment, &[queue_timeout_url, result_url])?;
+
+        let url = Endpoint::AccountBalance.url(self.environment.base_url());
         let credentials = self.gen_security_credentials()?;
 
         let payload = AccountBalancePayload {
             command_id: CommandId::AccountBalance,
-            party_a,
-            identifier_type: IdentifierTypes::Shortcode,
+            party_a: party_a.as_str(),
+            identifier_type,
             remarks,
             initiator_name,
             queue_timeout_url,
@@ -388,23 +1017,1119 @@ impl Mpesa {
             security_credentials: &credentials,
         };
 
-        let data = json!({
-            "CommandID": payload.command_id.to_string(),
-            "PartyA": payload.party_a,
-            "IdentifierType": "4", // FIXME
-            "Remarks": payload.remarks,
-            "Initiator": payload.initiator_name,
-            "SecurityCredential": payload.security_credentials,
-            "QueueTimeOutURL": payload.queue_timeout_url,
-            "ResultURL": payload.result_url,
-        });
-
-        let response = Client::new().post(&url)
-            .bearer_auth(self.auth()?)
-            .json(&data)
-            .send()?
-            .json()?;
-
-        Ok(response)
+        self.post(&url, &payload)
     }
+
+    /// # Transaction Status API
+    /// Queries the status of a transaction.
+    ///
+    /// The result is posted to `result_url` and parses into a `callbacks::TransactionStatusCallback`,
+    /// whose `DebitPartyName` and `CreditPartyName` carry the names the parties are registered
+    /// under. M-Pesa has no API that resolves a short code or till number to its registered name
+    /// before paying it; querying the status of an earlier transaction with the party confirms
+    /// who it belongs to, e.g. "you're paying ACME LTD", before sending it more funds.
+    ///
+    /// `identifier_type` identifies `party_a`, the organization that initiated the query.
+    /// See more at: https://developer.safaricom.co.ke/APIs/TransactionStatus
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let transaction_status_response = client.transaction_status(
+    ///         "testapi496",
+    ///         "OEI2AK4Q16",
+    ///         "600496",
+    ///         mpesa::IdentifierTypes::Shortcode,
+    ///         "Confirm payee",
+    ///         "",
+    ///         "https://muriuki.dev/api/a",
+    ///         "https://muriuki.dev/api/b",
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if `transaction_id` is empty, a
+    /// `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "transaction_status",
+        skip_all,
+        fields(endpoint = Endpoint::TransactionStatus.path(), party_a = %Masked(party_a))
+    ))]
+    pub fn transaction_status(
+        &self,
+        initiator_name: &str,
+        transaction_id: &str,
+        party_a: &str,
+        identifier_type: IdentifierTypes,
+        remarks: &str,
+        occasion: &str,
+        queue_timeout_url: &str,
+        result_url: &str,
+    ) -> Result<TransactionStatusResponse, MpesaError> {
+        if transaction_id.is_empty() {
+            return Err(MpesaError::MissingField("transaction_id"));
+        }
+
+        validation::callback_urls(&self.environment, &[queue_timeout_url, result_url])?;
+
+        let url = Endpoint::TransactionStatus.url(self.environment.base_url());
+        let credentials = self.gen_security_credentials()?;
+
+        let payload = TransactionStatusPayload {
+            initiator_name,
+            security_credentials: &credentials,
+            command_id: CommandId::TransactionStatusQuery,
+            transaction_id,
+            party_a,
+            identifier_type,
+            remarks,
+            occasion,
+            queue_timeout_url,
+            result_url,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # Reversal API
+    /// Reverses the transaction `transaction_id`, e.g. a payment received from a customer
+    /// in error, returning `amount` from the organization `receiver_party` to the sender.
+    ///
+    /// The reversal is acknowledged synchronously; its result is posted to `result_url`.
+    /// See more at: https://developer.safaricom.co.ke/APIs/Reversal
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if `transaction_id` is empty, a
+    /// `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "reversal",
+        skip_all,
+        fields(endpoint = Endpoint::Reversal.path(), receiver_party = %Masked(receiver_party.as_str()))
+    ))]
+    pub fn reversal(
+        &self,
+        initiator_name: &str,
+        transaction_id: &str,
+        amount: Amount,
+        receiver_party: &ShortCode,
+        remarks: &str,
+        occasion: &str,
+        queue_timeout_url: &str,
+        result_url: &str,
+    ) -> Result<ReversalResponse, MpesaError> {
+        if transaction_id.is_empty() {
+            return Err(MpesaError::MissingField("transaction_id"));
+        }
+
+        validation::callback_urls(&self.environment, &[queue_timeout_url, result_url])?;
+
+        let url = Endpoint::Reversal.url(self.environment.base_url());
+        let credentials = self.gen_security_credentials()?;
+
+        let payload = ReversalPayload {
+            initiator_name,
+            security_credentials: &credentials,
+            command_id: CommandId::TransactionReversal,
+            transaction_id,
+            amount,
+            receiver_party,
+            receiver_identifier_type: IdentifierTypes::Reversal,
+            remarks,
+            occasion,
+            queue_timeout_url,
+            result_url,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// Reverses a transaction known by its M-Pesa receipt number, e.g. one a customer
+    /// quotes to support, using the details of its transaction status.
+    ///
+    /// The Reversal API needs the transaction's amount, which the receipt alone does not
+    /// give. Query `transaction_status` with the receipt number first: its result, posted
+    /// to the query's `result_url`, is then passed here as `status`. The transaction is
+    /// reversed with the receipt number and amount `status` reports, once it confirms the
+    /// transaction completed and was received by `receiver_party`. Both `status` and the
+    /// reversal response are returned in a `ReceiptReversal`.
+    ///
+    /// # Example
+    /// ```
+    /// use mpesa::callbacks::TransactionStatusCallback;
+    ///
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// // Posted to the result url of an earlier `transaction_status` query
+    /// # let body = r#"{"Result":{"ResultType":0,"ResultCode":0,"ResultDesc":"ok","OriginatorConversationID":"1","ConversationID":"2","TransactionID":"OEI2AK4Q16","ResultParameters":{"ResultParameter":[{"Key":"ReceiptNo","Value":"OEI2AK4Q16"},{"Key":"Amount","Value":100},{"Key":"TransactionStatus","Value":"Completed"},{"Key":"CreditPartyName","Value":"600496 - Safaricom496"}]}}}"#;
+    /// let status: TransactionStatusCallback = serde_json::from_str(body).unwrap();
+    ///
+    /// let reversed = client.reverse_by_receipt(
+    ///         "testapi496",
+    ///         status,
+    ///         &mpesa::ShortCode::new("600496").unwrap(),
+    ///         "Paid in error",
+    ///         "",
+    ///         "https://muriuki.dev/api/a",
+    ///         "https://muriuki.dev/api/b",
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::NotReversible` if `status` shows a failed query, or a transaction
+    /// that did not complete, has no receipt or amount, or was received by another party,
+    /// and the errors of `reversal` otherwise
+    pub fn reverse_by_receipt(
+        &self,
+        initiator_name: &str,
+        status: TransactionStatusCallback,
+        receiver_party: &ShortCode,
+        remarks: &str,
+        occasion: &str,
+        queue_timeout_url: &str,
+        result_url: &str,
+    ) -> Result<ReceiptReversal, MpesaError> {
+        let (receipt, amount) = reversible(&status, receiver_party)?;
+
+        let reversal = self.reversal(
+            initiator_name,
+            receipt,
+            amount,
+            receiver_party,
+            remarks,
+            occasion,
+            queue_timeout_url,
+            result_url,
+        )?;
+
+        Ok(ReceiptReversal { status, reversal })
+    }
+
+    /// # Lipa na M-Pesa Online API (STK push)
+    /// Initiates an online payment on behalf of a customer.
+    ///
+    /// This API prompts the customer, via an STK push to their phone, to authorize a payment
+    /// to a paybill or till number. The `Password` is computed as the base64 encoding of
+    /// the business short code, the passkey and the request timestamp.
+    /// See more at: https://developer.safaricom.co.ke/docs#lipa-na-m-pesa-online-payment
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let express_response = client.express_request(
+    ///         "174379",
+    ///         "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+    ///         mpesa::CommandId::CustomerPayBillOnline,
+    ///         mpesa::Amount::try_new(1).unwrap(),
+    ///         &mpesa::PhoneNumber::new("254708374149").unwrap(),
+    ///         "174379",
+    ///         &mpesa::PhoneNumber::new("254708374149").unwrap(),
+    ///         "https://muriuki.dev/api/callback",
+    ///         "test",
+    ///         "test",
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`,
+    /// a `MpesaError::InvalidUrl` if a callback url is not a valid https url,
+    /// a `MpesaError::InvalidCommand` if `transaction_type` is not a c2b command id,
+    /// or a `MpesaError::AmountOutOfRange` if `amount` is outside the express limits
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "express_request",
+        skip_all,
+        fields(endpoint = Endpoint::Express.path(), business_short_code = %Masked(business_short_code), phone_number = %Masked(phone_number.as_str()))
+    ))]
+    pub fn express_request(
+        &self,
+        business_short_code: &str,
+        passkey: &str,
+        transaction_type: CommandId,
+        amount: Amount,
+        party_a: &PhoneNumber,
+        party_b: &str,
+        phone_number: &PhoneNumber,
+        callback_url: &str,
+        account_reference: &str,
+        transaction_desc: &str,
+    ) -> Result<ExpressResponse, MpesaError> {
+        validation::command_id(transaction_type, CommandId::is_c2b)?;
+        validation::amount(amount, self.limits.express)?;
+        validation::callback_urls(&self.environment, &[callback_url])?;
+
+        let url = Endpoint::Express.url(self.environment.base_url());
+        let timestamp = security::timestamp_at(self.clock.now());
+        let password = security::stk_password(business_short_code, passkey, &timestamp);
+
+        let payload = ExpressRequestPayload {
+            business_short_code,
+            password: &password,
+            timestamp: &timestamp,
+            transaction_type,
+            amount,
+            party_a: party_a.as_str(),
+            party_b,
+            phone_number: phone_number.as_str(),
+            callback_url,
+            account_reference,
+            transaction_desc,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # Lipa na M-Pesa Online Query API
+    /// Queries the status of a Lipa na M-Pesa online (STK push) payment.
+    ///
+    /// Takes the `CheckoutRequestID` returned by `express_request` and computes the same
+    /// `Password`/`Timestamp` pair used to initiate the payment.
+    /// See more at: https://developer.safaricom.co.ke/docs#lipa-na-m-pesa-online-query-request
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let express_query_response = client.express_query(
+    ///         "174379",
+    ///         "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+    ///         "ws_CO_DMZ_123212312_2342347678234",
+    ///     );
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "express_query",
+        skip_all,
+        fields(endpoint = Endpoint::ExpressQuery.path(), business_short_code = %Masked(business_short_code))
+    ))]
+    pub fn express_query(
+        &self,
+        business_short_code: &str,
+        passkey: &str,
+        checkout_request_id: &str,
+    ) -> Result<ExpressQueryResponse, MpesaError> {
+        let url = Endpoint::ExpressQuery.url(self.environment.base_url());
+        let timestamp = security::timestamp_at(self.clock.now());
+        let password = security::stk_password(business_short_code, passkey, &timestamp);
+
+        let payload = ExpressQueryPayload {
+            business_short_code,
+            password: &password,
+            timestamp: &timestamp,
+            checkout_request_id,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # Lipa na M-Pesa Online Query API
+    /// Polls `express_query` every `poll_interval` until the customer completes or cancels
+    /// the STK push prompt, returning the final `ExpressQueryResponse`.
+    ///
+    /// The query API responds with an error while the prompt is still pending; those
+    /// responses are retried until `timeout` elapses. Inspect `ResultCode` on the returned
+    /// response to tell a completed payment (`"0"`) from a cancelled or failed one.
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let express_query_response = client.express_wait(
+    ///         "174379",
+    ///         "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+    ///         "ws_CO_DMZ_123212312_2342347678234",
+    ///         std::time::Duration::from_secs(5),
+    ///         std::time::Duration::from_secs(60),
+    ///     );
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::Timeout` if no final result is available before `timeout`
+    /// or a `MpesaError::ApiError` if the API responds with any other error
+    pub fn express_wait(
+        &self,
+        business_short_code: &str,
+        passkey: &str,
+        checkout_request_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<ExpressQueryResponse, MpesaError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match self.express_query(business_short_code, passkey, checkout_request_id) {
+                Err(MpesaError::ApiError { ref code, .. }) if code == EXPRESS_PENDING_CODE => {},
+                result => return result,
+            }
+
+            if Instant::now() + poll_interval > deadline {
+                return Err(MpesaError::Timeout);
+            }
+            debug!(poll_interval_ms = poll_interval.as_millis() as u64, "express request pending");
+            thread::sleep(poll_interval);
+        }
+    }
+
+    /// # B2B Express Checkout API
+    /// Initiates a USSD push to a merchant, prompting them to pay a vendor's paybill
+    /// from their till.
+    ///
+    /// The merchant (`primary_short_code`) receives a USSD prompt to confirm the payment
+    /// of `amount` to `receiver_short_code`; the result is posted to `callback_url`.
+    /// `request_ref_id` must be unique for every request.
+    /// See more at: https://developer.safaricom.co.ke/APIs/B2BExpressCheckout
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let b2b_express_response = client.b2b_express_checkout(
+    ///         "000001",
+    ///         "000002",
+    ///         mpesa::Amount::try_new(100).unwrap(),
+    ///         "paymentRef",
+    ///         "https://muriuki.dev/api/callback",
+    ///         "Vendor",
+    ///         "5d8b4b1c-0b9e-4f6a-9f5e-2a1d8c4b7e10",
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "b2b_express_checkout",
+        skip_all,
+        fields(endpoint = Endpoint::B2bExpressCheckout.path(), primary_short_code = %Masked(primary_short_code), receiver_short_code = %Masked(receiver_short_code))
+    ))]
+    pub fn b2b_express_checkout(
+        &self,
+        primary_short_code: &str,
+        receiver_short_code: &str,
+        amount: Amount,
+        payment_ref: &str,
+        callback_url: &str,
+        partner_name: &str,
+        request_ref_id: &str,
+    ) -> Result<B2bExpressCheckoutResponse, MpesaError> {
+        validation::callback_urls(&self.environment, &[callback_url])?;
+
+        let url = Endpoint::B2bExpressCheckout.url(self.environment.base_url());
+        let amount = amount.to_string();
+
+        let payload = B2bExpressCheckoutPayload {
+            primary_short_code,
+            receiver_short_code,
+            amount: &amount,
+            payment_ref,
+            callback_url,
+            partner_name,
+            request_ref_id,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # Dynamic QR API
+    /// Generates a dynamic M-Pesa QR code.
+    ///
+    /// Customers scan the QR code with the M-Pesa app to pay `amount` to
+    /// `credit_party_identifier` (a till, paybill, agent or phone number, depending on
+    /// `transaction_type`). `size` is the width of the generated image in pixels.
+    /// See more at: https://developer.safaricom.co.ke/APIs/DynamicQRCode
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let qr_response = client.dynamic_qr(
+    ///         "TEST SUPERMARKET",
+    ///         "Invoice Test",
+    ///         mpesa::Amount::try_new(1).unwrap(),
+    ///         mpesa::QrTransactionType::BuyGoods,
+    ///         "373132",
+    ///         300,
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "dynamic_qr",
+        skip_all,
+        fields(endpoint = Endpoint::DynamicQr.path(), credit_party_identifier = %Masked(credit_party_identifier))
+    ))]
+    pub fn dynamic_qr(
+        &self,
+        merchant_name: &str,
+        ref_no: &str,
+        amount: Amount,
+        transaction_type: QrTransactionType,
+        credit_party_identifier: &str,
+        size: u32,
+    ) -> Result<QrResponse, MpesaError> {
+        let url = Endpoint::DynamicQr.url(self.environment.base_url());
+        let size = size.to_string();
+
+        let payload = DynamicQrPayload {
+            merchant_name,
+            ref_no,
+            amount,
+            transaction_type,
+            credit_party_identifier,
+            size: &size,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # Tax Remittance API
+    /// Remits tax to the Kenya Revenue Authority (KRA).
+    ///
+    /// Moves `amount` from the business short code `party_a` to the KRA short code `party_b`.
+    /// `account_reference` is the payment registration number (PRN) issued by KRA and is required.
+    /// See more at: https://developer.safaricom.co.ke/APIs/TaxRemittance
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let tax_remittance_response = client.tax_remittance(
+    ///         "testapi496",
+    ///         mpesa::Amount::try_new(239).unwrap(),
+    ///         "600496",
+    ///         "572572",
+    ///         "Tax remittance",
+    ///         "353353",
+    ///         "https://muriuki.dev/api/a",
+    ///         "https://muriuki.dev/api/b",
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if `account_reference` is empty, a
+    /// `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "tax_remittance",
+        skip_all,
+        fields(endpoint = Endpoint::TaxRemittance.path(), party_a = %Masked(party_a), party_b = %Masked(party_b))
+    ))]
+    pub fn tax_remittance(
+        &self,
+        initiator_name: &str,
+        amount: Amount,
+        party_a: &str,
+        party_b: &str,
+        remarks: &str,
+        account_reference: &str,
+        queue_timeout_url: &str,
+        result_url: &str,
+    ) -> Result<TaxRemittanceResponse, MpesaError> {
+        if account_reference.is_empty() {
+            return Err(MpesaError::MissingField("account_reference"));
+        }
+
+        validation::callback_urls(&self.environment, &[queue_timeout_url, result_url])?;
+
+        let url = Endpoint::TaxRemittance.url(self.environment.base_url());
+        let credentials = self.gen_security_credentials()?;
+
+        let payload = TaxRemittancePayload {
+            initiator_name,
+            security_credentials: &credentials,
+            command_id: CommandId::PayTaxToKRA,
+            sender_id: IdentifierTypes::Shortcode,
+            receiver_id: IdentifierTypes::Shortcode,
+            amount,
+            party_a,
+            party_b,
+            account_reference,
+            remarks,
+            queue_timeout_url,
+            result_url,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # M-Pesa Ratiba API (standing orders)
+    /// Creates a standing order that debits the customer `party_a` every `frequency`
+    /// between `start_date` and `end_date`.
+    ///
+    /// The customer authorizes the standing order on their phone; the outcome is posted
+    /// to `callback_url`. `transaction_type` and `receiver_party_identifier_type` must both
+    /// designate either the paybill (`IdentifierTypes::Shortcode`) or the till
+    /// (`IdentifierTypes::TillNumber`) `business_short_code`.
+    /// See more at: https://developer.safaricom.co.ke/APIs/MpesaRatiba
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let standing_order_response = client.standing_order(
+    ///         "Test Standing Order",
+    ///         chrono::NaiveDate::from_ymd_opt(2024, 9, 5).unwrap(),
+    ///         chrono::NaiveDate::from_ymd_opt(2025, 9, 5).unwrap(),
+    ///         "174379",
+    ///         mpesa::Amount::try_new(4500).unwrap(),
+    ///         &mpesa::PhoneNumber::new("254708374149").unwrap(),
+    ///         mpesa::Frequency::Monthly,
+    ///         mpesa::StandingOrderTransactionType::PayBill,
+    ///         mpesa::IdentifierTypes::Shortcode,
+    ///         "Test",
+    ///         "Test",
+    ///         "https://muriuki.dev/api/callback",
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body
+    /// or a `MpesaError::InvalidUrl` if a callback url is not a valid https url
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "standing_order",
+        skip_all,
+        fields(endpoint = Endpoint::StandingOrder.path(), business_short_code = %Masked(business_short_code), party_a = %Masked(party_a.as_str()))
+    ))]
+    pub fn standing_order(
+        &self,
+        standing_order_name: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        business_short_code: &str,
+        amount: Amount,
+        party_a: &PhoneNumber,
+        frequency: Frequency,
+        transaction_type: StandingOrderTransactionType,
+        receiver_party_identifier_type: IdentifierTypes,
+        account_reference: &str,
+        transaction_desc: &str,
+        callback_url: &str,
+    ) -> Result<StandingOrderResponse, MpesaError> {
+        validation::callback_urls(&self.environment, &[callback_url])?;
+
+        let url = Endpoint::StandingOrder.url(self.environment.base_url());
+        let start_date = start_date.format("%Y%m%d").to_string();
+        let end_date = end_date.format("%Y%m%d").to_string();
+        let amount = amount.to_string();
+
+        let payload = StandingOrderPayload {
+            standing_order_name,
+            start_date: &start_date,
+            end_date: &end_date,
+            business_short_code,
+            transaction_type,
+            receiver_party_identifier_type,
+            amount: &amount,
+            party_a: party_a.as_str(),
+            callback_url,
+            account_reference,
+            transaction_desc,
+            frequency,
+        };
+
+        self.post(&url, &payload)
+    }
+
+    /// # Bill Manager API
+    /// Returns a `BillManager` to onboard a paybill number to Bill Manager, send it
+    /// e-invoices and reconcile their payments.
+    ///
+    /// See more at: https://developer.safaricom.co.ke/APIs/BillManager
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let response = client.bill_manager().opt_in(
+    ///         &mpesa::ShortCode::new("718003").unwrap(),
+    ///         "billing@muriuki.dev",
+    ///         &mpesa::PhoneNumber::new("0708374149").unwrap(),
+    ///         true,
+    ///         "https://muriuki.dev/logo.png",
+    ///         "https://muriuki.dev/api/callback",
+    ///     ).unwrap();
+    /// ```
+    pub fn bill_manager(&self) -> BillManager<'_, Mpesa> {
+        BillManager::new(self)
+    }
+
+    /// # Advanced
+    /// Sends `body` to an endpoint the crate does not cover yet and returns the raw JSON
+    /// response, still using the cached access token, retries and error handling.
+    ///
+    /// `path` is joined to the environment's base url, e.g. `"/mpesa/b2b/v1/remittax"`.
+    /// Prefer the typed methods where they exist: nothing about `body` is checked before
+    /// it is sent.
+    ///
+    /// # Example
+    /// ```
+    /// dotenv::dotenv().ok();
+    ///
+    /// let client = mpesa::Mpesa::new(
+    ///    std::env::var("CLIENT_KEY").unwrap(),
+    ///    std::env::var("CLIENT_SECRET").unwrap(),
+    ///    mpesa::Environment::Sandbox,
+    ///    std::env::var("INIT_PASSWORD").unwrap(),
+    /// );
+    ///
+    /// let response = client.post_json(
+    ///         "/mpesa/c2b/v1/simulate",
+    ///         serde_json::json!({
+    ///             "ShortCode": "600496",
+    ///             "CommandID": "CustomerPayBillOnline",
+    ///             "Amount": 1,
+    ///             "Msisdn": "254705583540",
+    ///             "BillRefNumber": "123abc",
+    ///         }),
+    ///     ).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "post_json",
+        skip_all,
+        fields(endpoint = %path)
+    ))]
+    pub fn post_json(&self, path: &str, body: Value) -> Result<Value, MpesaError> {
+        let url = format!("{}/{}", self.environment.base_url(), path.trim_start_matches('/'));
+
+        self.post(&url, &body)
+    }
+
+    /// # Advanced
+    /// Sends a request built without being sent, e.g. by `B2cBuilder::build_request` in
+    /// another service, and returns the raw JSON response, using this client's access
+    /// token, retries and error handling.
+    ///
+    /// The request is sent as is: its security credentials are not regenerated, so this
+    /// client does not need the initiator password.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body or a non-zero `ResponseCode`
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "send_signed",
+        skip_all,
+        fields(endpoint = %request.url)
+    ))]
+    pub fn send_signed(&self, request: &SignedRequest) -> Result<Value, MpesaError> {
+        self.post(&request.url, &request.body)
+    }
+}
+
+impl<'a> B2cBuilder<'a, Mpesa> {
+    /// Sends the b2c payment request.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if a field was not set, a `MpesaError::InvalidUrl`
+    /// if a callback url is not a valid https url, a `MpesaError::InvalidCommand` if the
+    /// command id is not a b2c command id, a `MpesaError::AmountOutOfRange` if the amount
+    /// is outside the b2c limits, a `MpesaError::Validation` listing every problem if there
+    /// are several, a `MpesaError::DuplicateRequest` if the API reports a payment with the
+    /// same originator conversation id was already received, or a `MpesaError::ApiError`
+    /// if the API responds with an error body or a non-zero `ResponseCode`
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "b2c",
+        skip_all,
+        fields(endpoint = Endpoint::B2c.path())
+    ))]
+    pub fn send(self) -> Result<B2cResponse, MpesaError> {
+        let client = self.client();
+        let request = self.build_request()?;
+        let originator_conversation_id = request.body["OriginatorConversationID"].as_str().unwrap_or_default();
+
+        client
+            .post(&request.url, &request.body)
+            .map_err(|error| duplicate_request(error, originator_conversation_id))
+    }
+
+    /// Validates the b2c payment request and generates its security credentials without
+    /// sending it, e.g. to sign it in a service holding the initiator password and send
+    /// it from another with `Mpesa::send_signed`.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::MissingField` if a field was not set, a `MpesaError::InvalidUrl`
+    /// if a callback url is not a valid https url, a `MpesaError::InvalidCommand` if the
+    /// command id is not a b2c command id, a `MpesaError::AmountOutOfRange` if the amount
+    /// is outside the b2c limits, a `MpesaError::Validation` listing every problem if there
+    /// are several, or a `MpesaError::EncryptionError` if the security
+    /// credentials could not be generated
+    pub fn build_request(self) -> Result<SignedRequest, MpesaError> {
+        let client = self.client();
+        self.validate(&client.environment, &client.limits)?;
+
+        let url = Endpoint::B2c.url(client.environment.base_url());
+        let credentials = match self.supplied_security_credential() {
+            Some(credentials) => credentials.to_string(),
+            None => client.gen_security_credentials()?,
+        };
+        let payload = self.build(&credentials)?;
+
+        Ok(SignedRequest {
+            url,
+            body: payload.to_json(),
+            method: String::from("POST"),
+        })
+    }
+}
+
+impl<'a> BillManager<'a, Mpesa> {
+    /// Onboards `short_code` to Bill Manager.
+    ///
+    /// `official_contact` and `email` are shown to customers on their invoices, `logo`
+    /// is the url of the image printed on them and `callback_url` receives the payments
+    /// made against them. Reminders are sent before an invoice is due if `send_reminders`
+    /// is set.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::InvalidUrl` if the callback url is not a valid https url
+    /// or a `MpesaError::ApiError` if the API responds with an error body
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "bill_manager_opt_in",
+        skip_all,
+        fields(endpoint = Endpoint::BillManagerOptIn.path(), short_code = %Masked(short_code.as_str()))
+    ))]
+    pub fn opt_in(
+        &self,
+        short_code: &ShortCode,
+        email: &str,
+        official_contact: &PhoneNumber,
+        send_reminders: bool,
+        logo: &str,
+        callback_url: &str,
+    ) -> Result<BillManagerOptInResponse, MpesaError> {
+        let client = self.client();
+        validation::callback_urls(&client.environment, &[callback_url])?;
+
+        let url = Endpoint::BillManagerOptIn.url(client.environment.base_url());
+
+        let payload = BillManagerOptInPayload {
+            shortcode: short_code.as_str(),
+            email,
+            official_contact: official_contact.as_str(),
+            send_reminders: if send_reminders { "1" } else { "0" },
+            logo,
+            callback_url,
+        };
+
+        client.post(&url, &payload)
+    }
+
+    /// Sends a single e-invoice of `amount`, itemized by `invoice_items`, to the customer
+    /// at `billed_phone_number`.
+    ///
+    /// `external_reference` identifies the invoice in the biller's system and is sent back
+    /// with its payment.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "bill_manager_single_invoice",
+        skip_all,
+        fields(endpoint = Endpoint::BillManagerInvoice.path(), billed_phone_number = %Masked(billed_phone_number.as_str()))
+    ))]
+    pub fn single_invoice(
+        &self,
+        external_reference: &str,
+        billed_full_name: &str,
+        billed_phone_number: &PhoneNumber,
+        billed_period: &str,
+        invoice_name: &str,
+        due_date: NaiveDate,
+        account_reference: &str,
+        amount: Amount,
+        invoice_items: &[InvoiceItem<'_>],
+    ) -> Result<BillManagerResponse, MpesaError> {
+        let client = self.client();
+        let url = Endpoint::BillManagerInvoice.url(client.environment.base_url());
+        let due_date = due_date.format("%Y-%m-%d").to_string();
+
+        let payload = InvoicePayload {
+            external_reference,
+            billed_full_name,
+            billed_phone_number: billed_phone_number.as_str(),
+            billed_period,
+            invoice_name,
+            due_date: &due_date,
+            account_reference,
+            amount,
+            invoice_items,
+        };
+
+        client.post(&url, &payload)
+    }
+
+    /// Acknowledges a payment of `paid_amount` made against the invoice `external_reference`,
+    /// which sends the customer an e-receipt.
+    ///
+    /// # Errors
+    /// Returns a `MpesaError::ApiError` if the API responds with an error body
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "bill_manager_reconciliation",
+        skip_all,
+        fields(endpoint = Endpoint::BillManagerReconciliation.path(), phone_number = %Masked(phone_number.as_str()))
+    ))]
+    pub fn reconciliation(
+        &self,
+        payment_date: NaiveDate,
+        paid_amount: Amount,
+        account_reference: &str,
+        transaction_id: &str,
+        phone_number: &PhoneNumber,
+        full_name: &str,
+        invoice_name: &str,
+        external_reference: &str,
+    ) -> Result<BillManagerResponse, MpesaError> {
+        let client = self.client();
+        let url = Endpoint::BillManagerReconciliation.url(client.environment.base_url());
+        let payment_date = payment_date.format("%Y-%m-%d").to_string();
+
+        let payload = ReconciliationPayload {
+            payment_date: &payment_date,
+            paid_amount,
+            account_reference,
+            transaction_id,
+            phone_number: phone_number.as_str(),
+            full_name,
+            invoice_name,
+            external_reference,
+        };
+
+        client.post(&url, &payload)
+    }
+}
+
+/// OAuth grant requested for access tokens, the only one Safaricom currently supports
+pub(crate) const GRANT_TYPE: &str = "client_credentials";
+
+/// Media type of every request and response body, sent in the `Accept` header
+pub(crate) const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// Bytes of an unexpected response body kept in `MpesaError::UnexpectedResponse`, so
+/// that a whole HTML maintenance page does not end up in an error message
+pub(crate) const UNEXPECTED_BODY_LIMIT: usize = 512;
+
+/// `errorCode` returned by the STK push query API while the customer has not yet
+/// responded to the prompt
+pub(crate) const EXPRESS_PENDING_CODE: &str = "500.001.1001";
+
+/// Whether the `errorMessage` of a C2B register error says urls are already registered
+/// for the short code, e.g. `"Urls are already registered"`. The error code is shared
+/// with unrelated errors, so only the message tells it apart.
+pub(crate) fn is_already_registered(message: &str) -> bool {
+    message.to_lowercase().contains("already registered")
+}
+
+/// Turns an API error saying the request is a duplicate, e.g. `"Duplicate OriginatorConversationID"`,
+/// into `MpesaError::DuplicateRequest` for the request sent with `originator_conversation_id`
+pub(crate) fn duplicate_request(error: MpesaError, originator_conversation_id: &str) -> MpesaError {
+    match error {
+        MpesaError::ApiError { ref message, .. } if message.to_lowercase().contains("duplicate") => {
+            MpesaError::DuplicateRequest {
+                originator_conversation_id: originator_conversation_id.to_string(),
+                message: message.clone(),
+            }
+        }
+        error => error,
+    }
+}
+
+/// The receipt number and amount to reverse the transaction `status` describes with, if it
+/// completed and was credited to `receiver_party`
+pub(crate) fn reversible<'a>(status: &'a TransactionStatusCallback, receiver_party: &ShortCode) -> Result<(&'a str, Amount), MpesaError> {
+    let not_reversible = |reason: String| Err(MpesaError::NotReversible(reason));
+
+    if !status.result_code().is_success() {
+        return not_reversible(format!("the status query failed: {}", status.ResultDesc));
+    }
+    match status.TransactionStatus.as_deref() {
+        Some("Completed") => {},
+        Some(transaction_status) => return not_reversible(format!("the transaction is {}", transaction_status)),
+        None => return not_reversible(String::from("the transaction status is unknown")),
+    }
+    let receipt = match status.ReceiptNo.as_deref() {
+        Some(receipt) if !receipt.is_empty() => receipt,
+        _ => return not_reversible(String::from("the transaction has no receipt number")),
+    };
+    let amount = match status.Amount {
+        Some(amount) if amount.fract() == 0.0 && amount >= 0.0 && amount <= u32::MAX as f64 => Amount::try_new(amount as u32).ok(),
+        _ => None,
+    };
+    let amount = match amount {
+        Some(amount) => amount,
+        None => return not_reversible(format!("the transaction amount {:?} is not a valid amount", status.Amount)),
+    };
+    // e.g. "600496 - Safaricom496"
+    let credit_party = status.CreditPartyName.as_deref().unwrap_or_default();
+    let credited_to = credit_party.split(" - ").next().unwrap_or_default().trim();
+    if credited_to != receiver_party.as_str() {
+        return not_reversible(format!("the transaction was credited to {:?}, not {}", credit_party, receiver_party));
+    }
+
+    Ok((receipt, amount))
+}
+
+/// Reports an OAuth error response, e.g. for a wrong client key or secret,
+/// as `MpesaError::Authentication`
+pub(crate) fn authentication_error(error: MpesaError) -> MpesaError {
+    match error {
+        MpesaError::ApiError { code, message } => MpesaError::Authentication { code, message },
+        MpesaError::UnexpectedResponse { status, body } if status == 400 || status == 401 => MpesaError::Authentication {
+            code: status.to_string(),
+            message: if body.trim().is_empty() { String::from("invalid client key or secret") } else { body },
+        },
+        error => error,
+    }
+}
+
+/// Deserializes an API response into `T`.
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, MpesaError> {
+    let status = response.status();
+    let content_type = content_type(response.headers());
+    let body = response.text()?;
+    parse_body(status, content_type.as_deref(), body)
+}
+
+/// The `Content-Type` of a response, if set
+pub(crate) fn content_type(headers: &HeaderMap) -> Option<String> {
+    headers.get(CONTENT_TYPE)?.to_str().ok().map(String::from)
+}
+
+/// Deserializes a raw response body into `T`.
+/// Responses whose `content_type` is not JSON, e.g. an HTML maintenance page, bodies that
+/// are not JSON and error responses of an unknown shape are returned as
+/// `MpesaError::UnexpectedResponse` with the status and the start of the raw body.
+pub(crate) fn parse_body<T: DeserializeOwned>(status: StatusCode, content_type: Option<&str>, body: String) -> Result<T, MpesaError> {
+    if matches!(content_type, Some(content_type) if !content_type.to_ascii_lowercase().contains("json")) {
+        return Err(unexpected_response(status, body));
+    }
+
+    let value: Value = match serde_json::from_str(&body) {
+        Ok(value) => value,
+        Err(_) => return Err(unexpected_response(status, body)),
+    };
+
+    match parse_value(value) {
+        Err(MpesaError::ParseError(_)) if !status.is_success() => Err(unexpected_response(status, body)),
+        result => result,
+    }
+}
+
+fn unexpected_response(status: StatusCode, mut body: String) -> MpesaError {
+    if body.len() > UNEXPECTED_BODY_LIMIT {
+        let end = (0..=UNEXPECTED_BODY_LIMIT).rev().find(|&index| body.is_char_boundary(index)).unwrap_or(0);
+        body.truncate(end);
+        body.push_str("...");
+    }
+
+    warn!(status = status.as_u16(), body = %body, "unexpected response");
+    MpesaError::UnexpectedResponse {
+        status: status.as_u16(),
+        body,
+    }
+}
+
+/// Deserializes a JSON response body into `T`.
+/// Safaricom error bodies and responses with a non-zero numeric `ResponseCode`, sent as
+/// a string or a number, are returned as `MpesaError::ApiError`. Some APIs, e.g. dynamic QR,
+/// send a reference as their `ResponseCode`, which is not treated as a failure.
+pub(crate) fn parse_value<T: DeserializeOwned>(value: Value) -> Result<T, MpesaError> {
+    if let Ok(error) = serde_json::from_value::<MpesaErrorResponse>(value.clone()) {
+        warn!(code = %error.errorCode, message = %error.errorMessage, "api error");
+        return Err(MpesaError::ApiError {
+            code: error.errorCode,
+            message: error.errorMessage,
+        });
+    }
+
+    if let Some(code) = value.get("ResponseCode").and_then(code_string) {
+        if !is_success_code(&code) {
+            let message = value.get("ResponseDescription")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            warn!(code = %code, message = %message, "non-zero response code");
+
+            return Err(MpesaError::ApiError {
+                code,
+                message: message.to_string(),
+            });
+        }
+    }
+
+    Ok(serde_json::from_value(value)?)
 }
\ No newline at end of file
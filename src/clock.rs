@@ -0,0 +1,52 @@
+//! # clock
+//! The source of the current time the clients sign time based requests with, e.g. the
+//! `Timestamp` and `Password` of Lipa na M-Pesa online (STK push) requests
+
+use std::fmt::Debug;
+use chrono::{DateTime,Local};
+
+/// Tells the current local time.
+///
+/// Clients use `SystemClock` unless another clock is set with `MpesaBuilder::clock`,
+/// e.g. a `FixedClock` so that tests can assert the exact timestamp and password sent.
+pub trait Clock: Debug + Send + Sync {
+    /// The current local time
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// The system's clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A clock stopped at a fixed time, for deterministic tests.
+///
+/// ## Example
+/// ```
+/// use chrono::{Local, TimeZone};
+/// use mpesa::clock::{Clock, FixedClock};
+/// use mpesa::security::timestamp_at;
+///
+/// let clock = FixedClock::new(Local.with_ymd_and_hms(2016, 2, 16, 16, 56, 27).unwrap());
+/// assert_eq!(timestamp_at(clock.now()), "20160216165627");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(DateTime<Local>);
+
+impl FixedClock {
+    /// A clock always telling `time`
+    pub fn new(time: DateTime<Local>) -> Self {
+        Self(time)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        self.0
+    }
+}
@@ -1,21 +1,70 @@
+use std::convert::TryFrom;
+use std::error::Error;
 use std::fmt::{Display,Formatter,Result as FmtResult};
+use std::str::FromStr;
+use serde::{Deserialize,Serialize,Serializer};
 
 /// Mpesa command ids
-#[derive(Debug)]
+///
+/// Serializes to and parses from the exact command id expected by the API,
+/// e.g. `"BusinessPayment"`. Each API only accepts some command ids, see `is_b2c`,
+/// `is_b2b` and `is_c2b`; the client returns `MpesaError::InvalidCommand` before
+/// sending a request with any other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CommandId {
+    /// Reverses a transaction (Reversal API)
     TransactionReversal,
+    /// B2C payment of salaries
     SalaryPayment,
+    /// B2C payment with no specific purpose, e.g. a refund
     BusinessPayment,
+    /// B2C payment of bonuses or promotions
     PromotionPayment,
+    /// Queries an organization's account balance (Account Balance API)
     AccountBalance,
+    /// C2B or STK push payment to a paybill number
     CustomerPayBillOnline,
+    /// C2B or STK push payment to a till number
+    CustomerBuyGoodsOnline,
+    /// Queries the status of a transaction (Transaction Status API)
     TransactionStatusQuery,
     CheckIdentity,
+    /// B2B payment to a paybill number
     BusinessPayBill,
+    /// B2B payment to a till number
     BusinessBuyGoods,
+    /// B2B transfer from a business' utility account to its working account
     DisburseFundsToBusiness,
+    /// B2B transfer between the working accounts of two businesses
     BusinessToBusinessTransfer,
+    /// B2B transfer from a business' MMF account to its utility account
     BusinessTransferFromMMFToUtility,
+    /// Tax remittance to the Kenya Revenue Authority
+    PayTaxToKRA,
+}
+
+impl CommandId {
+    /// Whether the command id is accepted by the B2C API
+    pub fn is_b2c(&self) -> bool {
+        matches!(self, CommandId::SalaryPayment | CommandId::BusinessPayment | CommandId::PromotionPayment)
+    }
+
+    /// Whether the command id is accepted by the B2B API
+    pub fn is_b2b(&self) -> bool {
+        matches!(
+            self,
+            CommandId::BusinessPayBill
+                | CommandId::BusinessBuyGoods
+                | CommandId::DisburseFundsToBusiness
+                | CommandId::BusinessToBusinessTransfer
+                | CommandId::BusinessTransferFromMMFToUtility
+        )
+    }
+
+    /// Whether the command id is accepted by the C2B simulate and Lipa na M-Pesa online APIs
+    pub fn is_c2b(&self) -> bool {
+        matches!(self, CommandId::CustomerPayBillOnline | CommandId::CustomerBuyGoodsOnline)
+    }
 }
 
 impl Display for CommandId {
@@ -24,34 +73,125 @@ impl Display for CommandId {
     }
 }
 
-/// Identifier types - both sender and receiver - identify an M-Pesa transaction’s sending and receiving party as
-/// either a shortcode, a till number or a MSISDN (phone number).
-/// There are three identifier types that can be used with M-Pesa APIs.
+impl FromStr for CommandId {
+    type Err = ParseCommandIdError;
+
+    /// Parses the command id as sent to the API, e.g. `"BusinessPayment"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TransactionReversal" => Ok(CommandId::TransactionReversal),
+            "SalaryPayment" => Ok(CommandId::SalaryPayment),
+            "BusinessPayment" => Ok(CommandId::BusinessPayment),
+            "PromotionPayment" => Ok(CommandId::PromotionPayment),
+            "AccountBalance" => Ok(CommandId::AccountBalance),
+            "CustomerPayBillOnline" => Ok(CommandId::CustomerPayBillOnline),
+            "CustomerBuyGoodsOnline" => Ok(CommandId::CustomerBuyGoodsOnline),
+            "TransactionStatusQuery" => Ok(CommandId::TransactionStatusQuery),
+            "CheckIdentity" => Ok(CommandId::CheckIdentity),
+            "BusinessPayBill" => Ok(CommandId::BusinessPayBill),
+            "BusinessBuyGoods" => Ok(CommandId::BusinessBuyGoods),
+            "DisburseFundsToBusiness" => Ok(CommandId::DisburseFundsToBusiness),
+            "BusinessToBusinessTransfer" => Ok(CommandId::BusinessToBusinessTransfer),
+            "BusinessTransferFromMMFToUtility" => Ok(CommandId::BusinessTransferFromMMFToUtility),
+            "PayTaxToKRA" => Ok(CommandId::PayTaxToKRA),
+            _ => Err(ParseCommandIdError(s.to_string())),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown `CommandId`
 #[derive(Debug)]
+pub struct ParseCommandIdError(String);
+
+impl Display for ParseCommandIdError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "unknown command id: {}", self.0)
+    }
+}
+
+impl Error for ParseCommandIdError {}
+
+/// Identifier types - both sender and receiver - identify an M-Pesa transaction’s sending and receiving party as
+/// either a shortcode, a till number or a MSISDN (phone number), or identify the organization
+/// receiving a reversed transaction.
+/// There are four identifier types that can be used with M-Pesa APIs.
+/// Serializes to the numeric code expected by the API, e.g. `"4"` for `Shortcode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IdentifierTypes {
-    MSISDN = 1,
+    Msisdn = 1,
     TillNumber = 2,
     Shortcode = 4,
+    /// The organization receiving a reversed transaction, used by the Reversal API
+    Reversal = 11,
 }
 
 impl IdentifierTypes {
-    pub fn get_code(&self) -> &str {
+    /// Numeric code of the identifier type as expected by the API
+    pub fn code(&self) -> &'static str {
         match self {
-            IdentifierTypes::MSISDN => "1",
+            IdentifierTypes::Msisdn => "1",
             IdentifierTypes::TillNumber => "2",
             IdentifierTypes::Shortcode => "4",
+            IdentifierTypes::Reversal => "11",
         }
     }
+
+    /// Looks up the identifier type with the numeric `code`, e.g. from a callback,
+    /// returning `None` for unknown codes
+    pub fn from_code(code: &str) -> Option<IdentifierTypes> {
+        match code {
+            "1" => Some(IdentifierTypes::Msisdn),
+            "2" => Some(IdentifierTypes::TillNumber),
+            "4" => Some(IdentifierTypes::Shortcode),
+            "11" => Some(IdentifierTypes::Reversal),
+            _ => None,
+        }
+    }
+
+    /// Numeric code of the identifier type as expected by the API
+    #[deprecated(note = "use `IdentifierTypes::code` instead")]
+    pub fn get_code(&self) -> &str {
+        self.code()
+    }
+}
+
+impl Serialize for IdentifierTypes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
 }
 
 impl Display for IdentifierTypes {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "{:?}", self.get_code())
+        write!(f, "{}", self.code())
     }
 }
 
-/// M-pesa result and response codes
+impl TryFrom<&str> for IdentifierTypes {
+    type Error = ParseIdentifierTypeError;
+
+    /// Parses the numeric code of the identifier type, e.g. `"4"`
+    fn try_from(code: &str) -> Result<Self, Self::Error> {
+        IdentifierTypes::from_code(code).ok_or_else(|| ParseIdentifierTypeError(code.to_string()))
+    }
+}
+
+/// Error returned when parsing an unknown `IdentifierTypes` code
 #[derive(Debug)]
+pub struct ParseIdentifierTypeError(String);
+
+impl Display for ParseIdentifierTypeError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "unknown identifier type code: {}", self.0)
+    }
+}
+
+impl Error for ParseIdentifierTypeError {}
+
+/// M-pesa result and response codes
+///
+/// See `ResultCode` to parse the codes of responses and callbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MpesaResponseCode {
     Success = 0,
     InsufficientFunds = 1,
@@ -76,4 +216,164 @@ impl Display for MpesaResponseCode {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         write!(f, "{:?}", self)
     }
-}
\ No newline at end of file
+}
+
+/// Common result and response codes returned across the M-Pesa APIs, parsed from the
+/// raw code of a response or callback with `ResultCode::from_code`.
+///
+/// # Example
+/// ```
+/// use mpesa::ResultCode;
+///
+/// let code = ResultCode::from_code("2001");
+/// assert_eq!(code, ResultCode::InitiatorInformationInvalid);
+/// assert!(!code.is_success());
+/// assert_eq!(code.code(), "2001");
+///
+/// assert_eq!(ResultCode::from_code("400.002.02"), ResultCode::Unknown(String::from("400.002.02")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResultCode {
+    Success,
+    InsufficientFunds,
+    LessThanMinimum,
+    MoreThanMaximum,
+    ExceededDailyLimit,
+    ExceededMinimumBalance,
+    UnresolvedPrimaryParty,
+    UnresolvedReceiverParty,
+    ExceededMaximumBalance,
+    InvalidDebitAccount,
+    InvalidCreditAccount,
+    UnresolvedDebitAccount,
+    UnresolvedCreditAccount,
+    DuplicateDetected,
+    /// Internal failure, also returned when a rule limits the transaction
+    InternalFailure,
+    UnresolvedInitiator,
+    TrafficBlocking,
+    /// Another transaction of the subscriber is already in process
+    SubscriberLocked,
+    /// Lipa na M-Pesa online request expired before the customer entered their PIN
+    TransactionExpired,
+    /// Lipa na M-Pesa online prompt could not be sent to the customer
+    PushRequestError,
+    /// Lipa na M-Pesa online request cancelled by the customer
+    CancelledByUser,
+    /// Lipa na M-Pesa online prompt could not reach the customer's phone
+    UserUnreachable,
+    /// Wrong initiator name or password, or wrong PIN entered by the customer
+    InitiatorInformationInvalid,
+    /// Any other code, as sent by the API
+    Unknown(String),
+}
+
+impl ResultCode {
+    /// Parses a code sent as a string, e.g. `"0"` or `"1032"`. Codes with no variant,
+    /// including the dotted codes of error bodies, are kept in `Unknown`.
+    pub fn from_code(code: &str) -> ResultCode {
+        match code.trim().parse::<u32>() {
+            Ok(0) => ResultCode::Success,
+            Ok(1) => ResultCode::InsufficientFunds,
+            Ok(2) => ResultCode::LessThanMinimum,
+            Ok(3) => ResultCode::MoreThanMaximum,
+            Ok(4) => ResultCode::ExceededDailyLimit,
+            Ok(5) => ResultCode::ExceededMinimumBalance,
+            Ok(6) => ResultCode::UnresolvedPrimaryParty,
+            Ok(7) => ResultCode::UnresolvedReceiverParty,
+            Ok(8) => ResultCode::ExceededMaximumBalance,
+            Ok(11) => ResultCode::InvalidDebitAccount,
+            Ok(12) => ResultCode::InvalidCreditAccount,
+            Ok(13) => ResultCode::UnresolvedDebitAccount,
+            Ok(14) => ResultCode::UnresolvedCreditAccount,
+            Ok(15) => ResultCode::DuplicateDetected,
+            Ok(17) => ResultCode::InternalFailure,
+            Ok(20) => ResultCode::UnresolvedInitiator,
+            Ok(26) => ResultCode::TrafficBlocking,
+            Ok(1001) => ResultCode::SubscriberLocked,
+            Ok(1019) => ResultCode::TransactionExpired,
+            Ok(1025) => ResultCode::PushRequestError,
+            Ok(1032) => ResultCode::CancelledByUser,
+            Ok(1037) => ResultCode::UserUnreachable,
+            Ok(2001) => ResultCode::InitiatorInformationInvalid,
+            _ => ResultCode::Unknown(code.to_string()),
+        }
+    }
+
+    /// The numeric code as sent by the API
+    pub fn code(&self) -> &str {
+        match self {
+            ResultCode::Success => "0",
+            ResultCode::InsufficientFunds => "1",
+            ResultCode::LessThanMinimum => "2",
+            ResultCode::MoreThanMaximum => "3",
+            ResultCode::ExceededDailyLimit => "4",
+            ResultCode::ExceededMinimumBalance => "5",
+            ResultCode::UnresolvedPrimaryParty => "6",
+            ResultCode::UnresolvedReceiverParty => "7",
+            ResultCode::ExceededMaximumBalance => "8",
+            ResultCode::InvalidDebitAccount => "11",
+            ResultCode::InvalidCreditAccount => "12",
+            ResultCode::UnresolvedDebitAccount => "13",
+            ResultCode::UnresolvedCreditAccount => "14",
+            ResultCode::DuplicateDetected => "15",
+            ResultCode::InternalFailure => "17",
+            ResultCode::UnresolvedInitiator => "20",
+            ResultCode::TrafficBlocking => "26",
+            ResultCode::SubscriberLocked => "1001",
+            ResultCode::TransactionExpired => "1019",
+            ResultCode::PushRequestError => "1025",
+            ResultCode::CancelledByUser => "1032",
+            ResultCode::UserUnreachable => "1037",
+            ResultCode::InitiatorInformationInvalid => "2001",
+            ResultCode::Unknown(code) => code,
+        }
+    }
+
+    /// Whether the code means the request or transaction succeeded
+    pub fn is_success(&self) -> bool {
+        *self == ResultCode::Success
+    }
+
+    /// Human readable description of the code
+    pub fn description(&self) -> &'static str {
+        match self {
+            ResultCode::Success => "Success",
+            ResultCode::InsufficientFunds => "Insufficient funds",
+            ResultCode::LessThanMinimum => "Less than the minimum transaction value",
+            ResultCode::MoreThanMaximum => "More than the maximum transaction value",
+            ResultCode::ExceededDailyLimit => "Would exceed the daily transfer limit",
+            ResultCode::ExceededMinimumBalance => "Would exceed the minimum balance",
+            ResultCode::UnresolvedPrimaryParty => "Unresolved primary party",
+            ResultCode::UnresolvedReceiverParty => "Unresolved receiver party",
+            ResultCode::ExceededMaximumBalance => "Would exceed the maximum balance",
+            ResultCode::InvalidDebitAccount => "Debit account invalid",
+            ResultCode::InvalidCreditAccount => "Credit account invalid",
+            ResultCode::UnresolvedDebitAccount => "Unresolved debit account",
+            ResultCode::UnresolvedCreditAccount => "Unresolved credit account",
+            ResultCode::DuplicateDetected => "Duplicate detected",
+            ResultCode::InternalFailure => "Internal failure",
+            ResultCode::UnresolvedInitiator => "Unresolved initiator",
+            ResultCode::TrafficBlocking => "Traffic blocking condition in place",
+            ResultCode::SubscriberLocked => "A transaction is already in process for the subscriber",
+            ResultCode::TransactionExpired => "Transaction expired",
+            ResultCode::PushRequestError => "Error sending the push request",
+            ResultCode::CancelledByUser => "Request cancelled by the user",
+            ResultCode::UserUnreachable => "The user could not be reached",
+            ResultCode::InitiatorInformationInvalid => "The initiator information is invalid",
+            ResultCode::Unknown(_) => "Unknown code",
+        }
+    }
+}
+
+impl From<&str> for ResultCode {
+    fn from(code: &str) -> Self {
+        ResultCode::from_code(code)
+    }
+}
+
+impl Display for ResultCode {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.code())
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::{Display,Formatter,Result as FmtResult};
+
+/// The M-Pesa APIs the clients send requests to, each with the path it is served at.
+///
+/// Safaricom moves endpoints to new gateway paths from time to time; every path the
+/// crate uses is listed here, so following a move only takes changing `Endpoint::path`.
+///
+/// # Example
+/// ```
+/// use mpesa::endpoints::Endpoint;
+/// use mpesa::Environment;
+///
+/// assert_eq!(Endpoint::B2c.path(), "/mpesa/b2c/v1/paymentrequest");
+/// assert_eq!(
+///     Endpoint::B2c.url(Environment::Sandbox.base_url()),
+///     "https://sandbox.safaricom.co.ke/mpesa/b2c/v1/paymentrequest",
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// OAuth access token generation
+    Auth,
+    B2b,
+    B2c,
+    C2bRegister,
+    C2bSimulate,
+    AccountBalance,
+    TransactionStatus,
+    Reversal,
+    /// Lipa na M-Pesa online (STK push)
+    Express,
+    /// Lipa na M-Pesa online (STK push) status query
+    ExpressQuery,
+    B2bExpressCheckout,
+    DynamicQr,
+    TaxRemittance,
+    StandingOrder,
+    BillManagerOptIn,
+    BillManagerInvoice,
+    BillManagerReconciliation,
+}
+
+impl Endpoint {
+    /// Every endpoint, in the order they are declared
+    pub const ALL: &'static [Endpoint] = &[
+        Endpoint::Auth,
+        Endpoint::B2b,
+        Endpoint::B2c,
+        Endpoint::C2bRegister,
+        Endpoint::C2bSimulate,
+        Endpoint::AccountBalance,
+        Endpoint::TransactionStatus,
+        Endpoint::Reversal,
+        Endpoint::Express,
+        Endpoint::ExpressQuery,
+        Endpoint::B2bExpressCheckout,
+        Endpoint::DynamicQr,
+        Endpoint::TaxRemittance,
+        Endpoint::StandingOrder,
+        Endpoint::BillManagerOptIn,
+        Endpoint::BillManagerInvoice,
+        Endpoint::BillManagerReconciliation,
+    ];
+
+    /// Path of the endpoint, starting with a `/`
+    pub fn path(&self) -> &'static str {
+        match self {
+            Endpoint::Auth => "/oauth/v1/generate",
+            Endpoint::B2b => "/mpesa/b2b/v1/paymentrequest",
+            Endpoint::B2c => "/mpesa/b2c/v1/paymentrequest",
+            Endpoint::C2bRegister => "/mpesa/c2b/v1/registerurl",
+            Endpoint::C2bSimulate => "/mpesa/c2b/v1/simulate",
+            Endpoint::AccountBalance => "/mpesa/accountbalance/v1/query",
+            Endpoint::TransactionStatus => "/mpesa/transactionstatus/v1/query",
+            Endpoint::Reversal => "/mpesa/reversal/v1/request",
+            Endpoint::Express => "/mpesa/stkpush/v1/processrequest",
+            Endpoint::ExpressQuery => "/mpesa/stkpushquery/v1/query",
+            Endpoint::B2bExpressCheckout => "/v1/ussdpush/get-msisdn",
+            Endpoint::DynamicQr => "/mpesa/qrcode/v1/generate",
+            Endpoint::TaxRemittance => "/mpesa/b2b/v1/remittax",
+            Endpoint::StandingOrder => "/standingorder/v1/createStandingOrderExternal",
+            Endpoint::BillManagerOptIn => "/v1/billmanager-invoice/optin",
+            Endpoint::BillManagerInvoice => "/v1/billmanager-invoice/single-invoicing",
+            Endpoint::BillManagerReconciliation => "/v1/billmanager-invoice/reconciliation",
+        }
+    }
+
+    /// Full url of the endpoint, joining its path to `base_url`, e.g. `Environment::base_url()`
+    pub fn url(&self, base_url: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), self.path())
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.path())
+    }
+}
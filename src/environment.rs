@@ -1,31 +1,66 @@
-///! # environment
-///! Code related to setting up the desired Safaricom API environment
+//! # environment
+//! Code related to setting up the desired Safaricom API environment
 
+use std::error::Error;
+use std::fmt::{Display,Formatter,Result as FmtResult};
 use std::str::FromStr;
 
-#[derive(Debug)]
+/// Base url of the production API
+pub const PRODUCTION_BASE_URL: &str = "https://api.safaricom.co.ke";
+
+/// Base url of the sandbox API
+pub const SANDBOX_BASE_URL: &str = "https://sandbox.safaricom.co.ke";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Enum to map to desired environment so as to access certificate
 /// and the base url
 /// Required to construct a new `Mpesa` struct
 pub enum Environment {
     Production,
     Sandbox,
+    /// Custom base url, e.g. a local mock server. Has no bundled certificate, see `certificate`.
+    Custom(String),
 }
 
 impl FromStr for Environment {
-    type Err = String;
+    type Err = ParseEnvironmentError;
 
+    /// Parses `"production"` or `"sandbox"`, ignoring case
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.to_lowercase().as_str() {
             "production" => Ok(Self::Production),
             "sandbox" => Ok(Self::Sandbox),
-            _ => Err(String::from("error"))
+            _ => Err(ParseEnvironmentError(s.to_string())),
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Environment::Production => write!(f, "production"),
+            Environment::Sandbox => write!(f, "sandbox"),
+            Environment::Custom(url) => write!(f, "{}", url),
         }
     }
 }
 
+/// Error returned when parsing an unknown `Environment`
+#[derive(Debug)]
+pub struct ParseEnvironmentError(String);
+
+impl Display for ParseEnvironmentError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "unknown environment: {}", self.0)
+    }
+}
+
+impl Error for ParseEnvironmentError {}
+
 impl Environment {
-    /// Matches to intended base_url depending on Environment variant
+    /// Matches to intended base_url depending on Environment variant:
+    /// `PRODUCTION_BASE_URL`, `SANDBOX_BASE_URL`, or the custom url without its trailing slash.
+    /// Never allocates.
     /// 
     /// ## Example
     /// ```
@@ -34,19 +69,52 @@ impl Environment {
     /// let env: Environment = Environment::Production;
     /// let base_url: &str = env.base_url();
     /// assert_eq!("https://api.safaricom.co.ke", base_url);
+    ///
+    /// let env: Environment = Environment::Custom(String::from("http://127.0.0.1:8080/"));
+    /// assert_eq!("http://127.0.0.1:8080", env.base_url());
     /// ```
-    pub fn base_url(&self) -> &'static str{
+    pub fn base_url(&self) -> &str {
         match self {
-            Environment::Production => "https://api.safaricom.co.ke",
-            Environment::Sandbox => "https://sandbox.safaricom.co.ke",
+            Environment::Production => PRODUCTION_BASE_URL,
+            Environment::Sandbox => SANDBOX_BASE_URL,
+            Environment::Custom(url) => url.trim_end_matches('/'),
         }
     }
 
-    /// Match to X509 public key certificate based on
-    /// environment variant
-     pub fn get_certificate(&self) -> &'static str {
+    /// The bundled M-Pesa public key certificate used to generate security credentials,
+    /// which differs between the sandbox and production.
+    ///
+    /// `Custom` environments have no bundled certificate: set one with
+    /// `MpesaBuilder::security_certificate`, otherwise generating security credentials
+    /// fails with a `MpesaError::EncryptionError`.
+    ///
+    /// ## Example
+    /// ```
+    /// use mpesa::Environment;
+    /// use mpesa::environment::{PRODUCTION_CERTIFICATE, SANDBOX_CERTIFICATE};
+    ///
+    /// assert_eq!(Environment::Production.certificate(), Some(PRODUCTION_CERTIFICATE));
+    /// assert_eq!(Environment::Sandbox.certificate(), Some(SANDBOX_CERTIFICATE));
+    /// assert_eq!(Environment::Custom(String::from("http://127.0.0.1:8080")).certificate(), None);
+    /// ```
+    pub fn certificate(&self) -> Option<&'static str> {
         match self {
-            Environment::Production => r#"-----BEGIN CERTIFICATE-----
+            Environment::Production => Some(PRODUCTION_CERTIFICATE),
+            Environment::Sandbox => Some(SANDBOX_CERTIFICATE),
+            Environment::Custom(_) => None,
+        }
+    }
+
+    /// Match to X509 public key certificate based on
+    /// environment variant. `Custom` environments get the sandbox certificate.
+    #[deprecated(note = "use `Environment::certificate` instead, which has no certificate for `Custom` environments")]
+    pub fn get_certificate(&self) -> &'static str {
+        self.certificate().unwrap_or(SANDBOX_CERTIFICATE)
+    }
+}
+
+/// M-Pesa public key certificate of the production environment, PEM encoded
+pub const PRODUCTION_CERTIFICATE: &str = r#"-----BEGIN CERTIFICATE-----
 MIIGkzCCBXugAwIBAgIKXfBp5gAAAD+hNjANBgkqhkiG9w0BAQsFADBbMRMwEQYK
 CZImiZPyLGQBGRYDbmV0MRkwFwYKCZImiZPyLGQBGRYJc2FmYXJpY29tMSkwJwYD
 VQQDEyBTYWZhcmljb20gSW50ZXJuYWwgSXNzdWluZyBDQSAwMjAeFw0xNzA0MjUx
@@ -84,8 +152,10 @@ dqAmj8WYkYdWIHQlkKFP9ba0RJv7aBKb8/KP+qZ5hJip0I5Ey6JJ3wlEWRWUYUKh
 gYoPHrJ92ToadnFCCpOlLKWc0xVxANofy6fqreOVboPO0qTAYpoXakmgeRNLUiar
 0ah6M/q/KA==
 -----END CERTIFICATE-----
-"#,
-            Environment::Sandbox => r#"-----BEGIN CERTIFICATE-----
+"#;
+
+/// M-Pesa public key certificate of the sandbox environment, PEM encoded
+pub const SANDBOX_CERTIFICATE: &str = r#"-----BEGIN CERTIFICATE-----
 MIIGKzCCBROgAwIBAgIQDL7NH8cxSdUpl0ihH0A1wTANBgkqhkiG9w0BAQsFADBN
 MQswCQYDVQQGEwJVUzEVMBMGA1UEChMMRGlnaUNlcnQgSW5jMScwJQYDVQQDEx5E
 aWdpQ2VydCBTSEEyIFNlY3VyZSBTZXJ2ZXIgQ0EwHhcNMTgwODI3MDAwMDAwWhcN
@@ -120,7 +190,4 @@ gh7AkKnqcf+g8F/MvCCVdNAiVMdz6UpCscf6BRPHNZ5ifvChGh7aUKjrVLLuF4Ls
 HE05qm6HNyV5eTa6wvcbc4ewguN1UDZvPWetSyfBk10Wbpor4znQ4TJ3Y9uCvsJH
 41ldblDvZZ2z4kB2UYQ7iBkPlJSxSOaFgW/GGDXq49sz/995xzhVITHxh2SdLkI=
 -----END CERTIFICATE-----
- "#,
-        }
-    }
-}
\ No newline at end of file
+ "#;
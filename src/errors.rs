@@ -0,0 +1,180 @@
+use std::fmt::{Display,Formatter,Result as FmtResult};
+use std::time::Duration;
+use crate::{CommandId,ShortCodeError};
+
+/// Mpesa error stack
+///
+/// Matches on it need a wildcard arm: the variants depend on the enabled features, e.g.
+/// `NetworkError` only exists with the `client` feature, and more may be added.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MpesaError {
+    /// The API responded with an error body or a non-zero `ResponseCode`
+    ApiError { code: String, message: String },
+    /// The OAuth endpoint rejected the client key and secret
+    Authentication { code: String, message: String },
+    /// Sending the request or reading the response failed
+    #[cfg(feature = "client")]
+    NetworkError(reqwest::Error),
+    /// The response body could not be deserialized
+    ParseError(serde_json::Error),
+    /// The API responded with a body that is not JSON, e.g. an HTML maintenance page,
+    /// or with an error of an unknown shape. Longer bodies are cut after 512 bytes.
+    UnexpectedResponse { status: u16, body: String },
+    /// Security credentials could not be generated
+    EncryptionError(String),
+    /// A required field was not set or was empty
+    MissingField(&'static str),
+    /// The client could not be configured from environment variables
+    EnvironmentError(String),
+    /// A callback url is not a valid `https` url
+    InvalidUrl(String),
+    /// The amount is outside the limits of the API it was sent to, see `MpesaBuilder::limits`
+    AmountOutOfRange { min: u32, max: u32 },
+    /// A short code or till number is not made of 5 to 7 digits
+    InvalidShortCode(String),
+    /// The command id is not accepted by the API it was sent to
+    InvalidCommand(CommandId),
+    /// An originator conversation id is too long or holds characters M-Pesa rejects,
+    /// see `ids::is_valid_originator_id`
+    InvalidOriginatorId(String),
+    /// A polling helper gave up before the API returned a final result
+    Timeout,
+    /// The API responded with `429 Too Many Requests`, with the delay it asked for
+    /// in its `Retry-After` header, if any
+    RateLimited { retry_after: Option<Duration> },
+    /// The API only exists in the sandbox, e.g. C2B simulate, and cannot be called in production
+    SandboxOnly(&'static str),
+    /// Several fields of a request or builder are missing or invalid. A single problem is
+    /// reported as its own error instead, e.g. `MpesaError::MissingField`.
+    Validation(Vec<FieldError>),
+    /// The API rejected a request as a duplicate of one it already received with the same
+    /// originator conversation id, e.g. a retried B2C payment. The first request was not
+    /// rejected: wait for its result callback instead of handling this as a failed payment.
+    DuplicateRequest { originator_conversation_id: String, message: String },
+    /// A transaction status result shows the transaction cannot be reversed, e.g. it failed,
+    /// was already reversed or was not received by the party asking for the reversal
+    NotReversible(String),
+    /// A payment of a batch reported no result, e.g. because the thread sending it panicked.
+    /// It may have been sent: check its originator conversation id before retrying it.
+    MissingResult(usize),
+}
+
+/// A missing or invalid field, reported in `MpesaError::Validation`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The builder field, e.g. `"result_url"`
+    pub field: &'static str,
+    /// What is wrong with it, e.g. `"missing"`
+    pub message: String,
+}
+
+impl FieldError {
+    /// Describes `error`, found validating `field`
+    #[cfg(feature = "client")]
+    pub(crate) fn new(field: &'static str, error: &MpesaError) -> Self {
+        let message = match error {
+            MpesaError::MissingField(_) => String::from("missing"),
+            error => error.to_string(),
+        };
+
+        Self { field, message }
+    }
+}
+
+impl MpesaError {
+    /// Whether Safaricom could not be reached or is not serving requests, e.g. during an
+    /// outage or maintenance, rather than rejecting the request or the credentials: a network
+    /// error, a `429 Too Many Requests` or an unexpected response with a server error status
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            #[cfg(feature = "client")]
+            MpesaError::NetworkError(_) => true,
+            MpesaError::RateLimited { .. } => true,
+            MpesaError::UnexpectedResponse { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl Display for MpesaError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            MpesaError::ApiError { code, message } => write!(f, "Api error {}: {}", code, message),
+            MpesaError::Authentication { code, message } => write!(f, "Authentication failed {}: {}", code, message),
+            #[cfg(feature = "client")]
+            MpesaError::NetworkError(e) => write!(f, "Network error: {}", e),
+            MpesaError::ParseError(e) => write!(f, "Error parsing response: {}", e),
+            MpesaError::UnexpectedResponse { status, body } => write!(f, "Unexpected response ({}): {}", status, body),
+            MpesaError::EncryptionError(e) => write!(f, "Error generating security credentials: {}", e),
+            MpesaError::MissingField(field) => write!(f, "Missing required field: {}", field),
+            MpesaError::EnvironmentError(e) => write!(f, "Environment error: {}", e),
+            MpesaError::InvalidUrl(e) => write!(f, "Invalid url {}", e),
+            MpesaError::AmountOutOfRange { min, max } => write!(f, "Amount must be between {} and {}", min, max),
+            MpesaError::InvalidShortCode(e) => write!(f, "Invalid short code {}", e),
+            MpesaError::InvalidCommand(command_id) => write!(f, "Command id {} is not valid for this API", command_id),
+            MpesaError::InvalidOriginatorId(id) => write!(f, "Invalid originator conversation id {}", id),
+            MpesaError::Timeout => write!(f, "Timed out waiting for a final result"),
+            MpesaError::RateLimited { retry_after: Some(retry_after) } => write!(f, "Rate limited, retry after {}s", retry_after.as_secs()),
+            MpesaError::RateLimited { retry_after: None } => write!(f, "Rate limited"),
+            MpesaError::SandboxOnly(api) => write!(f, "{} is only available in the sandbox", api),
+            MpesaError::DuplicateRequest { originator_conversation_id, message } => {
+                write!(f, "Duplicate request {}: {}", originator_conversation_id, message)
+            }
+            MpesaError::NotReversible(reason) => write!(f, "Transaction cannot be reversed: {}", reason),
+            MpesaError::MissingResult(index) => write!(f, "No result was reported for payment {} of the batch", index),
+            MpesaError::Validation(errors) => {
+                write!(f, "Invalid request:")?;
+                for (index, error) in errors.iter().enumerate() {
+                    write!(f, "{} {}", if index == 0 { "" } else { ";" }, error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MpesaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "client")]
+            MpesaError::NetworkError(e) => Some(e),
+            MpesaError::ParseError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl From<reqwest::Error> for MpesaError {
+    fn from(e: reqwest::Error) -> Self {
+        MpesaError::NetworkError(e)
+    }
+}
+
+impl From<serde_json::Error> for MpesaError {
+    fn from(e: serde_json::Error) -> Self {
+        MpesaError::ParseError(e)
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for MpesaError {
+    fn from(e: Box<dyn std::error::Error>) -> Self {
+        match e.downcast::<MpesaError>() {
+            Ok(e) => *e,
+            Err(e) => MpesaError::EncryptionError(e.to_string()),
+        }
+    }
+}
+
+impl From<ShortCodeError> for MpesaError {
+    fn from(e: ShortCodeError) -> Self {
+        MpesaError::InvalidShortCode(e.to_string())
+    }
+}
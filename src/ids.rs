@@ -0,0 +1,43 @@
+//! # ids
+//! Generates and checks the `OriginatorConversationID` of requests
+
+use uuid::Uuid;
+
+/// Longest `OriginatorConversationID` M-Pesa accepts. Longer ids are rejected, or
+/// truncated so the result callback can no longer be matched to the request.
+pub const MAX_ORIGINATOR_ID_LEN: usize = 36;
+
+/// A new random `OriginatorConversationID`: a hyphenated lowercase UUID v4,
+/// e.g. `"feb5e3f2-fbbc-4745-844c-ee37b546f627"`.
+///
+/// Used by the b2c and b2b requests and `b2c_batch` when no id is set.
+///
+/// ## Example
+/// ```
+/// use mpesa::ids::{is_valid_originator_id, new_originator_id};
+///
+/// let id = new_originator_id();
+/// assert_eq!(id.len(), 36);
+/// assert!(is_valid_originator_id(&id));
+/// assert_ne!(id, new_originator_id());
+/// ```
+pub fn new_originator_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Whether `id` can be sent as an `OriginatorConversationID`: 1 to `MAX_ORIGINATOR_ID_LEN`
+/// ASCII letters, digits, hyphens or underscores, e.g. `"16740-34861180-1"`.
+///
+/// ## Example
+/// ```
+/// use mpesa::ids::is_valid_originator_id;
+///
+/// assert!(is_valid_originator_id("16740-34861180-1"));
+/// assert!(!is_valid_originator_id(""));
+/// assert!(!is_valid_originator_id("order 1"));
+/// assert!(!is_valid_originator_id(&"a".repeat(37)));
+/// ```
+pub fn is_valid_originator_id(id: &str) -> bool {
+    (1..=MAX_ORIGINATOR_ID_LEN).contains(&id.len())
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
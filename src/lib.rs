@@ -19,6 +19,11 @@
 //! use mpesa::Mpesa;
 //! ```
 //!
+//! Or import the clients, enums and other common types at once with the prelude:
+//! ```rs
+//! use mpesa::prelude::*;
+//! ```
+//!
 //! ## Examples
 //!
 //! Use [`dotenv`](https://docs.rs/dotenv/0.15.0/dotenv/fn.dotenv.html) crate to store your keys as environmental variables instead of hard coding them like done in the example below.
@@ -34,6 +39,61 @@
 //! );
 //! ```
 //!
+//! Alternatively, use the builder, which checks that no field is missing or empty:
+//!
+//! ```rs
+//! use mpesa::{Mpesa, Environment};
+//!
+//! let client = Mpesa::builder()
+//!       .client_key("your_client_key")
+//!       .client_secret("your_client_secret")
+//!       .environment(Environment::Sandbox)
+//!       .initiator_password("your_initiator_password")
+//!       .build()
+//!       .unwrap();
+//! ```
+//!
+//! Or read `CLIENT_KEY`, `CLIENT_SECRET`, `INIT_PASSWORD` and `MPESA_ENVIRONMENT` from the environment:
+//!
+//! ```rs
+//! let client = mpesa::Mpesa::from_env().unwrap();
+//! ```
+//!
+//! ## Logging
+//!
+//! Enable the `tracing` feature to emit a [`tracing`](https://docs.rs/tracing) span for every API call, with its endpoint and masked short codes, and events for token refreshes, retries and error responses. Credentials, access tokens and passkeys are never logged.
+//!
+//! ```md
+//! [dependencies]
+//! mpesa = { version = "0.1.5", features = ["tracing"] }
+//! ```
+//!
+//! ## Payloads without the client
+//!
+//! The blocking and async clients are behind the default `client` feature. Disable default features to build only the payloads, callbacks and signing helpers, without `reqwest` or `tokio`, e.g. to build and sign requests in one service and send them from another. Every payload has a `to_json` method returning the JSON body it is sent as.
+//!
+//! ```md
+//! [dependencies]
+//! mpesa = { version = "0.1.5", default-features = false }
+//! ```
+//!
+//! ## Securing callback urls
+//!
+//! Callback urls are public, so anyone can post a forged result to them. Check that a callback comes from Safaricom with `callbacks::CallbackAllowList::safaricom()`, which trusts the documented `SAFARICOM_CALLBACK_IPS`, and add a random secret path segment to your callback urls, checked with `callbacks::secret_matches`.
+//!
+//! ## Masking customer data
+//!
+//! Phone numbers and account references are masked in the `Debug` output of payloads and callbacks and when a `PhoneNumber` is displayed, e.g. `2547****4149`, so they do not leak into logs. `masked::Masked` masks other values the same way. To see them in full while debugging against the sandbox, enable the `unmasked-pii` feature; never enable it in a production build.
+//!
+//! ## Testing against mock servers
+//!
+//! The `danger-accept-invalid-certs` feature adds `MpesaBuilder::danger_accept_invalid_certs`, which disables TLS certificate verification so the client can talk to local mock servers with self-signed certificates. It is refused for `Environment::Production`; never enable this feature in a production build.
+//!
+//! ```md
+//! [dev-dependencies]
+//! mpesa = { version = "0.1.5", features = ["danger-accept-invalid-certs"] }
+//! ```
+//!
 //! ## Author
 //!
 //! **Collins Muriuki**
@@ -41,14 +101,67 @@
 //! ## License
 //! This project is MIT licensed
 
+// the API methods take the request fields positionally, mirroring the payloads
+#![allow(clippy::too_many_arguments)]
+
+#[cfg(feature = "client")]
+#[macro_use]
+mod logging;
+#[cfg(feature = "client")]
 mod client;
+#[cfg(feature = "client")]
+mod async_client;
+#[cfg(feature = "client")]
+mod builder;
+#[cfg(feature = "client")]
+mod retry;
+#[cfg(feature = "client")]
+mod validation;
+#[macro_use]
+pub mod masked;
 pub mod environment;
 pub mod payloads;
+pub mod callbacks;
 pub mod constants;
 pub mod mpesa_security;
+pub mod security;
+pub mod errors;
+pub mod endpoints;
+pub mod ids;
+pub mod phone_number;
+pub mod short_code;
+pub mod token;
+pub mod amount;
+pub mod clock;
+pub mod limits;
+pub mod recording;
+pub mod signed_request;
+#[cfg(feature = "client")]
+pub mod metrics;
+pub mod prelude;
 
+#[cfg(feature = "client")]
 pub use client::Mpesa;
-pub use environment::Environment;
-pub use constants::{CommandId,IdentifierTypes};
-pub use payloads::ResponseType;
+#[cfg(feature = "client")]
+pub use async_client::MpesaAsync;
+#[cfg(feature = "client")]
+pub use builder::{MpesaBuilder,DEFAULT_POOL_IDLE_TIMEOUT,DEFAULT_TIMEOUT,DEFAULT_USER_AGENT};
+pub use environment::{Environment,ParseEnvironmentError};
+pub use constants::{CommandId,IdentifierTypes,ParseCommandIdError,ParseIdentifierTypeError,ResultCode};
+pub use payloads::{CommonResponse,MpesaResult,ResponseType,ParseResponseTypeError,QrTransactionType};
+pub use payloads::{Frequency,StandingOrderTransactionType};
+pub use payloads::InvoiceItem;
 pub use mpesa_security::MpesaSecurity;
+pub use errors::{FieldError,MpesaError};
+pub use phone_number::{PhoneNumber,PhoneNumberError};
+pub use short_code::{ShortCode,ShortCodeError};
+pub use token::AccessToken;
+pub use amount::{Amount,AmountError};
+pub use recording::RecordedRequest;
+pub use signed_request::SignedRequest;
+#[cfg(feature = "client")]
+pub use metrics::RequestMetrics;
+#[cfg(feature = "client")]
+pub use reqwest::Proxy;
+#[cfg(feature = "client")]
+pub use reqwest::header::{HeaderName,HeaderValue};
\ No newline at end of file
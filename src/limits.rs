@@ -0,0 +1,63 @@
+//! Per-transaction amount limits of each API, checked before a request is sent.
+//!
+//! The defaults are Safaricom's documented limits, which apply to both the sandbox and
+//! production. Safaricom adjusts limits per agreement; set the ones that apply to your
+//! organization with `MpesaBuilder::limits`.
+
+use crate::Amount;
+
+/// Smallest and largest amount accepted in a single transaction, in shillings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Limit {
+    /// Constructs a new `Limit` of `min..=max` shillings
+    pub const fn new(min: u32, max: u32) -> Self {
+        Self { min, max }
+    }
+
+    /// Whether `amount` is within the limit
+    pub fn contains(&self, amount: Amount) -> bool {
+        (self.min..=self.max).contains(&amount.value())
+    }
+}
+
+/// Amount limits of every API that moves money
+///
+/// ## Example
+/// ```
+/// use mpesa::limits::{Limit, Limits};
+///
+/// let limits = Limits {
+///     b2c: Limit::new(50, 70_000),
+///     ..Limits::default()
+/// };
+/// assert_eq!(limits.express, Limits::default().express);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// B2C payments: `b2c`, `pay_salary`, `pay_promotion` and `business_payment`
+    pub b2c: Limit,
+    /// B2B payments: `b2b`
+    pub b2b: Limit,
+    /// Simulated C2B payments: `c2b_simulate` and `c2b_simulate_batch`
+    pub c2b: Limit,
+    /// Lipa na M-Pesa online payments: `express_request`
+    pub express: Limit,
+}
+
+impl Default for Limits {
+    /// B2C payments start at 10 shillings; every other API at 1 shilling.
+    /// All of them are capped at `Amount::MAX`.
+    fn default() -> Self {
+        Self {
+            b2c: Limit::new(10, Amount::MAX),
+            b2b: Limit::new(Amount::MIN, Amount::MAX),
+            c2b: Limit::new(Amount::MIN, Amount::MAX),
+            express: Limit::new(Amount::MIN, Amount::MAX),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Helpers for the optional `tracing` feature.
+//!
+//! The macros below forward to `tracing` when the feature is enabled and expand to
+//! nothing otherwise, so call sites need no `cfg` attributes of their own.
+//! Secrets (initiator password, security credentials, access tokens, passkeys)
+//! must never be passed to them.
+
+/// Emits a `tracing::debug!` event when the `tracing` feature is enabled
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!($($arg)*);
+        }
+    };
+}
+
+/// Emits a `tracing::warn!` event when the `tracing` feature is enabled
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::warn!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "tracing")]
+use crate::masked::Masked;
+
+/// Fields of a request body holding secrets, replaced by `redact_body`
+#[cfg(feature = "tracing")]
+const SECRET_FIELDS: &[&str] = &["SecurityCredential", "Password"];
+
+/// Fields of a request body holding phone numbers or account references, masked by
+/// `redact_body` like the `Debug` impls of the payloads. `PartyA` and `PartyB` hold a
+/// phone number in some requests and a short code in others, and are always masked.
+#[cfg(feature = "tracing")]
+const PERSONAL_FIELDS: &[&str] = &[
+    "PartyA",
+    "PartyB",
+    "PhoneNumber",
+    "Msisdn",
+    "BillRefNumber",
+    "AccountReference",
+    "phoneNumber",
+    "billedPhoneNumber",
+    "officialContact",
+    "accountReference",
+];
+
+/// Copies a request body with its security credentials and STK push password redacted,
+/// and its phone numbers and account references masked with `Masked`
+#[cfg(feature = "tracing")]
+pub(crate) fn redact_body(body: &serde_json::Value) -> serde_json::Value {
+    let mut body = body.clone();
+    if let Some(fields) = body.as_object_mut() {
+        for field in SECRET_FIELDS {
+            if let Some(value) = fields.get_mut(*field) {
+                *value = serde_json::Value::from("[redacted]");
+            }
+        }
+        for field in PERSONAL_FIELDS {
+            if let Some(value) = fields.get_mut(*field) {
+                let masked = match value {
+                    serde_json::Value::String(value) => Masked(value.as_str()).to_string(),
+                    serde_json::Value::Number(value) => Masked(value.to_string()).to_string(),
+                    _ => continue,
+                };
+                *value = serde_json::Value::from(masked);
+            }
+        }
+    }
+    body
+}
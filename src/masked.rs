@@ -0,0 +1,68 @@
+use std::fmt::{Debug,Display,Formatter,Result as FmtResult};
+
+/// Formats a phone number, bill reference or other personal value with its middle
+/// masked, e.g. `2547****4149`, so it can be logged without leaking customer data.
+///
+/// The `Debug` impls of payloads and callbacks holding phone numbers or account
+/// references, and both the `Debug` and `Display` impls of `PhoneNumber`, mask them
+/// this way. Enable the `unmasked-pii` feature to print them in full, e.g. when
+/// debugging against the sandbox; never enable it in a production build.
+///
+/// # Example
+/// ```
+/// use mpesa::masked::Masked;
+///
+/// # #[cfg(not(feature = "unmasked-pii"))]
+/// assert_eq!(Masked("254708374149").to_string(), "2547****4149");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Masked<T>(pub T);
+
+impl<T: AsRef<str>> Display for Masked<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if cfg!(feature = "unmasked-pii") {
+            f.write_str(self.0.as_ref())
+        } else {
+            f.write_str(&mask_middle(self.0.as_ref()))
+        }
+    }
+}
+
+impl<T: AsRef<str>> Debug for Masked<T> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+/// Keeps up to four characters at each end of `value`, and a third of it at most,
+/// so that short values are mostly masked too
+fn mask_middle(value: &str) -> String {
+    let length = value.chars().count();
+    let visible = (length / 3).min(4);
+
+    value
+        .chars()
+        .enumerate()
+        .map(|(index, c)| if index < visible || index >= length - visible { c } else { '*' })
+        .collect()
+}
+
+/// Implements `Debug` for a struct, formatting the fields marked `: masked` with `Masked`.
+/// Every field must be listed, in declaration order.
+macro_rules! masked_debug {
+    (@value $value:expr) => {
+        &$value
+    };
+    (@value $value:expr, masked) => {
+        &$crate::masked::Masked(&$value)
+    };
+    ($name:ident $(<$lifetime:lifetime>)? { $($field:ident $(: $masked:ident)?),* $(,)? }) => {
+        impl$(<$lifetime>)? std::fmt::Debug for $name$(<$lifetime>)? {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), masked_debug!(@value self.$field $(, $masked)?)))*
+                    .finish()
+            }
+        }
+    };
+}
@@ -0,0 +1,44 @@
+//! Per-request metrics reported to the hook set with `MpesaBuilder::on_request_complete`
+
+use std::fmt::{Debug,Formatter,Result as FmtResult};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Metrics of a single API call, including OAuth token requests, reported once the
+/// call completed or failed after any retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestMetrics {
+    /// Path of the endpoint, e.g. `/mpesa/b2c/v1/paymentrequest`
+    pub endpoint: String,
+    /// Time from the first attempt until the last response or error, including retries
+    pub duration: Duration,
+    /// HTTP status of the last response, `None` if no response was received
+    pub status: Option<u16>,
+    /// Whether the last response had a 2xx status. The body may still hold an API error.
+    pub success: bool,
+    /// Number of retries after the first attempt
+    pub retries: u32,
+}
+
+/// Hook called with the metrics of every request
+#[derive(Clone)]
+pub(crate) struct MetricsHook(Arc<dyn Fn(&RequestMetrics) + Send + Sync>);
+
+impl MetricsHook {
+    pub(crate) fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&RequestMetrics) + Send + Sync + 'static,
+    {
+        MetricsHook(Arc::new(hook))
+    }
+
+    pub(crate) fn report(&self, metrics: &RequestMetrics) {
+        (self.0)(metrics)
+    }
+}
+
+impl Debug for MetricsHook {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("MetricsHook")
+    }
+}
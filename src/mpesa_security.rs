@@ -5,6 +5,8 @@ pub trait MpesaSecurity {
     /// Generates security credentials
     /// M-Pesa Core authenticates a transaction by decrypting the security credentials.
     /// Security credentials are generated by encrypting the base64 encoded initiator password with M-Pesa’s public key, a X509 certificate.
-    /// Returns base64 encoded string.
+    /// Returns base64 encoded string, generated once and reused by every request.
+    /// A credential set with `MpesaBuilder::security_credential` is returned as is instead.
+    /// Fails with `MpesaError::MissingField("initiator_password")` if the client has neither.
     fn gen_security_credentials(&self) -> Result<String, Box<dyn Error>>;
 }
\ No newline at end of file
@@ -1,25 +1,35 @@
-use serde::Deserialize;
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
 use crate::{CommandId,IdentifierTypes};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct AccountBalancePayload<'a> {
+    #[serde(rename = "Initiator")]
     pub initiator_name: &'a str,
+    #[serde(rename = "SecurityCredential")]
     pub security_credentials: &'a str,
+    #[serde(rename = "CommandID")]
     pub command_id: CommandId,
+    #[serde(rename = "PartyA")]
     pub party_a: &'a str,
+    #[serde(rename = "IdentifierType")]
     pub identifier_type: IdentifierTypes,
+    #[serde(rename = "Remarks")]
     pub remarks: &'a str,
+    #[serde(rename = "QueueTimeOutURL")]
     pub queue_timeout_url: &'a str,
+    #[serde(rename = "ResultURL")]
     pub result_url: &'a str,
 }
 
-/// B2C response
+/// Account balance response
 /// Field names deliberately in Pascal case to correctly deserialize the
 /// response data
-#[derive(Debug,Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AccountBalanceResponse {
     pub OriginatorConversationID: String,
     pub ConversationID: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub ResponseCode: String,
     pub ResponseDescription: String,
 }
\ No newline at end of file
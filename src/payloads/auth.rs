@@ -1,8 +1,27 @@
-use serde::Deserialize;
+use serde::{Deserialize,Deserializer,Serialize};
+use serde_json::Value;
 
-#[derive(Debug,Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Response from calling the Safaricom OAuth endpoint
 pub struct AuthResponse {
     pub access_token: String,
-    expires_in: String,
-}
\ No newline at end of file
+    /// Seconds until the token expires. Sent as a string by the API; defaults to an hour
+    /// if missing or malformed.
+    #[serde(default = "default_expires_in", deserialize_with = "deserialize_expires_in")]
+    pub expires_in: u64,
+}
+
+/// Tokens are documented to last an hour
+fn default_expires_in() -> u64 {
+    3600
+}
+
+fn deserialize_expires_in<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let expires_in = match Value::deserialize(deserializer)? {
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    };
+
+    Ok(expires_in.unwrap_or_else(default_expires_in))
+}
@@ -1,29 +1,46 @@
-use serde::Deserialize;
-use crate::CommandId;
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use crate::{Amount,CommandId,IdentifierTypes};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct B2bPayload<'a> {
+    #[serde(rename = "OriginatorConversationID")]
+    pub originator_conversation_id: String,
+    #[serde(rename = "Initiator")]
     pub initiator_name: &'a str,
+    #[serde(rename = "SecurityCredential")]
     pub security_credentials: &'a str,
+    #[serde(rename = "CommandID")]
     pub command_id: CommandId,
-    pub amount: u32,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "PartyA")]
     pub party_a: &'a str,
-    pub sender_id: u32,
+    #[serde(rename = "SenderIdentifierType")]
+    pub sender_id: IdentifierTypes,
+    #[serde(rename = "PartyB")]
     pub party_b: &'a str,
-    pub receiver_id: u32,
+    // The API expects this misspelling; `ReceiverIdentifierType` is silently ignored
+    #[serde(rename = "RecieverIdentifierType")]
+    pub receiver_id: IdentifierTypes,
+    #[serde(rename = "Remarks")]
     pub remarks: &'a str,
+    #[serde(rename = "QueueTimeOutURL")]
     pub queue_timeout_url: &'a str,
+    #[serde(rename = "ResultURL")]
     pub result_url: &'a str,
+    #[serde(rename = "AccountReference")]
     pub account_ref: &'a str,
 }
 
-#[derive(Debug,Deserialize)]
-/// B2C response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// B2B response
 /// Field names deliberately in Pascal case to correctly deserialize the
 /// response data
 pub struct B2bResponse {
     pub ConversationID: String,
     pub OriginatorConversationID: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub ResponseCode: String,
     pub ResponseDescription: String,
 }
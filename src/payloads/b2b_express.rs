@@ -0,0 +1,31 @@
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+
+#[derive(Debug, Serialize)]
+/// Payload to initiate a B2B express checkout (USSD push) to a merchant's till:
+/// See https://developer.safaricom.co.ke/APIs/B2BExpressCheckout for a
+/// detailed description of each field.
+pub struct B2bExpressCheckoutPayload<'a> {
+    #[serde(rename = "primaryShortCode")]
+    pub primary_short_code: &'a str,
+    #[serde(rename = "receiverShortCode")]
+    pub receiver_short_code: &'a str,
+    #[serde(rename = "amount")]
+    pub amount: &'a str,
+    #[serde(rename = "paymentRef")]
+    pub payment_ref: &'a str,
+    #[serde(rename = "callbackUrl")]
+    pub callback_url: &'a str,
+    #[serde(rename = "partnerName")]
+    pub partner_name: &'a str,
+    #[serde(rename = "RequestRefID")]
+    pub request_ref_id: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// B2B express checkout response
+pub struct B2bExpressCheckoutResponse {
+    #[serde(deserialize_with = "string_or_number")]
+    pub code: String,
+    pub status: String,
+}
@@ -1,30 +1,394 @@
-use serde::{Deserialize};
-use crate::CommandId;
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use super::{MpesaErrorResponse,MpesaResult};
+use crate::{Amount,CommandId,MpesaError};
+#[cfg(feature = "client")]
+use crate::ids;
+#[cfg(feature = "client")]
+use crate::{Environment,PhoneNumber,ShortCode};
+#[cfg(feature = "client")]
+use crate::limits::Limits;
+#[cfg(feature = "client")]
+use crate::validation::{self,Report};
 
-#[derive(Debug)]
+#[derive(Serialize)]
 /// Payload to allow for b2c transactions:
 /// See https://developer.safaricom.co.ke/docs#b2c-api for a
 /// detailed description of each field.
 pub struct B2cPayload<'a> {
+    #[serde(rename = "OriginatorConversationID")]
+    pub originator_conversation_id: String,
+    #[serde(rename = "InitiatorName")]
     pub initiator_name: &'a str,
+    #[serde(rename = "SecurityCredential")]
     pub security_credentials: &'a str,
+    #[serde(rename = "CommandID")]
     pub command_id: CommandId,
-    pub amount: u32,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "PartyA")]
     pub party_a: &'a str,
+    #[serde(rename = "PartyB")]
     pub party_b: &'a str,
-    pub remarks: &'a str,
+    #[serde(rename = "Remarks", skip_serializing_if = "Option::is_none")]
+    pub remarks: Option<&'a str>,
+    #[serde(rename = "QueueTimeOutURL")]
     pub queue_timeout_url: &'a str,
+    #[serde(rename = "ResultURL")]
     pub result_url: &'a str,
-    pub occasion: &'a str,
+    #[serde(rename = "Occasion", skip_serializing_if = "Option::is_none")]
+    pub occasion: Option<&'a str>,
 }
 
-#[derive(Debug, Deserialize)]
+masked_debug!(B2cPayload<'a> {
+    originator_conversation_id,
+    initiator_name,
+    security_credentials,
+    command_id,
+    amount,
+    party_a,
+    party_b: masked,
+    remarks,
+    queue_timeout_url,
+    result_url,
+    occasion,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// B2C response
 /// Field names deliberately in Pascal case to correctly deserialize the
 /// response data
 pub struct B2cResponse {
     pub ConversationID: String,
     pub OriginatorConversationID: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub ResponseCode: String,
     pub ResponseDescription: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+/// Either body the B2C API responds with, e.g. to deserialize the response to a request
+/// sent with `send_signed`: a `B2cResponse` once the request reached M-Pesa, or an error
+/// body when it was rejected before, e.g. for an invalid access token.
+///
+/// # Example
+/// ```
+/// use mpesa::payloads::B2cOutcome;
+///
+/// let outcome: B2cOutcome = serde_json::from_str(r#"{
+///     "requestId": "11728-2929992-1",
+///     "errorCode": "401.002.01",
+///     "errorMessage": "Error Occurred - Invalid Access Token - BJGFGOXv5aZnw90KkA4TDtu4Xdyf"
+/// }"#).unwrap();
+///
+/// assert!(matches!(outcome, B2cOutcome::Rejected(_)));
+/// assert!(outcome.into_result().is_err());
+/// ```
+pub enum B2cOutcome {
+    /// The request reached M-Pesa, which may still have refused it with a non-zero `ResponseCode`
+    Accepted(B2cResponse),
+    /// The request was rejected before reaching M-Pesa
+    Rejected(MpesaErrorResponse),
+}
+
+impl B2cOutcome {
+    /// The response of a request accepted for processing, or a `MpesaError::ApiError`
+    /// with the code and message of a rejected one, like the clients return
+    pub fn into_result(self) -> Result<B2cResponse, MpesaError> {
+        match self {
+            B2cOutcome::Accepted(response) => match response.error() {
+                None => Ok(response),
+                Some((code, message)) => Err(MpesaError::ApiError {
+                    code: code.to_string(),
+                    message: message.to_string(),
+                }),
+            },
+            B2cOutcome::Rejected(error) => Err(MpesaError::ApiError {
+                code: error.errorCode,
+                message: error.errorMessage,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+#[derive(Debug)]
+/// Builder for a b2c payment request, returned by `Mpesa::b2c` and `MpesaAsync::b2c`.
+/// Every field but `originator_conversation_id`, `remarks` and `occasion` is required;
+/// `send` fails with `MpesaError::MissingField` naming the field that was not set, or with
+/// `MpesaError::Validation` listing every missing or invalid field if there are several.
+pub struct B2cBuilder<'a, C> {
+    client: &'a C,
+    originator_conversation_id: Option<&'a str>,
+    initiator_name: Option<&'a str>,
+    command_id: Option<CommandId>,
+    amount: Option<Amount>,
+    party_a: Option<&'a str>,
+    party_b: Option<&'a str>,
+    remarks: Option<&'a str>,
+    queue_timeout_url: Option<&'a str>,
+    result_url: Option<&'a str>,
+    occasion: Option<&'a str>,
+    security_credential: Option<&'a str>,
+}
+
+#[cfg(feature = "client")]
+impl<'a, C> B2cBuilder<'a, C> {
+    /// Constructs a new, empty `B2cBuilder` bound to `client`.
+    pub fn new(client: &'a C) -> Self {
+        Self {
+            client,
+            originator_conversation_id: None,
+            initiator_name: None,
+            command_id: None,
+            amount: None,
+            party_a: None,
+            party_b: None,
+            remarks: None,
+            queue_timeout_url: None,
+            result_url: None,
+            occasion: None,
+            security_credential: None,
+        }
+    }
+
+    /// Unique identifier of the request, used by M-Pesa to detect duplicate requests.
+    ///
+    /// Defaults to `ids::new_originator_id()`, so every `send` is a new payment. To retry a
+    /// payment that may have gone through (e.g. after a timeout) without paying twice, generate
+    /// and store the id before the first attempt and set the same id on every retry.
+    /// Requests retried automatically after a server error always reuse the id.
+    /// A retry of a payment M-Pesa already received fails with `MpesaError::DuplicateRequest`.
+    /// See `ids::is_valid_originator_id` for the ids M-Pesa accepts.
+    pub fn originator_conversation_id(mut self, originator_conversation_id: &'a str) -> Self {
+        self.originator_conversation_id = Some(originator_conversation_id);
+        self
+    }
+
+    /// The credential/username used to authenticate the transaction request
+    pub fn initiator_name(mut self, initiator_name: &'a str) -> Self {
+        self.initiator_name = Some(initiator_name);
+        self
+    }
+
+    /// Unique command that specifies the B2C transaction type
+    pub fn command_id(mut self, command_id: CommandId) -> Self {
+        self.command_id = Some(command_id);
+        self
+    }
+
+    /// The amount of money being sent to the customer
+    pub fn amount(mut self, amount: Amount) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// The B2C organization shortcode from which the money is sent.
+    /// It is a `ShortCode` and `party_b` a `PhoneNumber`, so that swapping them does not compile.
+    pub fn party_a(mut self, party_a: &'a ShortCode) -> Self {
+        self.party_a = Some(party_a.as_str());
+        self
+    }
+
+    /// The customer mobile number to receive the amount
+    pub fn party_b(mut self, party_b: &'a PhoneNumber) -> Self {
+        self.party_b = Some(party_b.as_str());
+        self
+    }
+
+    /// Any additional information to be associated with the transaction.
+    /// Optional: omitted from the request if not set.
+    pub fn remarks(mut self, remarks: &'a str) -> Self {
+        self.remarks = Some(remarks);
+        self
+    }
+
+    /// The url that stores information of a timed out transaction
+    pub fn queue_timeout_url(mut self, queue_timeout_url: &'a str) -> Self {
+        self.queue_timeout_url = Some(queue_timeout_url);
+        self
+    }
+
+    /// The url that receives the result of the transaction
+    pub fn result_url(mut self, result_url: &'a str) -> Self {
+        self.result_url = Some(result_url);
+        self
+    }
+
+    /// Any additional information to be associated with the transaction.
+    /// Optional: omitted from the request if not set.
+    pub fn occasion(mut self, occasion: &'a str) -> Self {
+        self.occasion = Some(occasion);
+        self
+    }
+
+    /// A `SecurityCredential` computed outside the crate, e.g. by an HSM, sent instead of the
+    /// one the client generates or was built with, see `MpesaBuilder::security_credential`.
+    pub fn security_credential(mut self, security_credential: &'a str) -> Self {
+        self.security_credential = Some(security_credential);
+        self
+    }
+
+    /// The client this request will be sent with
+    pub(crate) fn client(&self) -> &'a C {
+        self.client
+    }
+
+    /// The credential set with `security_credential`, if any
+    pub(crate) fn supplied_security_credential(&self) -> Option<&'a str> {
+        self.security_credential
+    }
+
+    /// Checks every field at once: that the required ones were set, that the originator
+    /// conversation id, if set, is a valid id, that the callback urls
+    /// are valid https urls, that the command id is a b2c command id and that the amount
+    /// is within the b2c `limits`
+    pub(crate) fn validate(&self, environment: &Environment, limits: &Limits) -> Result<(), MpesaError> {
+        let mut report = Report::default();
+
+        if let Some(originator_conversation_id) = self.originator_conversation_id {
+            report.check("originator_conversation_id", validation::originator_id(originator_conversation_id));
+        }
+        report.required("initiator_name", self.initiator_name);
+        if let Some(command_id) = report.required("command_id", self.command_id) {
+            report.check("command_id", validation::command_id(command_id, CommandId::is_b2c));
+        }
+        if let Some(amount) = report.required("amount", self.amount) {
+            report.check("amount", validation::amount(amount, limits.b2c));
+        }
+        report.required("party_a", self.party_a);
+        report.required("party_b", self.party_b);
+        for &(field, url) in [("queue_timeout_url", self.queue_timeout_url), ("result_url", self.result_url)].iter() {
+            if let Some(url) = report.required(field, url) {
+                report.check(field, validation::callback_urls(environment, &[url]));
+            }
+        }
+
+        report.finish()
+    }
+
+    /// Checks that every field was set and assembles the payload
+    pub(crate) fn build<'b>(self, security_credentials: &'b str) -> Result<B2cPayload<'b>, MpesaError>
+    where
+        'a: 'b,
+    {
+        Ok(B2cPayload {
+            originator_conversation_id: self.originator_conversation_id
+                .map(String::from)
+                .unwrap_or_else(ids::new_originator_id),
+            initiator_name: self.initiator_name.ok_or(MpesaError::MissingField("initiator_name"))?,
+            security_credentials,
+            command_id: self.command_id.ok_or(MpesaError::MissingField("command_id"))?,
+            amount: self.amount.ok_or(MpesaError::MissingField("amount"))?,
+            party_a: self.party_a.ok_or(MpesaError::MissingField("party_a"))?,
+            party_b: self.party_b.ok_or(MpesaError::MissingField("party_b"))?,
+            remarks: self.remarks,
+            queue_timeout_url: self.queue_timeout_url.ok_or(MpesaError::MissingField("queue_timeout_url"))?,
+            result_url: self.result_url.ok_or(MpesaError::MissingField("result_url"))?,
+            occasion: self.occasion,
+        })
+    }
+}
+
+#[cfg(feature = "client")]
+#[derive(Debug, Clone)]
+/// A b2c payment of a batch sent with `Mpesa::b2c_batch` or `MpesaAsync::b2c_batch`.
+/// Each field is sent like the `B2cBuilder` field of the same name.
+pub struct B2cRequest {
+    /// Defaults to `ids::new_originator_id()`, generated before the batch is sent and reported in
+    /// `B2cBatchResult::originator_conversation_ids`
+    pub originator_conversation_id: Option<String>,
+    pub initiator_name: String,
+    pub command_id: CommandId,
+    pub amount: Amount,
+    pub party_a: ShortCode,
+    pub party_b: PhoneNumber,
+    pub remarks: Option<String>,
+    pub queue_timeout_url: String,
+    pub result_url: String,
+    pub occasion: Option<String>,
+}
+
+#[cfg(feature = "client")]
+impl B2cRequest {
+    /// A `B2cBuilder` bound to `client` with every field of this request set
+    pub(crate) fn builder<'a, C>(&'a self, client: &'a C) -> B2cBuilder<'a, C> {
+        B2cBuilder {
+            client,
+            originator_conversation_id: self.originator_conversation_id.as_deref(),
+            initiator_name: Some(&self.initiator_name),
+            command_id: Some(self.command_id),
+            amount: Some(self.amount),
+            party_a: Some(self.party_a.as_str()),
+            party_b: Some(self.party_b.as_str()),
+            remarks: self.remarks.as_deref(),
+            queue_timeout_url: Some(&self.queue_timeout_url),
+            result_url: Some(&self.result_url),
+            occasion: self.occasion.as_deref(),
+            security_credential: None,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+#[derive(Debug)]
+/// Summary of a batch of b2c payments, returned by `Mpesa::b2c_batch` and `MpesaAsync::b2c_batch`.
+///
+/// A payment that failed does not stop the others. Accepted payments are only accepted for
+/// processing: their result is posted to their `result_url`.
+pub struct B2cBatchResult {
+    /// Payments accepted by the API, with their index in the batch
+    pub successes: Vec<(usize, B2cResponse)>,
+    /// Payments that failed, with their index in the batch
+    pub failures: Vec<(usize, MpesaError)>,
+    /// The originator conversation id of every payment, in the order of the batch, to
+    /// match result callbacks or to retry failed payments without paying twice
+    pub originator_conversation_ids: Vec<String>,
+}
+
+#[cfg(feature = "client")]
+impl B2cBatchResult {
+    /// Sorts the result of every payment, given with its index in the batch, in the order
+    /// of the batch. A payment without a result is reported as a `MpesaError::MissingResult`.
+    pub(crate) fn new(originator_conversation_ids: Vec<String>, mut results: Vec<(usize, Result<B2cResponse, MpesaError>)>) -> Self {
+        let mut successes = Vec::new();
+        let mut failures = Vec::new();
+
+        results.sort_by_key(|(index, _)| *index);
+        let mut results = results.into_iter().peekable();
+        for index in 0..originator_conversation_ids.len() {
+            match results.next_if(|(reported, _)| *reported == index) {
+                Some((_, Ok(response))) => successes.push((index, response)),
+                Some((_, Err(error))) => failures.push((index, error)),
+                None => failures.push((index, MpesaError::MissingResult(index))),
+            }
+        }
+
+        Self {
+            successes,
+            failures,
+            originator_conversation_ids,
+        }
+    }
+
+    /// Whether every payment of the batch was accepted
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Assigns an originator conversation id to every payment that has none, so that the
+/// id is known before the batch is sent
+#[cfg(feature = "client")]
+pub(crate) fn assign_originator_conversation_ids<I>(payouts: I) -> Vec<B2cRequest>
+where
+    I: IntoIterator<Item = B2cRequest>,
+{
+    payouts.into_iter()
+        .map(|mut payout| {
+            payout.originator_conversation_id.get_or_insert_with(ids::new_originator_id);
+            payout
+        })
+        .collect()
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize,Serialize,Serializer};
+use super::common::string_or_number;
+use crate::Amount;
+
+#[derive(Serialize)]
+/// Payload to onboard a paybill number to Bill Manager:
+/// See https://developer.safaricom.co.ke/APIs/BillManager for a
+/// detailed description of each field.
+pub struct BillManagerOptInPayload<'a> {
+    pub shortcode: &'a str,
+    pub email: &'a str,
+    #[serde(rename = "officialContact")]
+    pub official_contact: &'a str,
+    /// `"1"` to send payment reminders to customers, `"0"` otherwise
+    #[serde(rename = "sendReminders")]
+    pub send_reminders: &'a str,
+    pub logo: &'a str,
+    #[serde(rename = "callbackurl")]
+    pub callback_url: &'a str,
+}
+
+masked_debug!(BillManagerOptInPayload<'a> {
+    shortcode,
+    email,
+    official_contact: masked,
+    send_reminders,
+    logo,
+    callback_url,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Bill Manager opt-in response
+/// Field names deliberately in snake/lower case to correctly deserialize the
+/// response data
+pub struct BillManagerOptInResponse {
+    pub app_key: String,
+    pub resmsg: String,
+    /// `"200"` if the short code was onboarded
+    #[serde(deserialize_with = "string_or_number")]
+    pub rescode: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+/// A line of a Bill Manager invoice
+pub struct InvoiceItem<'a> {
+    #[serde(rename = "itemName")]
+    pub item_name: &'a str,
+    #[serde(serialize_with = "amount_string")]
+    pub amount: Amount,
+}
+
+#[derive(Serialize)]
+/// Payload to send a single e-invoice to a customer with Bill Manager:
+/// See https://developer.safaricom.co.ke/APIs/BillManager for a
+/// detailed description of each field.
+pub struct InvoicePayload<'a> {
+    #[serde(rename = "externalReference")]
+    pub external_reference: &'a str,
+    #[serde(rename = "billedFullName")]
+    pub billed_full_name: &'a str,
+    #[serde(rename = "billedPhoneNumber")]
+    pub billed_phone_number: &'a str,
+    #[serde(rename = "billedPeriod")]
+    pub billed_period: &'a str,
+    #[serde(rename = "invoiceName")]
+    pub invoice_name: &'a str,
+    #[serde(rename = "dueDate")]
+    pub due_date: &'a str,
+    #[serde(rename = "accountReference")]
+    pub account_reference: &'a str,
+    #[serde(serialize_with = "amount_string")]
+    pub amount: Amount,
+    #[serde(rename = "invoiceItems")]
+    pub invoice_items: &'a [InvoiceItem<'a>],
+}
+
+masked_debug!(InvoicePayload<'a> {
+    external_reference,
+    billed_full_name,
+    billed_phone_number: masked,
+    billed_period,
+    invoice_name,
+    due_date,
+    account_reference: masked,
+    amount,
+    invoice_items,
+});
+
+#[derive(Serialize)]
+/// Payload to acknowledge a payment made against a Bill Manager invoice, which sends
+/// the customer an e-receipt:
+/// See https://developer.safaricom.co.ke/APIs/BillManager for a
+/// detailed description of each field.
+pub struct ReconciliationPayload<'a> {
+    #[serde(rename = "paymentDate")]
+    pub payment_date: &'a str,
+    #[serde(rename = "paidAmount")]
+    #[serde(serialize_with = "amount_string")]
+    pub paid_amount: Amount,
+    #[serde(rename = "accountReference")]
+    pub account_reference: &'a str,
+    #[serde(rename = "transactionId")]
+    pub transaction_id: &'a str,
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: &'a str,
+    #[serde(rename = "fullName")]
+    pub full_name: &'a str,
+    #[serde(rename = "invoiceName")]
+    pub invoice_name: &'a str,
+    #[serde(rename = "externalReference")]
+    pub external_reference: &'a str,
+}
+
+masked_debug!(ReconciliationPayload<'a> {
+    payment_date,
+    paid_amount,
+    account_reference: masked,
+    transaction_id,
+    phone_number: masked,
+    full_name,
+    invoice_name,
+    external_reference,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Bill Manager invoicing and reconciliation response
+/// Field names deliberately in snake/lower case to correctly deserialize the
+/// response data
+pub struct BillManagerResponse {
+    /// Only sent by the invoicing APIs
+    #[serde(default)]
+    pub Status_Message: String,
+    pub resmsg: String,
+    /// `"200"` if the request was accepted
+    #[serde(deserialize_with = "string_or_number")]
+    pub rescode: String,
+}
+
+/// Bill Manager expects amounts as strings, e.g. `"800"`
+fn amount_string<S: Serializer>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(amount)
+}
+
+#[cfg(feature = "client")]
+#[derive(Debug, Clone, Copy)]
+/// Bill Manager APIs, returned by `Mpesa::bill_manager` and `MpesaAsync::bill_manager`.
+///
+/// Bill Manager lets a paybill number send e-invoices, payment reminders and e-receipts
+/// to its customers. The paybill number must be onboarded with `opt_in` before invoicing.
+pub struct BillManager<'a, C> {
+    client: &'a C,
+}
+
+#[cfg(feature = "client")]
+impl<'a, C> BillManager<'a, C> {
+    /// Constructs a new `BillManager` bound to `client`.
+    pub(crate) fn new(client: &'a C) -> Self {
+        Self { client }
+    }
+
+    /// The client requests are sent with
+    pub(crate) fn client(&self) -> &'a C {
+        self.client
+    }
+}
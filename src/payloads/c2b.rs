@@ -1,65 +1,169 @@
-use serde::Deserialize;
+use serde::{Deserialize,Serialize,Serializer};
+use super::common::string_or_number;
+use std::convert::TryFrom;
+use std::error::Error;
 use std::fmt::{Display,Formatter,Result as FmtResult};
-use crate::CommandId;
+use std::str::FromStr;
+use crate::{Amount,CommandId};
+use super::MpesaResult;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 /// Payload to register the 3rd party’s confirmation and validation URLs to M-Pesa
 /// See more here: https://developer.safaricom.co.ke/docs?shell#c2b-api
 pub struct C2bRegisterPayload<'a> {
+    #[serde(rename = "ValidationURL")]
     pub validation_url: &'a str,
+    #[serde(rename = "ConfirmationURL")]
     pub confirmation_url: &'a str,
+    #[serde(rename = "ResponseType")]
     pub response_type: ResponseType,
+    #[serde(rename = "ShortCode")]
     pub short_code: &'a str,
 }
 
-#[derive(Debug,Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// C2B register response
 /// Field names deliberately in Pascal case to correctly deserialize the
 /// response data
 pub struct C2bRegisterResponse {
+    #[serde(default)]
     pub ConversationID: String,
+    // The API misspells this field as `OriginatorCoversationID`
+    #[serde(alias = "OriginatorCoversationID")]
     pub OriginatorConversationID: String,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
     pub ResponseDescription: String,
 }
 
-#[derive(Debug)]
-/// C2B Register Response types
+/// What M-Pesa does with a payment when the validation url cannot be reached.
+/// Serializes to the value expected by the API, `"Completed"` or `"Cancelled"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ResponseType {
+    /// The payment is completed
     Complete,
+    /// The payment is cancelled
     Cancelled,
 }
 
 impl ResponseType {
-    /// Stringify response type
+    /// Stringify response type as expected by the API
     fn response_type_string(&self) -> &'static str {
         match self {
             ResponseType::Cancelled => "Cancelled",
-            ResponseType::Complete => "Complete",
+            // The API expects "Completed"; "Complete" is rejected
+            ResponseType::Complete => "Completed",
         }
     }
 }
 
+impl Serialize for ResponseType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.response_type_string())
+    }
+}
+
 impl Display for ResponseType {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "{:?}", self.response_type_string())
+        write!(f, "{}", self.response_type_string())
+    }
+}
+
+impl FromStr for ResponseType {
+    type Err = ParseResponseTypeError;
+
+    /// Parses the response type as sent to the API, `"Completed"` or `"Cancelled"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Completed" => Ok(ResponseType::Complete),
+            "Cancelled" => Ok(ResponseType::Cancelled),
+            _ => Err(ParseResponseTypeError(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<&str> for ResponseType {
+    type Error = ParseResponseTypeError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
     }
 }
 
+/// Error returned when parsing an unknown `ResponseType`
 #[derive(Debug)]
+pub struct ParseResponseTypeError(String);
+
+impl Display for ParseResponseTypeError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "unknown response type: {}", self.0)
+    }
+}
+
+impl Error for ParseResponseTypeError {}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Outcome of `c2b_register_once`, telling a new registration from urls that were already
+/// registered for the short code
+pub enum C2bRegistration {
+    /// The urls were registered, or overwritten in the sandbox
+    Registered(C2bRegisterResponse),
+    /// Urls were already registered for the short code and were kept, with the message
+    /// returned by the API. They may differ from the urls that were sent.
+    AlreadyRegistered(String),
+}
+
+impl C2bRegistration {
+    /// Whether the urls were registered by this request
+    pub fn is_new(&self) -> bool {
+        matches!(self, C2bRegistration::Registered(_))
+    }
+}
+
+#[derive(Serialize)]
 /// Payload to make payment requests from C2B.
 /// See more: https://developer.safaricom.co.ke/docs#c2b-api
 pub struct C2bSimulatePayload<'a> {
+    #[serde(rename = "CommandID")]
     pub command_id: CommandId,
-    pub amount: u32,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "Msisdn")]
     pub msisdn: &'a str,
+    #[serde(rename = "BillRefNumber")]
     pub bill_ref_number: &'a str,
+    #[serde(rename = "ShortCode")]
     pub short_code: &'a str,
 }
 
-#[derive(Debug, Deserialize)]
+masked_debug!(C2bSimulatePayload<'a> {
+    command_id,
+    amount,
+    msisdn: masked,
+    bill_ref_number: masked,
+    short_code,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// C2B payment response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
 pub struct C2bSimulateResponse {
+    #[serde(default)]
     pub ConversationID: String,
-    pub OriginatorCoversationID: String,
+    // The API misspells this field as `OriginatorCoversationID`
+    #[serde(alias = "OriginatorCoversationID")]
+    pub OriginatorConversationID: String,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    /// Why the simulation was rejected, e.g. because the short code has no registered urls
     pub ResponseDescription: String,
+}
+
+impl C2bSimulateResponse {
+    /// Whether the simulated payment was accepted: a zero or missing `ResponseCode`.
+    /// `ResponseDescription` says why a simulation was rejected.
+    pub fn is_accepted(&self) -> bool {
+        self.is_success()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,230 @@
+use super::{AccountBalanceResponse,B2bResponse,B2cResponse,C2bRegisterResponse,C2bSimulateResponse,TaxRemittanceResponse};
+use super::{B2bExpressCheckoutResponse,ExpressResponse,ExpressQueryResponse,MpesaErrorResponse,QrResponse};
+use super::{BillManagerOptInResponse,BillManagerResponse,ReversalResponse,StandingOrderResponse,TransactionStatusResponse};
+use crate::ResultCode;
+use serde::{Deserialize,Deserializer};
+use serde_json::{Number,Value};
+
+/// Fields shared by the responses of the asynchronous M-Pesa APIs, whose result is
+/// later posted to a callback url.
+///
+/// Allows logging and reconciling any of these responses generically.
+///
+/// # Example
+/// ```
+/// use mpesa::CommonResponse;
+///
+/// fn log_response<R: CommonResponse>(response: &R) {
+///     println!(
+///         "{} / {}: {} {}",
+///         response.conversation_id(),
+///         response.originator_conversation_id(),
+///         response.response_code(),
+///         response.response_description(),
+///     );
+/// }
+/// ```
+pub trait CommonResponse {
+    /// Unique identifier of the transaction, assigned by M-Pesa
+    fn conversation_id(&self) -> &str;
+    /// Unique identifier of the request, also sent in the result callback
+    fn originator_conversation_id(&self) -> &str;
+    /// `"0"` if the request was accepted for processing
+    fn response_code(&self) -> &str;
+    /// Human readable description of the response code
+    fn response_description(&self) -> &str;
+}
+
+macro_rules! impl_common_response {
+    ($($response:ty),*) => {
+        $(
+            impl CommonResponse for $response {
+                fn conversation_id(&self) -> &str {
+                    &self.ConversationID
+                }
+
+                fn originator_conversation_id(&self) -> &str {
+                    &self.OriginatorConversationID
+                }
+
+                fn response_code(&self) -> &str {
+                    &self.ResponseCode
+                }
+
+                fn response_description(&self) -> &str {
+                    &self.ResponseDescription
+                }
+            }
+        )*
+    };
+}
+
+impl_common_response!(
+    AccountBalanceResponse,
+    B2bResponse,
+    B2cResponse,
+    C2bRegisterResponse,
+    C2bSimulateResponse,
+    ReversalResponse,
+    TaxRemittanceResponse,
+    TransactionStatusResponse
+);
+
+/// Distinguishes accepted requests from requests rejected by M-Pesa, whatever field
+/// the response carries its code in.
+///
+/// # Example
+/// ```
+/// use mpesa::{MpesaResult, ResultCode};
+/// use mpesa::payloads::B2cResponse;
+///
+/// let response: B2cResponse = serde_json::from_str(r#"{
+///     "ConversationID": "AG_20191219_00005797af5d7d75f652",
+///     "OriginatorConversationID": "16740-34861180-1",
+///     "ResponseCode": "2001",
+///     "ResponseDescription": "The initiator information is invalid."
+/// }"#).unwrap();
+///
+/// assert!(!response.is_success());
+/// assert_eq!(response.error(), Some(("2001", "The initiator information is invalid.")));
+/// assert_eq!(response.result_code(), ResultCode::InitiatorInformationInvalid);
+/// ```
+pub trait MpesaResult {
+    /// The code and description of the failure, if the request was rejected
+    fn error(&self) -> Option<(&str, &str)>;
+
+    /// Whether the request was accepted
+    fn is_success(&self) -> bool {
+        self.error().is_none()
+    }
+
+    /// The parsed code of the failure, or `ResultCode::Success` if the request was accepted
+    fn result_code(&self) -> ResultCode {
+        self.error().map_or(ResultCode::Success, |(code, _)| ResultCode::from_code(code))
+    }
+}
+
+/// Whether `code` means success: any non-numeric code, e.g. the reference some APIs
+/// send as their `ResponseCode`, or a numeric code equal to zero
+pub(crate) fn is_success_code(code: &str) -> bool {
+    code.parse::<u64>().map_or(true, |code| code == 0)
+}
+
+/// Deserializes a code that the API sends either as a string or as a number,
+/// e.g. `"0"` or `0`, into the same `String`
+pub(crate) fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(Number),
+    }
+
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(code) => code,
+        StringOrNumber::Number(code) => code.to_string(),
+    })
+}
+
+/// Reads a code sent either as a string or as a number from a JSON value
+pub(crate) fn code_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(code) => Some(code.clone()),
+        Value::Number(code) => Some(code.to_string()),
+        _ => None,
+    }
+}
+
+macro_rules! impl_mpesa_result {
+    ($($response:ty),*) => {
+        $(
+            impl MpesaResult for $response {
+                fn error(&self) -> Option<(&str, &str)> {
+                    if is_success_code(&self.ResponseCode) {
+                        None
+                    } else {
+                        Some((&self.ResponseCode, &self.ResponseDescription))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_mpesa_result!(
+    AccountBalanceResponse,
+    B2bResponse,
+    B2cResponse,
+    C2bRegisterResponse,
+    C2bSimulateResponse,
+    ExpressResponse,
+    QrResponse,
+    ReversalResponse,
+    TaxRemittanceResponse,
+    TransactionStatusResponse
+);
+
+/// Fails if either the request was rejected or the customer did not complete the payment
+impl MpesaResult for ExpressQueryResponse {
+    fn error(&self) -> Option<(&str, &str)> {
+        if !is_success_code(&self.ResponseCode) {
+            Some((&self.ResponseCode, &self.ResponseDescription))
+        } else if !is_success_code(&self.ResultCode) {
+            Some((&self.ResultCode, &self.ResultDesc))
+        } else {
+            None
+        }
+    }
+}
+
+impl MpesaResult for B2bExpressCheckoutResponse {
+    fn error(&self) -> Option<(&str, &str)> {
+        if is_success_code(&self.code) {
+            None
+        } else {
+            Some((&self.code, &self.status))
+        }
+    }
+}
+
+/// Accepted standing orders carry a `"200"` response code
+impl MpesaResult for StandingOrderResponse {
+    fn error(&self) -> Option<(&str, &str)> {
+        let header = &self.ResponseHeader;
+
+        if header.responseCode == "200" {
+            None
+        } else {
+            Some((&header.responseCode, &header.responseDescription))
+        }
+    }
+}
+
+/// Accepted Bill Manager requests carry a `"200"` `rescode`
+impl MpesaResult for BillManagerOptInResponse {
+    fn error(&self) -> Option<(&str, &str)> {
+        if self.rescode == "200" {
+            None
+        } else {
+            Some((&self.rescode, &self.resmsg))
+        }
+    }
+}
+
+/// Accepted Bill Manager requests carry a `"200"` `rescode`
+impl MpesaResult for BillManagerResponse {
+    fn error(&self) -> Option<(&str, &str)> {
+        if self.rescode == "200" {
+            None
+        } else {
+            Some((&self.rescode, &self.resmsg))
+        }
+    }
+}
+
+/// Always a failure
+impl MpesaResult for MpesaErrorResponse {
+    fn error(&self) -> Option<(&str, &str)> {
+        Some((&self.errorCode, &self.errorMessage))
+    }
+}
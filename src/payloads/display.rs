@@ -0,0 +1,117 @@
+//! One line summaries of the API responses, for human readable logs.
+//! `Debug` still prints every field.
+
+use std::fmt::{Display,Formatter,Result as FmtResult};
+use super::{AccountBalanceResponse,AuthResponse,B2bExpressCheckoutResponse,B2bResponse,B2cResponse};
+use super::{BillManagerOptInResponse,BillManagerResponse,C2bRegisterResponse,C2bSimulateResponse};
+use super::{ExpressQueryResponse,ExpressResponse,MpesaErrorResponse,QrResponse,StandingOrderResponse,TaxRemittanceResponse};
+use super::{ReversalResponse,TransactionStatusResponse};
+use super::MpesaResult;
+
+/// `"accepted"` or `"rejected"`
+fn outcome<R: MpesaResult>(response: &R) -> &'static str {
+    if response.is_success() { "accepted" } else { "rejected" }
+}
+
+macro_rules! impl_common_display {
+    ($($response:ty => $api:expr),*) => {
+        $(
+            /// e.g. `B2C accepted: ConversationID=AG_20191219_00005797af5d7d75f652 OriginatorConversationID=16740-34861180-1 code=0 (Accept the service request successfully.)`
+            impl Display for $response {
+                fn fmt(&self, f: &mut Formatter) -> FmtResult {
+                    write!(
+                        f,
+                        "{} {}: ConversationID={} OriginatorConversationID={} code={} ({})",
+                        $api, outcome(self), self.ConversationID, self.OriginatorConversationID,
+                        self.ResponseCode, self.ResponseDescription,
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_common_display!(
+    AccountBalanceResponse => "Account balance",
+    B2bResponse => "B2B",
+    B2cResponse => "B2C",
+    C2bRegisterResponse => "C2B register",
+    C2bSimulateResponse => "C2B simulate",
+    ReversalResponse => "Reversal",
+    TaxRemittanceResponse => "Tax remittance",
+    TransactionStatusResponse => "Transaction status"
+);
+
+/// Never shows the access token
+impl Display for AuthResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "Access token issued, expires in {}s", self.expires_in)
+    }
+}
+
+impl Display for ExpressResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "STK push {}: CheckoutRequestID={} MerchantRequestID={} code={} ({})",
+            outcome(self), self.CheckoutRequestID, self.MerchantRequestID, self.ResponseCode, self.ResponseDescription,
+        )
+    }
+}
+
+impl Display for ExpressQueryResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "STK push query {}: CheckoutRequestID={} MerchantRequestID={} code={} result={} ({})",
+            outcome(self), self.CheckoutRequestID, self.MerchantRequestID, self.ResponseCode, self.ResultCode, self.ResultDesc,
+        )
+    }
+}
+
+impl Display for B2bExpressCheckoutResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "B2B express checkout {}: code={} ({})", outcome(self), self.code, self.status)
+    }
+}
+
+/// Never shows the QR code image
+impl Display for QrResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "Dynamic QR {}: RequestID={} code={} ({})",
+            outcome(self), self.RequestID, self.ResponseCode, self.ResponseDescription,
+        )
+    }
+}
+
+impl Display for StandingOrderResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let header = &self.ResponseHeader;
+
+        write!(
+            f,
+            "Standing order {}: responseRefID={} code={} ({})",
+            outcome(self), header.responseRefID, header.responseCode, header.responseDescription,
+        )
+    }
+}
+
+impl Display for BillManagerOptInResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "Bill Manager opt-in {}: app_key={} code={} ({})", outcome(self), self.app_key, self.rescode, self.resmsg)
+    }
+}
+
+impl Display for BillManagerResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "Bill Manager {}: code={} ({})", outcome(self), self.rescode, self.resmsg)
+    }
+}
+
+impl Display for MpesaErrorResponse {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "M-Pesa error: requestId={} code={} ({})", self.requestId, self.errorCode, self.errorMessage)
+    }
+}
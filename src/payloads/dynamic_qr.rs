@@ -0,0 +1,76 @@
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use std::fmt::{Display,Formatter,Result as FmtResult};
+use crate::Amount;
+
+#[derive(Debug, Serialize)]
+/// Payload to generate a dynamic M-Pesa QR code:
+/// See https://developer.safaricom.co.ke/APIs/DynamicQRCode for a
+/// detailed description of each field.
+pub struct DynamicQrPayload<'a> {
+    #[serde(rename = "MerchantName")]
+    pub merchant_name: &'a str,
+    #[serde(rename = "RefNo")]
+    pub ref_no: &'a str,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "TrxCode")]
+    pub transaction_type: QrTransactionType,
+    #[serde(rename = "CPI")]
+    pub credit_party_identifier: &'a str,
+    #[serde(rename = "Size")]
+    pub size: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Dynamic QR code response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
+pub struct QrResponse {
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    #[serde(default)]
+    pub RequestID: String,
+    pub ResponseDescription: String,
+    /// Base64 encoded QR code image
+    pub QRCode: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+/// Transaction types of a dynamic QR code
+pub enum QrTransactionType {
+    /// Pay merchant (buy goods)
+    #[serde(rename = "BG")]
+    BuyGoods,
+    /// Withdraw cash at agent till
+    #[serde(rename = "WA")]
+    WithdrawCash,
+    /// Paybill or business number
+    #[serde(rename = "PB")]
+    PayBill,
+    /// Send money (mobile number)
+    #[serde(rename = "SM")]
+    SendMoney,
+    /// Sent to business. Business number CPI in MSISDN format.
+    #[serde(rename = "SB")]
+    SendToBusiness,
+}
+
+impl QrTransactionType {
+    /// Two letter code of the transaction type
+    fn code(&self) -> &'static str {
+        match self {
+            QrTransactionType::BuyGoods => "BG",
+            QrTransactionType::WithdrawCash => "WA",
+            QrTransactionType::PayBill => "PB",
+            QrTransactionType::SendMoney => "SM",
+            QrTransactionType::SendToBusiness => "SB",
+        }
+    }
+}
+
+impl Display for QrTransactionType {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.code())
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize,Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Error body returned by the Safaricom API when a request is rejected
+/// Field names deliberately in camel case to correctly deserialize the
+/// response data
+pub struct MpesaErrorResponse {
+    pub requestId: String,
+    pub errorCode: String,
+    pub errorMessage: String,
+}
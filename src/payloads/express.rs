@@ -0,0 +1,88 @@
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use crate::{Amount,CommandId};
+
+#[derive(Serialize)]
+/// Payload to initiate an online payment on behalf of a customer (STK push):
+/// See https://developer.safaricom.co.ke/docs#lipa-na-m-pesa-online-payment for a
+/// detailed description of each field.
+pub struct ExpressRequestPayload<'a> {
+    #[serde(rename = "BusinessShortCode")]
+    pub business_short_code: &'a str,
+    #[serde(rename = "Password")]
+    pub password: &'a str,
+    #[serde(rename = "Timestamp")]
+    pub timestamp: &'a str,
+    #[serde(rename = "TransactionType")]
+    pub transaction_type: CommandId,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "PartyA")]
+    pub party_a: &'a str,
+    #[serde(rename = "PartyB")]
+    pub party_b: &'a str,
+    #[serde(rename = "PhoneNumber")]
+    pub phone_number: &'a str,
+    #[serde(rename = "CallBackURL")]
+    pub callback_url: &'a str,
+    #[serde(rename = "AccountReference")]
+    pub account_reference: &'a str,
+    #[serde(rename = "TransactionDesc")]
+    pub transaction_desc: &'a str,
+}
+
+masked_debug!(ExpressRequestPayload<'a> {
+    business_short_code,
+    password,
+    timestamp,
+    transaction_type,
+    amount,
+    party_a: masked,
+    party_b,
+    phone_number: masked,
+    callback_url,
+    account_reference: masked,
+    transaction_desc,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Lipa na M-Pesa online (STK push) response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
+pub struct ExpressResponse {
+    pub CheckoutRequestID: String,
+    pub CustomerMessage: String,
+    pub MerchantRequestID: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    pub ResponseDescription: String,
+}
+
+#[derive(Debug, Serialize)]
+/// Payload to query the status of a Lipa na M-Pesa online (STK push) request
+/// See more: https://developer.safaricom.co.ke/docs#lipa-na-m-pesa-online-query-request
+pub struct ExpressQueryPayload<'a> {
+    #[serde(rename = "BusinessShortCode")]
+    pub business_short_code: &'a str,
+    #[serde(rename = "Password")]
+    pub password: &'a str,
+    #[serde(rename = "Timestamp")]
+    pub timestamp: &'a str,
+    #[serde(rename = "CheckoutRequestID")]
+    pub checkout_request_id: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Lipa na M-Pesa online (STK push) query response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
+pub struct ExpressQueryResponse {
+    pub CheckoutRequestID: String,
+    pub MerchantRequestID: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    pub ResponseDescription: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResultCode: String,
+    pub ResultDesc: String,
+}
@@ -2,11 +2,78 @@
 mod auth;
 mod b2c;
 mod b2b;
+mod b2b_express;
 mod c2b;
 mod account_balance;
+mod express;
+mod dynamic_qr;
+mod tax_remittance;
+mod transaction_status;
+mod reversal;
+mod standing_order;
+mod bill_manager;
+mod common;
+mod display;
+mod error;
 
 pub use auth::AuthResponse;
-pub use b2c::{B2cPayload,B2cResponse};
+pub use b2c::{B2cOutcome,B2cPayload,B2cResponse};
+#[cfg(feature = "client")]
+pub use b2c::{B2cBatchResult,B2cBuilder,B2cRequest};
+#[cfg(feature = "client")]
+pub(crate) use b2c::assign_originator_conversation_ids;
 pub use b2b::{B2bPayload,B2bResponse};
-pub use c2b::{C2bRegisterPayload,C2bRegisterResponse,ResponseType,C2bSimulatePayload,C2bSimulateResponse};
-pub use account_balance::{AccountBalancePayload,AccountBalanceResponse};
\ No newline at end of file
+pub use b2b_express::{B2bExpressCheckoutPayload,B2bExpressCheckoutResponse};
+pub use c2b::{C2bRegisterPayload,C2bRegisterResponse,C2bRegistration,ResponseType,ParseResponseTypeError,C2bSimulatePayload,C2bSimulateResponse};
+pub use account_balance::{AccountBalancePayload,AccountBalanceResponse};
+pub use express::{ExpressRequestPayload,ExpressResponse,ExpressQueryPayload,ExpressQueryResponse};
+pub use dynamic_qr::{DynamicQrPayload,QrResponse,QrTransactionType};
+pub use tax_remittance::{TaxRemittancePayload,TaxRemittanceResponse};
+pub use transaction_status::{TransactionStatusPayload,TransactionStatusResponse};
+pub use reversal::{ReceiptReversal,ReversalPayload,ReversalResponse};
+pub use standing_order::{StandingOrderPayload,StandingOrderResponse,StandingOrderResponseHeader,StandingOrderResponseBody};
+pub use standing_order::{Frequency,StandingOrderTransactionType};
+pub use bill_manager::{BillManagerOptInPayload,BillManagerOptInResponse,BillManagerResponse};
+pub use bill_manager::{InvoiceItem,InvoicePayload,ReconciliationPayload};
+#[cfg(feature = "client")]
+pub use bill_manager::BillManager;
+pub use common::{CommonResponse,MpesaResult};
+pub(crate) use common::code_string;
+#[cfg(feature = "client")]
+pub(crate) use common::is_success_code;
+pub use error::MpesaErrorResponse;
+
+use serde_json::Value;
+
+macro_rules! impl_to_json {
+    ($($payload:ident),*) => {
+        $(
+            impl $payload<'_> {
+                /// The JSON body this payload is sent as, e.g. to sign it in one service
+                /// and send it from another
+                pub fn to_json(&self) -> Value {
+                    serde_json::to_value(self).expect("payloads only hold strings and numbers")
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json!(
+    AccountBalancePayload,
+    B2bExpressCheckoutPayload,
+    B2bPayload,
+    B2cPayload,
+    BillManagerOptInPayload,
+    C2bRegisterPayload,
+    C2bSimulatePayload,
+    DynamicQrPayload,
+    ExpressQueryPayload,
+    ExpressRequestPayload,
+    InvoicePayload,
+    ReconciliationPayload,
+    ReversalPayload,
+    StandingOrderPayload,
+    TaxRemittancePayload,
+    TransactionStatusPayload
+);
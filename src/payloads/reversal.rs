@@ -0,0 +1,55 @@
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use crate::callbacks::TransactionStatusCallback;
+use crate::{Amount,CommandId,IdentifierTypes,ShortCode};
+
+#[derive(Debug, Serialize)]
+/// Payload to reverse a transaction:
+/// See https://developer.safaricom.co.ke/APIs/Reversal for a
+/// detailed description of each field.
+pub struct ReversalPayload<'a> {
+    #[serde(rename = "Initiator")]
+    pub initiator_name: &'a str,
+    #[serde(rename = "SecurityCredential")]
+    pub security_credentials: &'a str,
+    #[serde(rename = "CommandID")]
+    pub command_id: CommandId,
+    #[serde(rename = "TransactionID")]
+    pub transaction_id: &'a str,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "ReceiverParty")]
+    pub receiver_party: &'a ShortCode,
+    // The API expects this misspelling, as for tax remittances
+    #[serde(rename = "RecieverIdentifierType")]
+    pub receiver_identifier_type: IdentifierTypes,
+    #[serde(rename = "Remarks")]
+    pub remarks: &'a str,
+    #[serde(rename = "Occasion")]
+    pub occasion: &'a str,
+    #[serde(rename = "QueueTimeOutURL")]
+    pub queue_timeout_url: &'a str,
+    #[serde(rename = "ResultURL")]
+    pub result_url: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Reversal response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
+pub struct ReversalResponse {
+    pub ConversationID: String,
+    pub OriginatorConversationID: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    pub ResponseDescription: String,
+}
+
+#[derive(Debug)]
+/// Outcome of `reverse_by_receipt`: the transaction status the reversal was checked
+/// against, and the response of the Reversal API
+pub struct ReceiptReversal {
+    /// The transaction status passed to `reverse_by_receipt`
+    pub status: TransactionStatusCallback,
+    pub reversal: ReversalResponse,
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize,Serialize,Serializer};
+use super::common::string_or_number;
+use std::fmt::{Display,Formatter,Result as FmtResult};
+use crate::IdentifierTypes;
+
+#[derive(Serialize)]
+/// Payload to create a standing order (M-Pesa Ratiba):
+/// See https://developer.safaricom.co.ke/APIs/MpesaRatiba for a
+/// detailed description of each field.
+pub struct StandingOrderPayload<'a> {
+    #[serde(rename = "StandingOrderName")]
+    pub standing_order_name: &'a str,
+    #[serde(rename = "StartDate")]
+    pub start_date: &'a str,
+    #[serde(rename = "EndDate")]
+    pub end_date: &'a str,
+    #[serde(rename = "BusinessShortCode")]
+    pub business_short_code: &'a str,
+    #[serde(rename = "TransactionType")]
+    pub transaction_type: StandingOrderTransactionType,
+    #[serde(rename = "ReceiverPartyIdentifierType")]
+    pub receiver_party_identifier_type: IdentifierTypes,
+    #[serde(rename = "Amount")]
+    pub amount: &'a str,
+    #[serde(rename = "PartyA")]
+    pub party_a: &'a str,
+    #[serde(rename = "CallBackURL")]
+    pub callback_url: &'a str,
+    #[serde(rename = "AccountReference")]
+    pub account_reference: &'a str,
+    #[serde(rename = "TransactionDesc")]
+    pub transaction_desc: &'a str,
+    #[serde(rename = "Frequency")]
+    pub frequency: Frequency,
+}
+
+masked_debug!(StandingOrderPayload<'a> {
+    standing_order_name,
+    start_date,
+    end_date,
+    business_short_code,
+    transaction_type,
+    receiver_party_identifier_type,
+    amount,
+    party_a: masked,
+    callback_url,
+    account_reference: masked,
+    transaction_desc,
+    frequency,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Standing order response
+/// Field names deliberately in Pascal/camel case to correctly deserialize the
+/// response data
+pub struct StandingOrderResponse {
+    pub ResponseHeader: StandingOrderResponseHeader,
+    pub ResponseBody: StandingOrderResponseBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Header of a standing order response
+pub struct StandingOrderResponseHeader {
+    pub responseRefID: String,
+    /// `"200"` if the standing order was accepted for processing
+    #[serde(deserialize_with = "string_or_number")]
+    pub responseCode: String,
+    pub responseDescription: String,
+    #[serde(default)]
+    pub ResultDesc: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Body of a standing order response
+pub struct StandingOrderResponseBody {
+    pub responseDescription: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub responseCode: String,
+}
+
+/// How the customer pays a standing order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StandingOrderTransactionType {
+    /// Pays a paybill number
+    PayBill,
+    /// Pays a till number
+    BuyGoods,
+}
+
+impl StandingOrderTransactionType {
+    /// Transaction type as expected by the API
+    fn as_str(&self) -> &'static str {
+        match self {
+            StandingOrderTransactionType::PayBill => "Standing Order Customer Pay Bill",
+            // The API expects this misspelling
+            StandingOrderTransactionType::BuyGoods => "Standing Order Customer Pay Marchant",
+        }
+    }
+}
+
+impl Serialize for StandingOrderTransactionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl Display for StandingOrderTransactionType {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// How often a standing order is paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frequency {
+    OneOff,
+    Daily,
+    Weekly,
+    Monthly,
+    BiMonthly,
+    Quarterly,
+    HalfYearly,
+    Yearly,
+}
+
+impl Frequency {
+    /// Numeric code of the frequency as expected by the API
+    pub fn code(&self) -> &'static str {
+        match self {
+            Frequency::OneOff => "1",
+            Frequency::Daily => "2",
+            Frequency::Weekly => "3",
+            Frequency::Monthly => "4",
+            Frequency::BiMonthly => "5",
+            Frequency::Quarterly => "6",
+            Frequency::HalfYearly => "7",
+            Frequency::Yearly => "8",
+        }
+    }
+}
+
+impl Serialize for Frequency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl Display for Frequency {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.code())
+    }
+}
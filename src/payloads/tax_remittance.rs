@@ -0,0 +1,47 @@
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use crate::{Amount,CommandId,IdentifierTypes};
+
+#[derive(Debug, Serialize)]
+/// Payload to remit tax to the Kenya Revenue Authority (KRA):
+/// See https://developer.safaricom.co.ke/APIs/TaxRemittance for a
+/// detailed description of each field.
+pub struct TaxRemittancePayload<'a> {
+    #[serde(rename = "Initiator")]
+    pub initiator_name: &'a str,
+    #[serde(rename = "SecurityCredential")]
+    pub security_credentials: &'a str,
+    #[serde(rename = "CommandID")]
+    pub command_id: CommandId,
+    #[serde(rename = "SenderIdentifierType")]
+    pub sender_id: IdentifierTypes,
+    // The API expects this misspelling; `ReceiverIdentifierType` is silently ignored
+    #[serde(rename = "RecieverIdentifierType")]
+    pub receiver_id: IdentifierTypes,
+    #[serde(rename = "Amount")]
+    pub amount: Amount,
+    #[serde(rename = "PartyA")]
+    pub party_a: &'a str,
+    #[serde(rename = "PartyB")]
+    pub party_b: &'a str,
+    #[serde(rename = "AccountReference")]
+    pub account_reference: &'a str,
+    #[serde(rename = "Remarks")]
+    pub remarks: &'a str,
+    #[serde(rename = "QueueTimeOutURL")]
+    pub queue_timeout_url: &'a str,
+    #[serde(rename = "ResultURL")]
+    pub result_url: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Tax remittance response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
+pub struct TaxRemittanceResponse {
+    pub ConversationID: String,
+    pub OriginatorConversationID: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    pub ResponseDescription: String,
+}
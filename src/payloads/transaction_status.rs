@@ -0,0 +1,42 @@
+use serde::{Deserialize,Serialize};
+use super::common::string_or_number;
+use crate::{CommandId,IdentifierTypes};
+
+#[derive(Debug, Serialize)]
+/// Payload to query the status of a transaction:
+/// See https://developer.safaricom.co.ke/APIs/TransactionStatus for a
+/// detailed description of each field.
+pub struct TransactionStatusPayload<'a> {
+    #[serde(rename = "Initiator")]
+    pub initiator_name: &'a str,
+    #[serde(rename = "SecurityCredential")]
+    pub security_credentials: &'a str,
+    #[serde(rename = "CommandID")]
+    pub command_id: CommandId,
+    #[serde(rename = "TransactionID")]
+    pub transaction_id: &'a str,
+    #[serde(rename = "PartyA")]
+    pub party_a: &'a str,
+    #[serde(rename = "IdentifierType")]
+    pub identifier_type: IdentifierTypes,
+    #[serde(rename = "Remarks")]
+    pub remarks: &'a str,
+    #[serde(rename = "Occasion")]
+    pub occasion: &'a str,
+    #[serde(rename = "QueueTimeOutURL")]
+    pub queue_timeout_url: &'a str,
+    #[serde(rename = "ResultURL")]
+    pub result_url: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Transaction status response
+/// Field names deliberately in Pascal case to correctly deserialize the
+/// response data
+pub struct TransactionStatusResponse {
+    pub ConversationID: String,
+    pub OriginatorConversationID: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub ResponseCode: String,
+    pub ResponseDescription: String,
+}
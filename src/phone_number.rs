@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Debug,Display,Formatter,Result as FmtResult};
+use std::str::FromStr;
+use serde::{Serialize,Serializer};
+use crate::masked::Masked;
+
+/// A Kenyan MSISDN normalized to the `2547XXXXXXXX` / `2541XXXXXXXX`
+/// international format expected by the API.
+///
+/// ## Example
+/// ```
+/// use mpesa::PhoneNumber;
+///
+/// let number = PhoneNumber::new("0708 374 149").unwrap();
+/// assert_eq!(number.as_str(), "254708374149");
+/// assert_eq!(PhoneNumber::new("+254708374149").unwrap(), number);
+/// assert!(PhoneNumber::new("0208374149").is_err());
+/// ```
+///
+/// The number is masked when displayed or debug printed, e.g. `2547****4149`, see `Masked`.
+/// Use `as_str` for the full number.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// Normalizes `number`, accepting the `07XXXXXXXX`, `7XXXXXXXX`, `2547XXXXXXXX`
+    /// and `+2547XXXXXXXX` forms (and their `1XXXXXXXX` counterparts).
+    /// Spaces and dashes are ignored.
+    pub fn new(number: &str) -> Result<Self, PhoneNumberError> {
+        let digits: String = number.chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect();
+        let digits = digits.trim_start_matches('+');
+
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PhoneNumberError(number.to_string()));
+        }
+
+        let subscriber = match digits.len() {
+            12 if digits.starts_with("254") => &digits[3..],
+            10 if digits.starts_with('0') => &digits[1..],
+            9 => digits,
+            _ => return Err(PhoneNumberError(number.to_string())),
+        };
+
+        if !subscriber.starts_with('7') && !subscriber.starts_with('1') {
+            return Err(PhoneNumberError(number.to_string()));
+        }
+
+        Ok(Self(format!("254{}", subscriber)))
+    }
+
+    /// The normalized number
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for PhoneNumber {
+    type Err = PhoneNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for PhoneNumber {
+    type Error = PhoneNumberError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl Display for PhoneNumber {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", Masked(&self.0))
+    }
+}
+
+impl Debug for PhoneNumber {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_tuple("PhoneNumber").field(&Masked(&self.0)).finish()
+    }
+}
+
+impl Serialize for PhoneNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Error returned when a phone number is not a valid Kenyan MSISDN.
+/// The number is masked when formatted, like `PhoneNumber` itself
+pub struct PhoneNumberError(String);
+
+impl Debug for PhoneNumberError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_tuple("PhoneNumberError").field(&Masked(&self.0)).finish()
+    }
+}
+
+impl Display for PhoneNumberError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "invalid phone number: {}", Masked(&self.0))
+    }
+}
+
+impl Error for PhoneNumberError {}
@@ -0,0 +1,38 @@
+//! Re-exports the types most programs using the crate need, so they can be imported
+//! with a single glob import:
+//!
+//! ```
+//! use mpesa::prelude::*;
+//!
+//! let amount = Amount::try_new(1000).unwrap();
+//! let short_code = ShortCode::new("600496").unwrap();
+//! assert_eq!(CommandId::BusinessPayment.to_string(), "BusinessPayment");
+//! ```
+//!
+//! Included are:
+//! - the clients and their builder: `Mpesa`, `MpesaAsync` and `MpesaBuilder`, and
+//!   `B2cBuilder` and `B2cRequest` for B2C payments
+//! - the enums requests are made with: `Environment`, `CommandId`, `IdentifierTypes`,
+//!   `ResponseType`, `QrTransactionType`, `Frequency` and `StandingOrderTransactionType`,
+//!   and `ResultCode` and `CallbackKind` to handle results
+//! - the validated values requests take: `Amount`, `PhoneNumber` and `ShortCode`
+//! - the errors: `MpesaError` and `FieldError`
+//! - the `CommonResponse` and `MpesaResult` traits, whose methods are available on responses
+//!   and result callbacks once in scope
+//! - `InvoiceItem`, `C2bRegistration` and `B2cOutcome`, and `parse_callback`
+//!
+//! Payloads, responses and callback bodies are not included; import them from `payloads` and
+//! `callbacks` as needed. The clients and builders are only included with the `client` feature.
+
+#[cfg(feature = "client")]
+pub use crate::{Mpesa,MpesaAsync,MpesaBuilder};
+#[cfg(feature = "client")]
+pub use crate::payloads::{B2cBuilder,B2cRequest};
+pub use crate::{CommandId,Environment,IdentifierTypes,ResultCode};
+pub use crate::{Frequency,QrTransactionType,ResponseType,StandingOrderTransactionType};
+pub use crate::{Amount,PhoneNumber,ShortCode};
+pub use crate::{FieldError,MpesaError};
+pub use crate::{CommonResponse,MpesaResult};
+pub use crate::InvoiceItem;
+pub use crate::payloads::{B2cOutcome,C2bRegistration};
+pub use crate::callbacks::{parse_callback,CallbackKind};
@@ -0,0 +1,66 @@
+use serde_json::Value;
+#[cfg(feature = "client")]
+use serde_json::json;
+#[cfg(feature = "client")]
+use uuid::Uuid;
+
+/// A request captured by a client built with `MpesaBuilder::record_requests`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// The full url the request would have been sent to
+    pub url: String,
+    /// The JSON body of the request
+    pub body: Value,
+}
+
+/// Body answered to every recorded request: an accepted request with empty ids.
+/// It holds the fields of every response type, so it deserializes into any of them.
+#[cfg(feature = "client")]
+pub(crate) fn recorded_response() -> Value {
+    json!({
+        "ConversationID": "",
+        "OriginatorConversationID": "",
+        "ResponseCode": "0",
+        "ResponseDescription": "Request recorded",
+        "CheckoutRequestID": "",
+        "MerchantRequestID": "",
+        "CustomerMessage": "",
+        "ResultCode": "0",
+        "ResultDesc": "",
+        "QRCode": "",
+        "code": "0",
+        "status": "Request recorded",
+        "app_key": "",
+        "Status_Message": "",
+        "resmsg": "Request recorded",
+        "rescode": "200",
+        "ResponseHeader": {
+            "responseRefID": "",
+            "responseCode": "200",
+            "responseDescription": "Request recorded",
+        },
+        "ResponseBody": {
+            "responseCode": "200",
+            "responseDescription": "Request recorded",
+        },
+    })
+}
+
+/// Body answered to every request of a client built with `MpesaBuilder::dry_run`: an
+/// accepted request with generated ids, keeping the `OriginatorConversationID` of `body`
+#[cfg(feature = "client")]
+pub(crate) fn dry_run_response(body: &Value) -> Value {
+    let id = format!("dry-run-{}", Uuid::new_v4());
+    let originator_conversation_id = body.get("OriginatorConversationID")
+        .and_then(Value::as_str)
+        .map_or_else(|| id.clone(), String::from);
+
+    let mut response = recorded_response();
+    response["ConversationID"] = json!(id);
+    response["OriginatorConversationID"] = json!(originator_conversation_id);
+    response["CheckoutRequestID"] = json!(id);
+    response["MerchantRequestID"] = json!(id);
+    response["ResponseDescription"] = json!("Dry run: request not sent");
+    response["ResponseHeader"]["responseRefID"] = json!(id);
+    response
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+use chrono::{DateTime,Utc};
+use rand::Rng;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap,RETRY_AFTER};
+
+/// Upper bound on the delay between two attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Retry behaviour for transient failures, configured on `MpesaBuilder`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Requests are not retried by default
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a request should be sent again after `attempt` retries.
+    ///
+    /// Server errors (5xx) and rate limited requests (429), which were not processed, are
+    /// always retried. Other failures are only retried for idempotent requests, or when the
+    /// connection could not be established, so that a payment is never submitted twice.
+    /// Requests the server asks to retry after more than `MAX_BACKOFF` are not retried, so
+    /// that a long `Retry-After` is left to the caller instead of blocking the request.
+    pub fn should_retry(
+        &self,
+        attempt: u32,
+        outcome: Result<StatusCode, &reqwest::Error>,
+        retry_after: Option<Duration>,
+        idempotent: bool,
+    ) -> bool {
+        if attempt >= self.max_retries || matches!(retry_after, Some(retry_after) if retry_after > MAX_BACKOFF) {
+            return false;
+        }
+
+        match outcome {
+            Ok(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+            Err(e) => idempotent || e.is_connect(),
+        }
+    }
+
+    /// Jittered exponential backoff before the retry following `attempt`:
+    /// a random delay between half and all of `base_backoff * 2^attempt`
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self.base_backoff
+            .checked_mul(2u32.saturating_pow(attempt))
+            .unwrap_or(MAX_BACKOFF)
+            .min(MAX_BACKOFF);
+        let millis = backoff.as_millis() as u64;
+
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2, millis + 1))
+    }
+}
+
+/// The delay asked for by a `Retry-After` header, sent either as a number of seconds
+/// or as an HTTP date
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+}
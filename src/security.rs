@@ -0,0 +1,40 @@
+//! Helpers for the `Timestamp` and `Password` fields of Lipa na M-Pesa online (STK push)
+//! requests, exposed for applications that sign requests themselves.
+//!
+//! ## Example
+//! ```
+//! use chrono::{Local, TimeZone};
+//! use mpesa::security::{stk_password, timestamp_at};
+//!
+//! let timestamp = timestamp_at(Local.with_ymd_and_hms(2016, 2, 16, 16, 56, 27).unwrap());
+//! assert_eq!(timestamp, "20160216165627");
+//!
+//! let password = stk_password("174379", "passkey", &timestamp);
+//! ```
+
+use std::fmt::Display;
+use chrono::{DateTime,TimeZone};
+use crate::clock::{Clock,SystemClock};
+
+/// Format of the `Timestamp` field: `yyyyMMddHHmmss`
+const TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// The current local time as a `yyyyMMddHHmmss` timestamp
+pub fn timestamp() -> String {
+    timestamp_at(SystemClock.now())
+}
+
+/// Formats `time` as a `yyyyMMddHHmmss` timestamp
+pub fn timestamp_at<Tz>(time: DateTime<Tz>) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    time.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// Computes the Lipa na M-Pesa online `Password`:
+/// the base64 encoding of `short_code + passkey + timestamp`.
+pub fn stk_password(short_code: &str, passkey: &str, timestamp: &str) -> String {
+    base64::encode(format!("{}{}{}", short_code, passkey, timestamp))
+}
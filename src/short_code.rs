@@ -0,0 +1,77 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display,Formatter,Result as FmtResult};
+use std::str::FromStr;
+use serde::{Serialize,Serializer};
+
+/// An organization's paybill short code or till number: 5 to 7 digits.
+///
+/// ## Example
+/// ```
+/// use mpesa::ShortCode;
+///
+/// let short_code = ShortCode::new(" 600496 ").unwrap();
+/// assert_eq!(short_code.as_str(), "600496");
+/// assert!(ShortCode::new("").is_err());
+/// assert!(ShortCode::new("6004a6").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortCode(String);
+
+impl ShortCode {
+    /// Checks that `code`, ignoring surrounding whitespace, is made of 5 to 7 digits
+    pub fn new(code: &str) -> Result<Self, ShortCodeError> {
+        let code = code.trim();
+
+        if !(5..=7).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ShortCodeError(code.to_string()));
+        }
+
+        Ok(Self(code.to_string()))
+    }
+
+    /// The short code
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for ShortCode {
+    type Err = ShortCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for ShortCode {
+    type Error = ShortCodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl Display for ShortCode {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for ShortCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Error returned when a short code is not made of 5 to 7 digits
+#[derive(Debug)]
+pub struct ShortCodeError(String);
+
+impl Display for ShortCodeError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "invalid short code: {:?}", self.0)
+    }
+}
+
+impl Error for ShortCodeError {}
@@ -0,0 +1,18 @@
+use serde::{Deserialize,Serialize};
+use serde_json::Value;
+
+/// A request with its security credentials already generated, built without being sent,
+/// e.g. by `B2cBuilder::build_request`.
+///
+/// Lets a signing service that holds the initiator password prepare requests that another
+/// service, holding only the client key and secret, sends with `Mpesa::send_signed`.
+/// It serializes to JSON to be passed between the two.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedRequest {
+    /// The full url the request is sent to
+    pub url: String,
+    /// The JSON body of the request, holding the security credentials
+    pub body: Value,
+    /// The HTTP method of the request, always `"POST"` for the M-Pesa APIs
+    pub method: String,
+}
@@ -0,0 +1,37 @@
+use std::time::{Duration,Instant};
+
+/// Tokens are refreshed this long before they actually expire, so that a
+/// token is never sent just as it runs out
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// OAuth access token along with its expiry
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+impl AccessToken {
+    /// Constructs a new `AccessToken` valid for `expires_in` from now.
+    pub fn new(token: String, expires_in: Duration) -> Self {
+        Self {
+            token,
+            expires_at: Instant::now() + expires_in,
+        }
+    }
+
+    /// The bearer token sent with API requests
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Time left until the token expires
+    pub fn expires_in(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the token has expired, or is about to
+    pub fn is_expired(&self) -> bool {
+        self.expires_in() <= EXPIRY_MARGIN
+    }
+}
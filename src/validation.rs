@@ -0,0 +1,93 @@
+use reqwest::Url;
+
+use crate::{Amount, CommandId, Environment, FieldError, MpesaError};
+use crate::ids;
+use crate::limits::Limit;
+
+/// Checks that every url M-Pesa will call back is a valid `https` url.
+/// Plain `http` is tolerated outside of `Environment::Production`, as the sandbox accepts it.
+pub(crate) fn callback_urls(environment: &Environment, urls: &[&str]) -> Result<(), MpesaError> {
+    for url in urls {
+        let parsed = Url::parse(url).map_err(|e| MpesaError::InvalidUrl(format!("{}: {}", url, e)))?;
+
+        match (parsed.scheme(), environment) {
+            ("https", _) => {},
+            ("http", Environment::Sandbox) | ("http", Environment::Custom(_)) => {},
+            _ => return Err(MpesaError::InvalidUrl(format!("{}: callback urls must use https", url))),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a sandbox only `api`, e.g. `"c2b_simulate"`, is not called in production.
+/// Custom environments are allowed, as they usually point at mock servers.
+pub(crate) fn sandbox_only(environment: &Environment, api: &'static str) -> Result<(), MpesaError> {
+    match environment {
+        Environment::Production => Err(MpesaError::SandboxOnly(api)),
+        Environment::Sandbox | Environment::Custom(_) => Ok(()),
+    }
+}
+
+/// Checks that an originator conversation id set by the caller will be accepted by M-Pesa
+pub(crate) fn originator_id(id: &str) -> Result<(), MpesaError> {
+    if ids::is_valid_originator_id(id) {
+        Ok(())
+    } else {
+        Err(MpesaError::InvalidOriginatorId(id.to_string()))
+    }
+}
+
+/// Checks that `command_id` is accepted by the API being called, e.g. `CommandId::is_b2b`
+pub(crate) fn command_id(command_id: CommandId, is_valid: fn(&CommandId) -> bool) -> Result<(), MpesaError> {
+    if is_valid(&command_id) {
+        Ok(())
+    } else {
+        Err(MpesaError::InvalidCommand(command_id))
+    }
+}
+
+/// Checks that `amount` is within the `limit` of the API being called
+pub(crate) fn amount(amount: Amount, limit: Limit) -> Result<(), MpesaError> {
+    if limit.contains(amount) {
+        Ok(())
+    } else {
+        Err(MpesaError::AmountOutOfRange { min: limit.min, max: limit.max })
+    }
+}
+
+/// Collects the problems found validating every field of a request or builder, so that
+/// they are reported at once rather than one per attempt
+#[derive(Debug, Default)]
+pub(crate) struct Report {
+    errors: Vec<(&'static str, MpesaError)>,
+}
+
+impl Report {
+    /// Records the error of `result`, if any, against `field`
+    pub(crate) fn check(&mut self, field: &'static str, result: Result<(), MpesaError>) {
+        if let Err(error) = result {
+            self.errors.push((field, error));
+        }
+    }
+
+    /// Passes `value` through, recording `field` as missing if it is `None`
+    pub(crate) fn required<T>(&mut self, field: &'static str, value: Option<T>) -> Option<T> {
+        if value.is_none() {
+            self.errors.push((field, MpesaError::MissingField(field)));
+        }
+        value
+    }
+
+    /// The single problem found as is, e.g. `MpesaError::MissingField`, or several as a
+    /// `MpesaError::Validation` listing them in the order they were found
+    pub(crate) fn finish(mut self) -> Result<(), MpesaError> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0).1),
+            _ => Err(MpesaError::Validation(
+                self.errors.iter().map(|(field, error)| FieldError::new(field, error)).collect()
+            )),
+        }
+    }
+}
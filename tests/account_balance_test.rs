@@ -1,5 +1,6 @@
-use mpesa::{Mpesa,Environment,CommandId};
-use dotenv;
+#![cfg(feature = "client")]
+
+use mpesa::{Mpesa,Environment,IdentifierTypes,ShortCode};
 use std::env;
 
 #[test]
@@ -14,7 +15,8 @@ fn b2b_test() {
     );
 
     let account_balance_response = client.account_balance(
-        "600496",
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
         "none",
         "collins",
         "https://hell.world/api",
@@ -0,0 +1,16 @@
+use mpesa::Amount;
+use std::convert::TryFrom;
+
+#[test]
+fn amount_valid_test() {
+    assert_eq!(Amount::try_new(1).unwrap().value(), 1);
+    assert_eq!(Amount::try_new(Amount::MAX).unwrap().to_string(), "250000");
+    assert_eq!(Amount::try_from(1000).unwrap(), Amount::try_new(1000).unwrap());
+    assert_eq!(serde_json::to_value(Amount::try_new(1000).unwrap()).unwrap(), 1000);
+}
+
+#[test]
+fn amount_invalid_test() {
+    assert!(Amount::try_new(0).is_err());
+    assert!(Amount::try_new(Amount::MAX + 1).is_err());
+}
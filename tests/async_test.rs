@@ -0,0 +1,95 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::serve_requests;
+use mpesa::{MpesaAsync,Environment,Amount,CommandId,PhoneNumber,ShortCode};
+use mpesa::environment::SANDBOX_CERTIFICATE;
+use std::env;
+use std::time::Duration;
+
+#[tokio::test]
+async fn async_b2c_test() {
+    dotenv::dotenv().ok();
+
+    let client = MpesaAsync::new(
+        env::var("CLIENT_KEY").unwrap(),
+        env::var("CLIENT_SECRET").unwrap(),
+        Environment::Sandbox, // or environment variable
+        env::var("INIT_PASSWORD").unwrap(),
+    );
+
+    let b2c_response = client.b2c()
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .party_b(&PhoneNumber::new("254708374149").unwrap())
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .occasion("Test")
+        .send()
+        .await
+        .unwrap();
+
+    println!("Async b2c response -> {:#?}", b2c_response);
+
+    assert_eq!(b2c_response.ResponseCode, "0".to_string());
+}
+
+#[tokio::test]
+async fn async_c2b_simulate_batch_test() {
+    let client = MpesaAsync::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password")
+        .record_requests()
+        .build_async()
+        .unwrap();
+
+    let simulations = (1..=10).map(|amount| (
+        Amount::try_new(amount).unwrap(),
+        PhoneNumber::new("254705583540").unwrap(),
+        format!("bill-{}", amount),
+    ));
+
+    let responses = client.c2b_simulate_batch(CommandId::CustomerPayBillOnline, &ShortCode::new("600496").unwrap(), simulations, 3).await;
+
+    assert_eq!(responses.len(), 10);
+    assert!(responses.iter().all(Result::is_ok));
+
+    // Results and requests follow the order of the simulations
+    let bill_ref_numbers: Vec<_> = client.recorded_requests().unwrap()
+        .into_iter()
+        .map(|request| request.body["BillRefNumber"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(bill_ref_numbers[0], "bill-1");
+    assert_eq!(bill_ref_numbers.len(), 10);
+}
+
+#[tokio::test]
+async fn token_refresher_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", r#"{"access_token":"fresh","expires_in":"3599"}"#)]);
+    let client = MpesaAsync::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url))
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE)
+        .build_async()
+        .unwrap();
+
+    // Still valid, but within the refresh margin
+    client.set_access_token(String::from("stale"), Duration::from_secs(90));
+    let refresher = client.spawn_token_refresher();
+    tokio::time::delay_for(Duration::from_millis(1500)).await;
+
+    assert_eq!(client.auth().await.unwrap().token(), "fresh");
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    // The refresher stops once the client is dropped
+    drop(client);
+    tokio::time::timeout(Duration::from_secs(5), refresher).await.unwrap().unwrap();
+}
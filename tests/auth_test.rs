@@ -0,0 +1,218 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,client_with,serve_requests,BALANCE,TOKEN};
+use mpesa::{Environment,IdentifierTypes,MpesaError,ShortCode};
+use mpesa::payloads::AuthResponse;
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn auth_caches_token_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let client = client(url);
+
+    let access_token = client.auth().unwrap();
+    assert_eq!(access_token.token(), "token");
+    assert!(access_token.expires_in() > Duration::from_secs(3500));
+
+    assert_eq!(client.auth().unwrap().token(), "token");
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn auth_query_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+
+    client(url).auth().unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0].target, "GET /oauth/v1/generate");
+    assert_eq!(requests[0].query, "grant_type=client_credentials");
+}
+
+#[test]
+fn clones_share_token_cache_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let client = client(url);
+    let clone = client.clone();
+
+    assert_eq!(client.auth().unwrap().token(), "token");
+    assert_eq!(clone.auth().unwrap().token(), "token");
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    clone.set_access_token(String::from("shared_token"), Duration::from_secs(3600));
+    assert_eq!(client.auth().unwrap().token(), "shared_token");
+}
+
+#[test]
+fn with_environment_test() {
+    let (sandbox_url, sandbox_requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let (production_url, production_requests) = serve_requests(vec![("200 OK", r#"{"access_token":"production_token","expires_in":"3599"}"#)]);
+    let client = client(sandbox_url);
+    let production = client.with_environment(Environment::Custom(production_url.clone()));
+
+    assert_eq!(client.auth().unwrap().token(), "token");
+    assert_eq!(production.auth().unwrap().token(), "production_token");
+    assert_eq!(production.environment().base_url(), production_url);
+    assert_eq!(sandbox_requests.lock().unwrap().len(), 1);
+    assert_eq!(production_requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn auth_base_url_test() {
+    let (auth_url, auth_requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let (api_url, api_requests) = serve_requests(vec![("200 OK", BALANCE)]);
+    let client = client_with(Environment::Custom(api_url), |builder| builder.auth_base_url(format!("{}/", auth_url)));
+
+    client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    let auth_requests = auth_requests.lock().unwrap();
+    assert_eq!(auth_requests.len(), 1);
+    assert_eq!(auth_requests[0].target, "GET /oauth/v1/generate");
+
+    let api_requests = api_requests.lock().unwrap();
+    assert_eq!(api_requests.len(), 1);
+    assert_eq!(api_requests[0].target, "POST /mpesa/accountbalance/v1/query");
+    assert_eq!(api_requests[0].header("authorization"), Some("bearer token"));
+}
+
+#[test]
+fn concurrent_auth_single_refresh_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN); 16]);
+    let client = client(url);
+
+    let handles: Vec<_> = (0..16)
+        .map(|_| {
+            let client = client.clone();
+            thread::spawn(move || client.auth().unwrap().token().to_string())
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "token");
+    }
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn set_access_token_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", BALANCE)]);
+    let client = client(url);
+
+    client.set_access_token(String::from("shared_token"), Duration::from_secs(3600));
+    assert_eq!(client.auth().unwrap().token(), "shared_token");
+
+    let response = client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    assert_eq!(response.ResponseCode, "0");
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn expired_access_token_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let client = client(url);
+
+    client.set_access_token(String::from("stale_token"), Duration::from_secs(0));
+
+    assert_eq!(client.auth().unwrap().token(), "token");
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn auth_response_expires_in_test() {
+    let auth_response: AuthResponse = serde_json::from_str(
+        r#"{"access_token":"c9SQxWWhmdVRlyh0zh8gZDTkubVF","expires_in":"3599"}"#
+    ).unwrap();
+
+    assert_eq!(auth_response.access_token, "c9SQxWWhmdVRlyh0zh8gZDTkubVF");
+    assert_eq!(auth_response.expires_in, 3599);
+
+    let auth_response: AuthResponse = serde_json::from_str(r#"{"access_token":"token","expires_in":3599}"#).unwrap();
+    assert_eq!(auth_response.expires_in, 3599);
+
+    let auth_response: AuthResponse = serde_json::from_str(r#"{"access_token":"token"}"#).unwrap();
+    assert_eq!(auth_response.expires_in, 3600);
+}
+
+#[test]
+fn auth_error_test() {
+    let (url, _) = serve_requests(vec![
+        ("400 Bad Request", r#"{"requestId":"1","errorCode":"400.008.01","errorMessage":"Invalid Authentication passed"}"#),
+        ("400 Bad Request", ""),
+    ]);
+    let client = client(url);
+
+    match client.auth() {
+        Err(MpesaError::Authentication { code, message }) => {
+            assert_eq!(code, "400.008.01");
+            assert_eq!(message, "Invalid Authentication passed");
+        },
+        result => panic!("expected an authentication error, got {:?}", result),
+    }
+
+    assert!(matches!(client.auth(), Err(MpesaError::Authentication { ref code, .. }) if code == "400"));
+}
+
+#[test]
+fn health_check_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let healthy = client(url);
+
+    healthy.health_check().unwrap();
+    healthy.health_check().unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    let (url, _) = serve_requests(vec![
+        ("400 Bad Request", r#"{"requestId":"1","errorCode":"400.008.01","errorMessage":"Invalid Authentication passed"}"#),
+    ]);
+    let error = client(url).health_check().unwrap_err();
+    assert!(matches!(error, MpesaError::Authentication { .. }));
+    assert!(!error.is_unavailable());
+
+    // Nothing listens on the port once the listener is dropped
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let error = client(url).health_check().unwrap_err();
+    assert!(matches!(error, MpesaError::NetworkError(_)));
+    assert!(error.is_unavailable());
+}
+
+#[test]
+fn validate_credentials_test() {
+    let (url, _) = serve_requests(vec![
+        ("400 Bad Request", r#"{"requestId":"1","errorCode":"400.008.01","errorMessage":"Invalid Authentication passed"}"#),
+    ]);
+
+    assert!(matches!(client(url).validate_credentials(), Err(MpesaError::Authentication { .. })));
+
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+    ]);
+    let client = client(url);
+
+    client.validate_credentials().unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    // The token is cached for the first request
+    client.validate_credentials().unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
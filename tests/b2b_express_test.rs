@@ -0,0 +1,29 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,serve_requests,TOKEN};
+use mpesa::Amount;
+
+#[test]
+fn b2b_express_checkout_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"code":"0","status":"USSD Initiated Successfully"}"#),
+    ]);
+
+    let client = client(url);
+
+    let b2b_express_response = client.b2b_express_checkout(
+        "000001",
+        "000002",
+        Amount::try_new(100).unwrap(),
+        "paymentRef",
+        "https://muriuki.dev/api/callback",
+        "Vendor",
+        "5d8b4b1c-0b9e-4f6a-9f5e-2a1d8c4b7e10",
+    ).unwrap();
+
+    assert_eq!(b2b_express_response.code, "0");
+    assert_eq!(b2b_express_response.status, "USSD Initiated Successfully");
+}
@@ -1,5 +1,9 @@
-use mpesa::{Mpesa,Environment,CommandId};
-use dotenv;
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::client_with;
+use mpesa::{Mpesa,Environment,Amount,CommandId,IdentifierTypes,MpesaError};
 use std::env;
 
 #[test]
@@ -16,18 +20,42 @@ fn b2b_test() {
     let b2b_response = client.b2b(
         "testapi496",
         CommandId::BusinessToBusinessTransfer,
-        1000,
+        Amount::try_new(1000).unwrap(),
         "600496",
-        4,
+        IdentifierTypes::Shortcode,
         "600000",
-        4,
+        IdentifierTypes::Shortcode,
         "gg",
         "https://muriuki.dev",
         "https://muriuki.dev/blog",
         "254708374149",
+        None,
     ).unwrap();
 
     println!("B2b response -> {:#?}", b2b_response);
 
     assert_eq!(b2b_response.ResponseCode, "0".to_string());
-}
\ No newline at end of file
+}
+
+#[test]
+fn b2b_invalid_command_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder.record_requests());
+
+    let result = client.b2b(
+        "testapi496",
+        CommandId::SalaryPayment,
+        Amount::try_new(1000).unwrap(),
+        "600496",
+        IdentifierTypes::Shortcode,
+        "600000",
+        IdentifierTypes::Shortcode,
+        "gg",
+        "https://muriuki.dev",
+        "https://muriuki.dev/blog",
+        "254708374149",
+        None,
+    );
+
+    assert!(matches!(result, Err(MpesaError::InvalidCommand(CommandId::SalaryPayment))));
+    assert!(client.last_request().is_none());
+}
@@ -1,5 +1,11 @@
-use mpesa::{Mpesa,Environment,CommandId};
-use dotenv;
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,client_with,serve_requests,TOKEN};
+use mpesa::{Mpesa,Environment,Amount,CommandId,FieldError,MpesaError,PhoneNumber,ShortCode,SignedRequest};
+use mpesa::payloads::{B2cBuilder,B2cRequest};
+use mpesa::limits::{Limit,Limits};
 use std::env;
 
 #[test]
@@ -15,19 +21,348 @@ fn b2c_test() {
 
     println!("{:#?}", client);
 
-    let b2c_response = client.b2c(
-        "testapi496",
-        CommandId::BusinessPayment,
-        1000,
-        "600496",
-        "254708374149",
-        "gg",
-        "https://muriuki.dev",
-        "https://muriuki.dev/blog",
-        "Test",
-    ).unwrap();
+    let b2c_response = client.b2c()
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .party_b(&PhoneNumber::new("254708374149").unwrap())
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .occasion("Test")
+        .send()
+        .unwrap();
 
     println!("B2c response -> {:#?}", b2c_response);
 
     assert_eq!(b2c_response.ResponseCode, "0".to_string());
-}
\ No newline at end of file
+}
+
+#[test]
+fn b2c_missing_field_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+
+    let b2c_response = client.b2c()
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .occasion("Test")
+        .send();
+
+    match b2c_response {
+        Err(MpesaError::MissingField(field)) => assert_eq!(field, "party_b"),
+        _ => panic!("expected a missing party_b"),
+    }
+}
+
+#[test]
+fn b2c_originator_conversation_id_test() {
+    let response = r#"{"ConversationID":"AG_20191219_00005797af5d7d75f652","OriginatorConversationID":"b2c-1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#;
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", response),
+        ("200 OK", response),
+    ]);
+
+    let client = client(url);
+    let party_a = ShortCode::new("600496").unwrap();
+    let party_b = PhoneNumber::new("254708374149").unwrap();
+    let b2c = || client.b2c()
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&party_a)
+        .party_b(&party_b)
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .occasion("Test");
+
+    b2c().originator_conversation_id("b2c-1").send().unwrap();
+    b2c().send().unwrap();
+
+    let requests = requests.lock().unwrap();
+    let explicit: serde_json::Value = requests[1].json();
+    let generated: serde_json::Value = requests[2].json();
+
+    assert_eq!(explicit["OriginatorConversationID"], "b2c-1");
+    assert_eq!(generated["OriginatorConversationID"].as_str().unwrap().len(), 36);
+}
+
+#[test]
+fn b2c_duplicate_request_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"ConversationID":"AG_20191219_00005797af5d7d75f652","OriginatorConversationID":"b2c-1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#),
+        ("409 Conflict", r#"{"requestId":"11728-2929992-1","errorCode":"400.002.02","errorMessage":"Duplicate OriginatorConversationID."}"#),
+    ]);
+
+    let client = client(url);
+    let party_a = ShortCode::new("600496").unwrap();
+    let party_b = PhoneNumber::new("254708374149").unwrap();
+    let b2c = || client.b2c()
+        .originator_conversation_id("b2c-1")
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&party_a)
+        .party_b(&party_b)
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .send();
+
+    b2c().unwrap();
+
+    match b2c().unwrap_err() {
+        MpesaError::DuplicateRequest { originator_conversation_id, message } => {
+            assert_eq!(originator_conversation_id, "b2c-1");
+            assert_eq!(message, "Duplicate OriginatorConversationID.");
+        }
+        error => panic!("expected a duplicate request, got {:?}", error),
+    }
+}
+
+fn payment<'a>(builder: B2cBuilder<'a, Mpesa>, party_a: &'a ShortCode, party_b: &'a PhoneNumber) -> B2cBuilder<'a, Mpesa> {
+    builder
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(party_a)
+        .party_b(party_b)
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+}
+
+#[test]
+fn b2c_security_credential_test() {
+    let response = r#"{"ConversationID":"AG_20191219_00005797af5d7d75f652","OriginatorConversationID":"b2c-1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#;
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", response),
+        ("200 OK", response),
+    ]);
+
+    // No initiator password: the credential is computed by an HSM
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url))
+        .security_credential("hsm-credential")
+        .build()
+        .unwrap();
+    let party_a = ShortCode::new("600496").unwrap();
+    let party_b = PhoneNumber::new("254708374149").unwrap();
+
+    payment(client.b2c(), &party_a, &party_b).send().unwrap();
+    payment(client.b2c(), &party_a, &party_b).security_credential("per-call-credential").send().unwrap();
+
+    let requests = requests.lock().unwrap();
+    let from_builder: serde_json::Value = requests[1].json();
+    let per_call: serde_json::Value = requests[2].json();
+    assert_eq!(from_builder["SecurityCredential"], "hsm-credential");
+    assert_eq!(per_call["SecurityCredential"], "per-call-credential");
+
+    // The credential is not carried over to another environment, leaving neither
+    let sandbox = client.with_environment(Environment::Sandbox);
+    match payment(sandbox.b2c(), &party_a, &party_b).build_request() {
+        Err(MpesaError::MissingField(field)) => assert_eq!(field, "initiator_password"),
+        other => panic!("expected a missing initiator_password, got {:?}", other),
+    }
+}
+
+#[test]
+fn b2c_command_id_helpers_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder.record_requests());
+    let party_a = ShortCode::new("600496").unwrap();
+    let party_b = PhoneNumber::new("254708374149").unwrap();
+
+    let payments = vec![
+        (client.pay_salary(), "SalaryPayment"),
+        (client.pay_promotion(), "PromotionPayment"),
+        (client.business_payment(), "BusinessPayment"),
+    ];
+
+    for (builder, command_id) in payments {
+        builder
+            .initiator_name("testapi496")
+            .amount(Amount::try_new(1000).unwrap())
+            .party_a(&party_a)
+            .party_b(&party_b)
+            .remarks("gg")
+            .queue_timeout_url("https://muriuki.dev")
+            .result_url("https://muriuki.dev/blog")
+            .occasion("Test")
+            .send()
+            .unwrap();
+
+        assert_eq!(client.last_request().unwrap().body["CommandID"], command_id);
+    }
+}
+
+#[test]
+fn b2c_limits_test() {
+    let client = |limits: Limits| client_with(Environment::Sandbox, |builder| {
+        builder
+            .limits(limits)
+            .record_requests()
+    });
+    let party_a = ShortCode::new("600496").unwrap();
+    let party_b = PhoneNumber::new("254708374149").unwrap();
+    let send = |client: &Mpesa, amount: u32| client.business_payment()
+        .initiator_name("testapi496")
+        .amount(Amount::try_new(amount).unwrap())
+        .party_a(&party_a)
+        .party_b(&party_b)
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .occasion("Test")
+        .send();
+
+    let default_limits = client(Limits::default());
+    assert!(matches!(send(&default_limits, 5), Err(MpesaError::AmountOutOfRange { min: 10, .. })));
+    assert!(send(&default_limits, 10).is_ok());
+
+    let agreed_limits = client(Limits { b2c: Limit::new(10, 70_000), ..Limits::default() });
+    assert!(matches!(send(&agreed_limits, 70_001), Err(MpesaError::AmountOutOfRange { min: 10, max: 70_000 })));
+    assert_eq!(agreed_limits.recorded_requests().unwrap().len(), 0);
+}
+
+#[test]
+fn b2c_signed_request_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"ConversationID":"AG_20191219_00005797af5d7d75f652","OriginatorConversationID":"b2c-1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#),
+    ]);
+
+    // The signing service holds the initiator password and never talks to the API
+    let signer = client(url.clone());
+
+    let signed = signer.b2c()
+        .originator_conversation_id("b2c-1")
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .party_b(&PhoneNumber::new("254708374149").unwrap())
+        .remarks("gg")
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .occasion("Test")
+        .build_request()
+        .unwrap();
+
+    assert_eq!(signed.url, format!("{}/mpesa/b2c/v1/paymentrequest", url));
+    assert_eq!(signed.method, "POST");
+    assert!(!signed.body["SecurityCredential"].as_str().unwrap().is_empty());
+
+    // The sending service only holds the client key and secret
+    let signed: SignedRequest = serde_json::from_str(&serde_json::to_string(&signed).unwrap()).unwrap();
+    let sender = Mpesa::new(
+        String::from("client_key"),
+        String::from("client_secret"),
+        Environment::Custom(url),
+        String::new(),
+    );
+
+    let response = sender.send_signed(&signed).unwrap();
+
+    assert_eq!(response["OriginatorConversationID"], "b2c-1");
+
+    let body: serde_json::Value = requests.lock().unwrap()[1].json();
+    assert_eq!(body, signed.body);
+}
+
+#[test]
+fn b2c_optional_fields_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+
+    let request = client.b2c()
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .party_b(&PhoneNumber::new("254708374149").unwrap())
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .build_request()
+        .unwrap();
+
+    assert!(request.body.get("Remarks").is_none());
+    assert!(request.body.get("Occasion").is_none());
+}
+
+#[test]
+fn b2c_batch_test() {
+    let accepted = r#"{"ConversationID":"AG_20191219_00005797af5d7d75f652","OriginatorConversationID":"b2c-1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#;
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", accepted),
+        ("400 Bad Request", r#"{"requestId":"1","errorCode":"400.002.02","errorMessage":"Bad Request - Invalid PartyB"}"#),
+        ("200 OK", accepted),
+    ]);
+
+    let client = client(url);
+    let payout = |originator_conversation_id: Option<&str>| B2cRequest {
+        originator_conversation_id: originator_conversation_id.map(String::from),
+        initiator_name: String::from("testapi496"),
+        command_id: CommandId::SalaryPayment,
+        amount: Amount::try_new(1000).unwrap(),
+        party_a: ShortCode::new("600496").unwrap(),
+        party_b: PhoneNumber::new("254708374149").unwrap(),
+        remarks: None,
+        queue_timeout_url: String::from("https://muriuki.dev"),
+        result_url: String::from("https://muriuki.dev/blog"),
+        occasion: None,
+    };
+
+    // One payment at a time, so the stub answers them in order
+    let result = client.b2c_batch(vec![payout(Some("b2c-1")), payout(None), payout(None)], 1);
+
+    assert!(!result.is_success());
+    assert_eq!(result.successes.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(result.failures.len(), 1);
+    assert!(matches!(&result.failures[0], (1, MpesaError::ApiError { code, .. }) if code == "400.002.02"));
+
+    let ids = &result.originator_conversation_ids;
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids[0], "b2c-1");
+    assert_eq!(ids[1].len(), 36);
+    assert_ne!(ids[1], ids[2]);
+
+    // A single token was requested, and each payment was sent with its id
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 4);
+    for (request, id) in requests[1..].iter().zip(ids) {
+        assert_eq!(request.json()["OriginatorConversationID"], id.as_str());
+    }
+}
+
+#[test]
+fn b2c_validation_test() {
+    let client = client_with(Environment::Production, |builder| builder);
+
+    let error = client.b2c()
+        .command_id(CommandId::BusinessPayBill)
+        .amount(Amount::try_new(1).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .result_url("http://muriuki.dev/blog")
+        .send()
+        .unwrap_err();
+
+    let errors = match error {
+        MpesaError::Validation(ref errors) => errors.clone(),
+        e => panic!("unexpected error: {}", e),
+    };
+    let fields: Vec<_> = errors.iter().map(|error| error.field).collect();
+    assert_eq!(fields, vec!["initiator_name", "command_id", "amount", "party_b", "queue_timeout_url", "result_url"]);
+    assert_eq!(errors[0], FieldError { field: "initiator_name", message: String::from("missing") });
+    assert!(errors[5].message.contains("https"));
+    assert!(error.to_string().starts_with("Invalid request: initiator_name: missing; command_id: "));
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{client,serve_requests,TOKEN};
+use mpesa::{Amount,InvoiceItem,MpesaResult,PhoneNumber,ResultCode,ShortCode};
+use serde_json::{json,Value};
+
+#[test]
+fn bill_manager_opt_in_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"app_key":"AG_2376487236_126732989KJ","resmsg":"Success","rescode":"200"}"#),
+    ]);
+
+    let response = client(url).bill_manager().opt_in(
+        &ShortCode::new("718003").unwrap(),
+        "billing@muriuki.dev",
+        &PhoneNumber::new("0708374149").unwrap(),
+        true,
+        "https://muriuki.dev/logo.png",
+        "https://muriuki.dev/api/callback",
+    ).unwrap();
+
+    assert!(response.is_success());
+    assert_eq!(response.app_key, "AG_2376487236_126732989KJ");
+
+    let body: Value = requests.lock().unwrap()[1].json();
+
+    assert_eq!(body, json!({
+        "shortcode": "718003",
+        "email": "billing@muriuki.dev",
+        "officialContact": "254708374149",
+        "sendReminders": "1",
+        "logo": "https://muriuki.dev/logo.png",
+        "callbackurl": "https://muriuki.dev/api/callback",
+    }));
+}
+
+#[test]
+fn bill_manager_single_invoice_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"Status_Message":"Invoice sent successfully","resmsg":"Success","rescode":"200"}"#),
+    ]);
+
+    let response = client(url).bill_manager().single_invoice(
+        "#9932340",
+        "John Doe",
+        &PhoneNumber::new("0708374149").unwrap(),
+        "August 2021",
+        "Jentrys",
+        NaiveDate::from_ymd_opt(2021, 10, 12).unwrap(),
+        "1ASD678H",
+        Amount::try_new(800).unwrap(),
+        &[
+            InvoiceItem { item_name: "food", amount: Amount::try_new(700).unwrap() },
+            InvoiceItem { item_name: "water", amount: Amount::try_new(100).unwrap() },
+        ],
+    ).unwrap();
+
+    assert!(response.is_success());
+    assert_eq!(response.Status_Message, "Invoice sent successfully");
+
+    let body: Value = requests.lock().unwrap()[1].json();
+
+    assert_eq!(body["dueDate"], "2021-10-12");
+    assert_eq!(body["amount"], "800");
+    assert_eq!(body["billedPhoneNumber"], "254708374149");
+    assert_eq!(body["invoiceItems"], json!([
+        { "itemName": "food", "amount": "700" },
+        { "itemName": "water", "amount": "100" },
+    ]));
+}
+
+#[test]
+fn bill_manager_reconciliation_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"resmsg":"Invoice not found","rescode":404}"#),
+    ]);
+
+    let response = client(url).bill_manager().reconciliation(
+        NaiveDate::from_ymd_opt(2021, 10, 1).unwrap(),
+        Amount::try_new(800).unwrap(),
+        "Balboa95",
+        "PJB53MYR1N",
+        &PhoneNumber::new("0708374149").unwrap(),
+        "John Doe",
+        "School Fees",
+        "955",
+    ).unwrap();
+
+    assert!(!response.is_success());
+    assert_eq!(response.error(), Some(("404", "Invoice not found")));
+    assert_eq!(response.result_code(), ResultCode::Unknown(String::from("404")));
+
+    let body: Value = requests.lock().unwrap()[1].json();
+
+    assert_eq!(body["paymentDate"], "2021-10-01");
+    assert_eq!(body["paidAmount"], "800");
+    assert_eq!(body["transactionId"], "PJB53MYR1N");
+}
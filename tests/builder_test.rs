@@ -0,0 +1,474 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{serve_requests,Response,BALANCE,TOKEN};
+use mpesa::{Mpesa,Environment,HeaderName,HeaderValue,IdentifierTypes,MpesaError,MpesaSecurity,Proxy,ShortCode,DEFAULT_USER_AGENT};
+use mpesa::environment::{PRODUCTION_CERTIFICATE,SANDBOX_CERTIFICATE};
+use mpesa::masked::Masked;
+use openssl::asn1::Asn1Time;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey,Private};
+use openssl::rsa::{Padding,Rsa};
+use openssl::x509::{X509,X509NameBuilder};
+use std::net::TcpListener;
+use std::time::Duration;
+
+#[test]
+fn builder_test() {
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password")
+        .build();
+
+    assert!(client.is_ok());
+}
+
+#[test]
+fn builder_missing_field_test() {
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password")
+        .build();
+
+    match client {
+        Err(MpesaError::MissingField(field)) => assert_eq!(field, "client_secret"),
+        _ => panic!("expected a missing client_secret"),
+    }
+
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .initiator_password("initiator_password")
+        .build();
+
+    match client {
+        Err(MpesaError::MissingField(field)) => assert_eq!(field, "environment"),
+        _ => panic!("expected a missing environment"),
+    }
+}
+
+#[test]
+fn builder_validation_test() {
+    match Mpesa::builder().client_secret(" ").build() {
+        Err(MpesaError::Validation(errors)) => {
+            let fields: Vec<_> = errors.iter().map(|error| error.field).collect();
+            assert_eq!(fields, vec!["client_key", "client_secret", "environment", "initiator_password"]);
+            assert!(errors.iter().all(|error| error.message == "missing"));
+        }
+        _ => panic!("expected a Validation"),
+    }
+}
+
+#[test]
+fn builder_timeout_test() {
+    // Accepts connections but never responds
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url))
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE)
+        .timeout(Duration::from_millis(200))
+        .connect_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let response = client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    );
+
+    match response {
+        Err(MpesaError::NetworkError(e)) => assert!(e.is_timeout()),
+        _ => panic!("expected a timeout"),
+    }
+}
+
+#[test]
+fn builder_pool_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", BALANCE),
+        ("200 OK", BALANCE),
+    ]);
+
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url))
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE)
+        .pool_idle_timeout(Duration::from_secs(10))
+        .pool_max_idle_per_host(0)
+        .build()
+        .unwrap();
+
+    // Without idle connections every request opens a new one
+    for _ in 0..2 {
+        let response = client.account_balance(
+            &ShortCode::new("600496").unwrap(),
+            IdentifierTypes::Shortcode,
+            "none",
+            "collins",
+            "https://hell.world/api",
+            "https://hello.world/api"
+        ).unwrap();
+
+        assert_eq!(response.ResponseCode, "0");
+    }
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn builder_proxy_test() {
+    let (proxy_url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", BALANCE),
+    ]);
+
+    // The host does not resolve, so requests only succeed through the proxy
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(String::from("http://mpesa.invalid")))
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE)
+        .proxy(Proxy::http(&proxy_url).unwrap())
+        .build()
+        .unwrap();
+
+    let response = client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    assert_eq!(response.ResponseCode, "0");
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn builder_security_certificate_test() {
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(String::from("http://127.0.0.1")))
+        .initiator_password("initiator_password")
+        .security_certificate(PRODUCTION_CERTIFICATE)
+        .build()
+        .unwrap();
+
+    assert!(client.gen_security_credentials().is_ok());
+}
+
+/// A self-signed certificate with a known keypair, so credentials can be decrypted
+fn test_certificate() -> (Rsa<Private>, Vec<u8>) {
+    let rsa = Rsa::generate(2048).unwrap();
+    let key = PKey::from_rsa(rsa.clone()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", "mpesa.test").unwrap();
+    let name = name.build();
+    let mut certificate = X509::builder().unwrap();
+    certificate.set_version(2).unwrap();
+    certificate.set_subject_name(&name).unwrap();
+    certificate.set_issuer_name(&name).unwrap();
+    certificate.set_pubkey(&key).unwrap();
+    certificate.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+    certificate.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+    certificate.sign(&key, MessageDigest::sha256()).unwrap();
+
+    (rsa, certificate.build().to_pem().unwrap())
+}
+
+fn decrypt(rsa: &Rsa<Private>, credentials: String) -> Vec<u8> {
+    let mut decrypted = vec![0; rsa.size() as usize];
+    let length = rsa.private_decrypt(&base64::decode(credentials).unwrap(), &mut decrypted, Padding::PKCS1).unwrap();
+    decrypted.truncate(length);
+    decrypted
+}
+
+#[test]
+fn security_credentials_certificate_test() {
+    let (rsa, certificate) = test_certificate();
+
+    let builder = || Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(String::from("http://127.0.0.1")))
+        .initiator_password("initiator_password");
+
+    let credentials = builder().security_certificate(certificate).build().unwrap()
+        .gen_security_credentials()
+        .unwrap();
+    assert_eq!(decrypt(&rsa, credentials), b"initiator_password");
+
+    // Custom environments have no bundled certificate to fall back to
+    let error = builder().build().unwrap().gen_security_credentials().unwrap_err();
+    assert!(error.to_string().contains("require a security certificate"));
+}
+
+#[test]
+fn refresh_security_certificate_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", SANDBOX_CERTIFICATE)]);
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url.clone()))
+        .initiator_password("initiator_password")
+        .security_certificate_url(format!("{}/certificate.cer", url))
+        .build()
+        .unwrap();
+
+    assert!(client.gen_security_credentials().is_err());
+
+    client.refresh_security_certificate().unwrap();
+
+    assert!(client.gen_security_credentials().is_ok());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn refresh_security_certificate_replaces_credentials_test() {
+    let (rsa, certificate) = test_certificate();
+    let (url, _) = serve_requests(vec![
+        Response::new("200 OK", String::from_utf8(certificate).unwrap()),
+    ]);
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password")
+        .security_certificate_url(format!("{}/certificate.cer", url))
+        .build()
+        .unwrap();
+
+    // Cached, encrypted with the bundled sandbox certificate
+    let stale = client.gen_security_credentials().unwrap();
+
+    client.refresh_security_certificate().unwrap();
+
+    let credentials = client.gen_security_credentials().unwrap();
+    assert_ne!(credentials, stale);
+    assert_eq!(decrypt(&rsa, credentials), b"initiator_password");
+}
+
+#[test]
+fn refresh_security_certificate_fallback_test() {
+    let (url, _) = serve_requests(vec![
+        ("404 Not Found", "not found"),
+        ("200 OK", "not a certificate"),
+    ]);
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password")
+        .security_certificate_url(format!("{}/certificate.cer", url))
+        .build()
+        .unwrap();
+
+    let error = client.refresh_security_certificate().unwrap_err();
+    assert!(matches!(error, MpesaError::UnexpectedResponse { status: 404, .. }));
+
+    let error = client.refresh_security_certificate().unwrap_err();
+    assert!(matches!(error, MpesaError::EncryptionError(_)));
+
+    // The bundled sandbox certificate is still used
+    assert!(client.gen_security_credentials().is_ok());
+
+    let error = Mpesa::new(
+        String::from("client_key"),
+        String::from("client_secret"),
+        Environment::Sandbox,
+        String::from("initiator_password"),
+    ).refresh_security_certificate().unwrap_err();
+    assert!(matches!(error, MpesaError::MissingField("certificate_url")));
+}
+
+#[test]
+fn security_credentials_cached_test() {
+    let client = Mpesa::new(
+        String::from("client_key"),
+        String::from("client_secret"),
+        Environment::Sandbox,
+        String::from("initiator_password"),
+    );
+
+    // PKCS1 padding is random, so encrypting twice would yield different credentials
+    let credentials = client.gen_security_credentials().unwrap();
+    assert_eq!(client.gen_security_credentials().unwrap(), credentials);
+}
+
+#[test]
+fn builder_invalid_security_certificate_test() {
+    let builder = || Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password");
+
+    match builder().security_certificate("not a certificate").build() {
+        Err(MpesaError::EncryptionError(_)) => {},
+        _ => panic!("expected an EncryptionError"),
+    }
+
+    match builder().security_certificate_path("does/not/exist.cer").build() {
+        Err(MpesaError::EncryptionError(_)) => {},
+        _ => panic!("expected an EncryptionError"),
+    }
+}
+
+#[test]
+fn debug_redacts_secrets_test() {
+    let client = Mpesa::new(
+        String::from("client_key"),
+        String::from("client_secret"),
+        Environment::Sandbox,
+        String::from("initiator_password"),
+    );
+
+    let debug = format!("{:#?}", client);
+
+    assert!(debug.contains(&format!("{:?}", Masked("client_key"))));
+    #[cfg(not(feature = "unmasked-pii"))]
+    assert!(!debug.contains("client_key\""));
+    assert!(!debug.contains("client_secret"));
+    assert!(!debug.contains("initiator_password"));
+}
+
+#[test]
+fn builder_debug_redacts_secrets_test() {
+    let builder = Mpesa::builder()
+        .client_key("key_value")
+        .client_secret("secret_value")
+        .environment(Environment::Sandbox)
+        .initiator_password("password_value")
+        .security_credential("credential_value");
+
+    let debug = format!("{:?}", builder);
+
+    assert!(debug.contains(&format!("{:?}", Masked("key_value"))));
+    assert!(debug.contains("Sandbox"));
+    #[cfg(not(feature = "unmasked-pii"))]
+    assert!(!debug.contains("key_value"));
+    assert!(!debug.contains("secret_value"));
+    assert!(!debug.contains("password_value"));
+    assert!(!debug.contains("credential_value"));
+}
+
+#[test]
+fn builder_headers_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN), ("200 OK", BALANCE)]);
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url))
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE)
+        .user_agent("partner-app/1.0")
+        .default_header(HeaderName::from_static("x-api-key"), HeaderValue::from_static("gateway_key"))
+        .build()
+        .unwrap();
+
+    client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    for request in requests.iter() {
+        assert_eq!(request.header("user-agent"), Some("partner-app/1.0"));
+        assert_eq!(request.header("x-api-key"), Some("gateway_key"));
+    }
+}
+
+#[test]
+fn builder_http_client_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN), ("200 OK", BALANCE), ("200 OK", BALANCE)]);
+    let http_client = reqwest::blocking::Client::builder()
+        .user_agent("org-client/2.0")
+        .build()
+        .unwrap();
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Custom(url))
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE)
+        // Ignored in favour of the injected client
+        .user_agent("partner-app/1.0")
+        .http_client(http_client)
+        .build()
+        .unwrap();
+    let account_balance = || client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    account_balance();
+    account_balance();
+
+    // The token is cached across calls on the injected client
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    for request in requests.iter() {
+        assert_eq!(request.header("user-agent"), Some("org-client/2.0"));
+    }
+}
+
+#[test]
+fn default_user_agent_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let client = Mpesa::new(
+        String::from("client_key"),
+        String::from("client_secret"),
+        Environment::Custom(url),
+        String::from("initiator_password"),
+    );
+
+    client.auth().unwrap();
+
+    let expected = DEFAULT_USER_AGENT.to_lowercase();
+    assert_eq!(requests.lock().unwrap()[0].header("user-agent"), Some(expected.as_str()));
+    assert!(DEFAULT_USER_AGENT.starts_with("mpesa-rust/"));
+}
+
+#[cfg(feature = "danger-accept-invalid-certs")]
+#[test]
+fn danger_accept_invalid_certs_test() {
+    let builder = || Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .initiator_password("initiator_password")
+        .danger_accept_invalid_certs(true);
+
+    assert!(builder().environment(Environment::Sandbox).build().is_ok());
+    assert!(matches!(
+        builder().environment(Environment::Production).build_async(),
+        Err(MpesaError::EnvironmentError(_))
+    ));
+}
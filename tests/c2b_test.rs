@@ -1,5 +1,10 @@
-use mpesa::{Environment,Mpesa,ResponseType,CommandId};
-use dotenv;
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,client_with,serve_requests,TOKEN};
+use mpesa::{Amount,Environment,Mpesa,MpesaError,PhoneNumber,ResponseType,CommandId,ShortCode};
+use mpesa::payloads::C2bRegistration;
 use std::env;
 
 #[test]
@@ -17,8 +22,67 @@ fn c2b_register_test() {
         "https://muriuki.dev/api",
         "https://muriuki.dev/verify",
         ResponseType::Complete,
-        "600496"
+        &ShortCode::new("600496").unwrap(),
     ).unwrap();
 
-    assert_eq!("https://sandbox.safaricom.co.ke/mpesa/c2b/v1/registerurl", c2b_register_response.url().as_str());
-}
\ No newline at end of file
+    println!("C2b register response -> {:#?}", c2b_register_response);
+
+    assert!(!c2b_register_response.OriginatorConversationID.is_empty());
+}
+
+fn simulations() -> Vec<(Amount, PhoneNumber, String)> {
+    (1..=10)
+        .map(|amount| (
+            Amount::try_new(amount).unwrap(),
+            PhoneNumber::new("254705583540").unwrap(),
+            format!("bill-{}", amount),
+        ))
+        .collect()
+}
+
+#[test]
+fn c2b_simulate_batch_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder.record_requests());
+
+    let responses = client.c2b_simulate_batch(CommandId::CustomerPayBillOnline, &ShortCode::new("600496").unwrap(), simulations(), 3);
+
+    assert_eq!(responses.len(), 10);
+    assert!(responses.iter().all(Result::is_ok));
+
+    let mut bill_ref_numbers: Vec<_> = client.recorded_requests().unwrap()
+        .into_iter()
+        .map(|request| request.body["BillRefNumber"].as_str().unwrap().to_string())
+        .collect();
+    bill_ref_numbers.sort();
+    let mut expected: Vec<_> = simulations().into_iter().map(|(_, _, bill_ref_number)| bill_ref_number).collect();
+    expected.sort();
+    assert_eq!(bill_ref_numbers, expected);
+}
+
+fn register_once(response: &'static str) -> Result<C2bRegistration, MpesaError> {
+    let (url, _) = serve_requests(vec![("200 OK", TOKEN), ("400 Bad Request", response)]);
+
+    let client = client(url);
+
+    client.c2b_register_once(
+        "https://muriuki.dev/api",
+        "https://muriuki.dev/verify",
+        ResponseType::Complete,
+        &ShortCode::new("600496").unwrap(),
+    )
+}
+
+#[test]
+fn c2b_register_once_already_registered_test() {
+    let registration = register_once(r#"{"requestId":"1","errorCode":"500.003.1001","errorMessage":"Urls are already registered"}"#).unwrap();
+
+    assert!(!registration.is_new());
+    assert_eq!(registration, C2bRegistration::AlreadyRegistered("Urls are already registered".to_string()));
+}
+
+#[test]
+fn c2b_register_once_error_test() {
+    let error = register_once(r#"{"requestId":"1","errorCode":"500.003.1001","errorMessage":"Internal Server Error"}"#).unwrap_err();
+
+    assert!(matches!(error, MpesaError::ApiError { ref message, .. } if message == "Internal Server Error"));
+}
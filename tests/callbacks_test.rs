@@ -0,0 +1,451 @@
+use mpesa::callbacks::{AccountBalanceCallback,AccountBalanceEntry,B2cCallback,B2cCallbackKind,C2bConfirmationRequest,C2bValidationRequest,C2bValidationResponse};
+use mpesa::callbacks::{parse_callback,CallbackKind,ResultParameters};
+use mpesa::callbacks::{secret_matches,CallbackAllowList,TransactionStatusCallback,SAFARICOM_CALLBACK_IPS};
+use mpesa::MpesaError;
+
+#[test]
+fn b2c_callback_success_test() {
+    let body = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "10571-7910404-1",
+            "ConversationID": "AG_20191219_00004e48cf7e3533f581",
+            "TransactionID": "NLJ41HAY6Q",
+            "ResultParameters": {
+                "ResultParameter": [
+                    { "Key": "TransactionAmount", "Value": 10 },
+                    { "Key": "TransactionReceipt", "Value": "NLJ41HAY6Q" },
+                    { "Key": "B2CRecipientIsRegisteredCustomer", "Value": "Y" },
+                    { "Key": "B2CChargesPaidAccountAvailableFunds", "Value": -4510.00 },
+                    { "Key": "ReceiverPartyPublicName", "Value": "254708374149 - John Doe" },
+                    { "Key": "TransactionCompletedDateTime", "Value": "19.12.2019 11:45:50" },
+                    { "Key": "B2CUtilityAccountAvailableFunds", "Value": 10116.00 },
+                    { "Key": "B2CWorkingAccountAvailableFunds", "Value": 900000.00 }
+                ]
+            },
+            "ReferenceData": {
+                "ReferenceItem": {
+                    "Key": "QueueTimeoutURL",
+                    "Value": "https://internalsandbox.safaricom.co.ke/mpesa/b2cresults/v1/submit"
+                }
+            }
+        }
+    }"#;
+
+    let callback: B2cCallback = serde_json::from_str(body).unwrap();
+
+    assert_eq!(callback.ResultCode, 0);
+    assert_eq!(callback.TransactionID, "NLJ41HAY6Q");
+    assert_eq!(callback.TransactionReceipt.as_deref(), Some("NLJ41HAY6Q"));
+    assert_eq!(callback.TransactionAmount, Some(10.0));
+    assert_eq!(callback.B2CUtilityAccountAvailableFunds, Some(10116.0));
+    assert_eq!(callback.B2CRecipientIsRegisteredCustomer, Some(true));
+    assert_eq!(callback.ReceiverPartyPublicName.as_deref(), Some("254708374149 - John Doe"));
+    assert_eq!(callback.parameter("TransactionAmount").and_then(|v| v.as_u64()), Some(10));
+    assert!(callback.parameter("Unknown").is_none());
+}
+
+#[test]
+fn b2c_callback_failure_test() {
+    let body = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 2001,
+            "ResultDesc": "The initiator information is invalid.",
+            "OriginatorConversationID": "29112-34801843-1",
+            "ConversationID": "AG_20191219_00006c6fddb15123addf",
+            "TransactionID": "NLJ0000000"
+        }
+    }"#;
+
+    let callback: B2cCallback = serde_json::from_str(body).unwrap();
+
+    assert_eq!(callback.ResultCode, 2001);
+    assert!(callback.TransactionReceipt.is_none());
+    assert!(callback.ResultParameters.is_empty());
+}
+
+#[test]
+fn c2b_confirmation_request_test() {
+    let body = r#"{
+        "TransactionType": "Pay Bill",
+        "TransID": "RKTQDM7W6S",
+        "TransTime": "20191122063845",
+        "TransAmount": "10",
+        "BusinessShortCode": "600638",
+        "BillRefNumber": "A123",
+        "InvoiceNumber": "",
+        "OrgAccountBalance": "49197.00",
+        "ThirdPartyTransID": "",
+        "MSISDN": "254708374149",
+        "FirstName": "John",
+        "MiddleName": "",
+        "LastName": "Doe"
+    }"#;
+
+    let request: C2bConfirmationRequest = serde_json::from_str(body).unwrap();
+
+    assert_eq!(request.TransID, "RKTQDM7W6S");
+    assert_eq!(request.TransAmount, "10");
+    assert_eq!(request.BillRefNumber, "A123");
+    assert_eq!(request.OrgAccountBalance, "49197.00");
+
+    #[cfg(not(feature = "unmasked-pii"))]
+    {
+        let debug = format!("{:?}", request);
+        assert!(debug.contains(r#"MSISDN: "2547****4149""#));
+        assert!(debug.contains(r#"BillRefNumber: "A**3""#));
+        assert!(!debug.contains("254708374149"));
+    }
+}
+
+#[test]
+fn c2b_validation_request_test() {
+    let body = r#"{
+        "TransactionType": "Pay Bill",
+        "TransID": "RKTQDM7W6S",
+        "TransTime": "20191122063845",
+        "TransAmount": "10",
+        "BusinessShortCode": "600638",
+        "MSISDN": "254708374149"
+    }"#;
+
+    let request: C2bValidationRequest = serde_json::from_str(body).unwrap();
+
+    assert_eq!(request.BusinessShortCode, "600638");
+    assert_eq!(request.FirstName, "");
+
+    assert_eq!(serde_json::to_value(C2bValidationResponse::accepted()).unwrap(), serde_json::json!({
+        "ResultCode": "0",
+        "ResultDesc": "Accepted",
+    }));
+    assert_eq!(C2bValidationResponse::rejected("C2B00012").ResultCode, "C2B00012");
+}
+
+#[test]
+fn account_balance_callback_test() {
+    let body = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "16917-22577599-3",
+            "ConversationID": "AG_20200206_00005e091a8ec6b9eac5",
+            "TransactionID": "OA90000000",
+            "ResultParameters": {
+                "ResultParameter": [
+                    {
+                        "Key": "AccountBalance",
+                        "Value": "Working Account|KES|700000.00|700000.00|0.00|0.00&Float Account|KES|0.00|0.00|0.00|0.00&Utility Account|KES|228037.00|228037.00|0.00|0.00&Charges Paid Account|KES|-1540.00|-1540.00|0.00|0.00&Organization Settlement Account|KES|0.00|0.00|0.00|0.00"
+                    },
+                    { "Key": "BOCompletedTime", "Value": 20200109125710 }
+                ]
+            },
+            "ReferenceData": {
+                "ReferenceItem": {
+                    "Key": "QueueTimeoutURL",
+                    "Value": "https://internalsandbox.safaricom.co.ke/mpesa/abresults/v1/submit"
+                }
+            }
+        }
+    }"#;
+
+    let callback: AccountBalanceCallback = serde_json::from_str(body).unwrap();
+
+    assert_eq!(callback.ResultCode, 0);
+    assert_eq!(callback.AccountBalance.len(), 5);
+    assert_eq!(callback.AccountBalance[0], AccountBalanceEntry {
+        name: "Working Account".to_string(),
+        currency: "KES".to_string(),
+        amount: 700000.0,
+        amount_cents: 70000000,
+        raw: "Working Account|KES|700000.00|700000.00|0.00|0.00".to_string(),
+    });
+    assert_eq!(callback.balance("Charges Paid Account").map(|entry| entry.amount), Some(-1540.0));
+    assert_eq!(callback.balance("Charges Paid Account").map(|entry| entry.amount_cents), Some(-154000));
+    assert_eq!(callback.BOCompletedTime.as_deref(), Some("20200109125710"));
+}
+
+#[test]
+fn account_balance_formatting_test() {
+    // Sandbox balances, with stray whitespace and thousands separators
+    let body = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "16917-22577599-3",
+            "ConversationID": "AG_20200206_00005e091a8ec6b9eac5",
+            "TransactionID": "OA90000000",
+            "ResultParameters": {
+                "ResultParameter": {
+                    "Key": "AccountBalance",
+                    "Value": "Working Account|KES|1,234,567.89|1,234,567.89|0.00|0.00 & Utility Account | KES | 228037.5 |228037.50|0.00|0.00&Broken Account|KES|12.345|0|0|0&"
+                }
+            }
+        }
+    }"#;
+
+    let callback: AccountBalanceCallback = serde_json::from_str(body).unwrap();
+
+    assert_eq!(callback.AccountBalance.len(), 2);
+    let working = callback.balance("Working Account").unwrap();
+    assert_eq!((working.amount, working.amount_cents), (1234567.89, 123456789));
+    assert_eq!(working.raw, "Working Account|KES|1,234,567.89|1,234,567.89|0.00|0.00");
+    let utility = callback.balance("Utility Account").unwrap();
+    assert_eq!((utility.currency.as_str(), utility.amount_cents), ("KES", 22803750));
+}
+
+#[test]
+fn result_parameter_amount_test() {
+    let parameters: ResultParameters = serde_json::from_str(r#"{
+        "ResultParameter": [
+            { "Key": "TransactionAmount", "Value": "KES 1,000.50" },
+            { "Key": "B2CUtilityAccountAvailableFunds", "Value": " 228037.00 " },
+            { "Key": "B2CWorkingAccountAvailableFunds", "Value": 700000.0 }
+        ]
+    }"#).unwrap();
+
+    assert_eq!(parameters.get_f64("TransactionAmount"), Some(1000.5));
+    assert_eq!(parameters.get_f64("B2CUtilityAccountAvailableFunds"), Some(228037.0));
+    assert_eq!(parameters.get_f64("B2CWorkingAccountAvailableFunds"), Some(700000.0));
+}
+
+#[test]
+fn account_balance_callback_failure_test() {
+    let body = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 2001,
+            "ResultDesc": "The initiator information is invalid.",
+            "OriginatorConversationID": "16917-22577599-3",
+            "ConversationID": "AG_20200206_00005e091a8ec6b9eac5",
+            "TransactionID": "OA90000000"
+        }
+    }"#;
+
+    let callback: AccountBalanceCallback = serde_json::from_str(body).unwrap();
+
+    assert_eq!(callback.ResultCode, 2001);
+    assert!(callback.AccountBalance.is_empty());
+    assert!(callback.BOCompletedTime.is_none());
+}
+
+#[test]
+fn b2c_callback_kind_test() {
+    let result = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "10571-7910404-1",
+            "ConversationID": "AG_20191219_00004e48cf7e3533f581",
+            "TransactionID": "NLJ41HAY6Q",
+            "ResultParameters": {
+                "ResultParameter": { "Key": "TransactionReceipt", "Value": "NLJ41HAY6Q" }
+            }
+        }
+    }"#;
+
+    match B2cCallbackKind::from_json(result).unwrap() {
+        B2cCallbackKind::Result(callback) => {
+            assert_eq!(callback.TransactionReceipt.as_deref(), Some("NLJ41HAY6Q"));
+        }
+        other => panic!("Expected a result, got {:?}", other),
+    }
+
+    let timeout = r#"{
+        "OriginatorConversationID": "10571-7910404-1",
+        "ConversationID": "AG_20191219_00004e48cf7e3533f581",
+        "ResultCode": 1037,
+        "ResultDesc": "The request timed out in the queue."
+    }"#;
+
+    match B2cCallbackKind::from_json(timeout).unwrap() {
+        B2cCallbackKind::Timeout(callback) => {
+            assert_eq!(callback.OriginatorConversationID.as_deref(), Some("10571-7910404-1"));
+            assert_eq!(callback.ResultCode.as_deref(), Some("1037"));
+            assert_eq!(callback.ResultDesc.as_deref(), Some("The request timed out in the queue."));
+        }
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+
+    match B2cCallbackKind::from_json("{}").unwrap() {
+        B2cCallbackKind::Timeout(callback) => assert!(callback.ResultCode.is_none()),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+
+    assert!(B2cCallbackKind::from_json("not json").is_err());
+    assert!(B2cCallbackKind::from_json(r#"{ "Result": {} }"#).is_err());
+}
+
+#[test]
+fn result_parameters_test() {
+    let parameters: ResultParameters = serde_json::from_str(r#"{
+        "ResultParameter": [
+            { "Key": "TransactionReceipt", "Value": "NLJ41HAY6Q" },
+            { "Key": "TransactionAmount", "Value": 10 },
+            { "Key": "B2CUtilityAccountAvailableFunds", "Value": "10116.50" },
+            { "Key": "DebitPartyCharges", "Value": " 25 " },
+            { "Key": "ReceiverPartyPublicName" }
+        ]
+    }"#).unwrap();
+
+    assert_eq!(parameters.len(), 5);
+    assert_eq!(parameters.get_str("TransactionReceipt"), Some("NLJ41HAY6Q"));
+    assert_eq!(parameters.get_str("TransactionAmount"), None);
+    assert_eq!(parameters.get_string("TransactionAmount").as_deref(), Some("10"));
+    assert_eq!(parameters.get_i64("TransactionAmount"), Some(10));
+    assert_eq!(parameters.get_i64("DebitPartyCharges"), Some(25));
+    assert_eq!(parameters.get_i64("TransactionReceipt"), None);
+    assert_eq!(parameters.get_f64("B2CUtilityAccountAvailableFunds"), Some(10116.5));
+    assert!(parameters.get("ReceiverPartyPublicName").unwrap().is_null());
+    assert!(parameters.get("Unknown").is_none());
+    assert_eq!(
+        parameters.iter().map(|parameter| parameter.Key.as_str()).next(),
+        Some("TransactionReceipt")
+    );
+
+    let single: ResultParameters = serde_json::from_str(r#"{
+        "ResultParameter": { "Key": "BOCompletedTime", "Value": 20200109125710 }
+    }"#).unwrap();
+
+    assert_eq!(single.get_i64("BOCompletedTime"), Some(20200109125710));
+    assert!(!single.is_empty());
+}
+
+#[test]
+fn parse_callback_test() {
+    let b2c = br#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "10571-7910404-1",
+            "ConversationID": "AG_20191219_00004e48cf7e3533f581",
+            "TransactionID": "NLJ41HAY6Q",
+            "ResultParameters": {
+                "ResultParameter": { "Key": "TransactionReceipt", "Value": "NLJ41HAY6Q" }
+            }
+        }
+    }"#;
+    match parse_callback(b2c).unwrap() {
+        CallbackKind::B2c(callback) => assert_eq!(callback.TransactionReceipt.as_deref(), Some("NLJ41HAY6Q")),
+        other => panic!("Expected a b2c result, got {:?}", other),
+    }
+
+    let account_balance = br#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "16917-22577599-3",
+            "ConversationID": "AG_20200206_00005e091a8ec6b9eac5",
+            "TransactionID": "OA90000000",
+            "ResultParameters": {
+                "ResultParameter": [
+                    { "Key": "AccountBalance", "Value": "Working Account|KES|700000.00|700000.00|0.00|0.00" },
+                    { "Key": "BOCompletedTime", "Value": 20200109125710 }
+                ]
+            }
+        }
+    }"#;
+    match parse_callback(account_balance).unwrap() {
+        CallbackKind::AccountBalance(callback) => assert_eq!(callback.AccountBalance.len(), 1),
+        other => panic!("Expected an account balance result, got {:?}", other),
+    }
+
+    let timeout = br#"{
+        "OriginatorConversationID": "10571-7910404-1",
+        "ResultCode": 1037,
+        "ResultDesc": "The request timed out in the queue."
+    }"#;
+    assert!(matches!(parse_callback(timeout).unwrap(), CallbackKind::B2cTimeout(_)));
+
+    let c2b = br#"{
+        "TransactionType": "Pay Bill",
+        "TransID": "RKTQDM7W6S",
+        "TransTime": "20191122063845",
+        "TransAmount": "10",
+        "BusinessShortCode": "600638",
+        "MSISDN": "25470****149"
+    }"#;
+    match parse_callback(c2b).unwrap() {
+        CallbackKind::C2b(payment) => assert_eq!(payment.BusinessShortCode, "600638"),
+        other => panic!("Expected a c2b payment, got {:?}", other),
+    }
+
+    let stk = br#"{ "Body": { "stkCallback": { "ResultCode": 0 } } }"#;
+    match parse_callback(stk).unwrap() {
+        CallbackKind::Unknown(body) => assert_eq!(body["Body"]["stkCallback"]["ResultCode"], 0),
+        other => panic!("Expected an unknown callback, got {:?}", other),
+    }
+
+    assert!(matches!(parse_callback(b"not json"), Err(MpesaError::ParseError(_))));
+    assert!(matches!(parse_callback(br#"{ "TransID": 1 }"#), Err(MpesaError::ParseError(_))));
+}
+
+#[test]
+fn callback_allow_list_test() {
+    let safaricom = CallbackAllowList::safaricom();
+    for ip in SAFARICOM_CALLBACK_IPS {
+        assert!(safaricom.is_trusted(ip.parse().unwrap()));
+    }
+    assert!(safaricom.is_trusted("::ffff:196.201.214.200".parse().unwrap()));
+    assert!(!safaricom.is_trusted("196.201.214.201".parse().unwrap()));
+    assert!(!CallbackAllowList::new().is_trusted("196.201.214.200".parse().unwrap()));
+
+    let allow_list = CallbackAllowList::new()
+        .allow("10.0.0.0/8").unwrap()
+        .allow("2001:db8::/32").unwrap();
+    assert!(allow_list.is_trusted("10.255.0.1".parse().unwrap()));
+    assert!(!allow_list.is_trusted("11.0.0.1".parse().unwrap()));
+    assert!(allow_list.is_trusted("2001:db8::1".parse().unwrap()));
+    assert!(!allow_list.is_trusted("2001:db9::1".parse().unwrap()));
+
+    assert!(CallbackAllowList::new().allow("0.0.0.0/0").unwrap().is_trusted("203.0.113.7".parse().unwrap()));
+    assert!(CallbackAllowList::new().allow("10.0.0.0/33").is_err());
+    assert!(CallbackAllowList::new().allow("safaricom.co.ke").is_err());
+
+    assert!(secret_matches("k8Jd02nQ", "k8Jd02nQ"));
+    assert!(!secret_matches("k8Jd02n", "k8Jd02nQ"));
+    assert!(!secret_matches("", ""));
+}
+
+#[test]
+fn transaction_status_callback_test() {
+    let body = r#"{
+        "Result": {
+            "ResultType": 0,
+            "ResultCode": 0,
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "1236-7134259-1",
+            "ConversationID": "AG_20210709_1234409f86436c583e3f",
+            "TransactionID": "OEI2AK4Q16",
+            "ResultParameters": {
+                "ResultParameter": [
+                    { "Key": "DebitPartyName", "Value": "600310 - Safaricom333" },
+                    { "Key": "CreditPartyName", "Value": "600000 - ACME LTD" },
+                    { "Key": "TransactionStatus", "Value": "Completed" },
+                    { "Key": "ReceiptNo", "Value": "OEI2AK4Q16" },
+                    { "Key": "Amount", "Value": 300 },
+                    { "Key": "FinalisedTime", "Value": 20210709091144 }
+                ]
+            }
+        }
+    }"#;
+
+    let callback: TransactionStatusCallback = serde_json::from_str(body).unwrap();
+    assert_eq!(callback.TransactionStatus.as_deref(), Some("Completed"));
+    assert_eq!(callback.Amount, Some(300.0));
+    assert_eq!(callback.FinalisedTime.as_deref(), Some("20210709091144"));
+    assert_eq!(callback.debit_party_registered_name(), Some("Safaricom333"));
+    assert_eq!(callback.credit_party_registered_name(), Some("ACME LTD"));
+
+    match parse_callback(body.as_bytes()).unwrap() {
+        CallbackKind::TransactionStatus(callback) => assert_eq!(callback.ReceiptNo.as_deref(), Some("OEI2AK4Q16")),
+        callback => panic!("unexpected callback {:?}", callback),
+    }
+}
@@ -0,0 +1,145 @@
+#![allow(dead_code)]
+
+use std::io::{BufRead,BufReader,Read,Write};
+use std::net::TcpListener;
+use std::sync::{Arc,Mutex};
+use std::thread;
+use mpesa::{Mpesa,MpesaBuilder,Environment};
+use mpesa::environment::SANDBOX_CERTIFICATE;
+
+pub const TOKEN: &str = r#"{"access_token":"token","expires_in":"3599"}"#;
+pub const BALANCE: &str = r#"{"OriginatorConversationID":"1","ConversationID":"2","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#;
+
+/// A client of the stub server at `url`
+pub fn client(url: String) -> Mpesa {
+    client_with(Environment::Custom(url), |builder| builder)
+}
+
+/// A client of `environment` with test credentials and the sandbox certificate, built
+/// with the options `options` sets on its builder
+pub fn client_with<F>(environment: Environment, options: F) -> Mpesa
+where
+    F: FnOnce(MpesaBuilder) -> MpesaBuilder,
+{
+    let builder = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(environment)
+        .initiator_password("initiator_password")
+        .security_certificate(SANDBOX_CERTIFICATE);
+
+    options(builder).build().unwrap()
+}
+
+/// A request received by the stub server
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// Method and path, e.g. `POST /mpesa/b2c/v1/paymentrequest`, without the query
+    pub target: String,
+    /// The query, e.g. `grant_type=client_credentials`, or an empty string
+    pub query: String,
+    /// Lowercased header lines, e.g. `authorization: bearer token`
+    pub headers: Vec<String>,
+    pub body: String,
+}
+
+impl Request {
+    /// The lowercased value of the header `name`
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let prefix = format!("{}: ", name);
+        self.headers.iter().find_map(|header| header.strip_prefix(prefix.as_str()))
+    }
+
+    /// The body parsed as JSON
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+/// A response for the stub server to send, sent as JSON unless a `Content-Type` header is set
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// Status code and reason, e.g. `429 Too Many Requests`
+    pub status: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new<B: Into<String>>(status: &'static str, body: B) -> Self {
+        Self { status, headers: Vec::new(), body: body.into() }
+    }
+
+    /// Adds the header `name`, e.g. `Retry-After`
+    pub fn header<V: Into<String>>(mut self, name: &'static str, value: V) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+}
+
+impl From<(&'static str, &'static str)> for Response {
+    fn from((status, body): (&'static str, &'static str)) -> Self {
+        Self::new(status, body)
+    }
+}
+
+/// Serves `responses` in order, one per connection, recording every request received
+pub fn serve_requests<R: Into<Response>>(responses: Vec<R>) -> (String, Arc<Mutex<Vec<Request>>>) {
+    let responses: Vec<Response> = responses.into_iter().map(Into::into).collect();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+
+    thread::spawn(move || {
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            let mut request_line = String::new();
+            let mut headers = Vec::new();
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if request_line.is_empty() {
+                    request_line = line.trim_end().to_string();
+                    continue;
+                }
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                headers.push(line.trim_end().to_lowercase());
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            let target = request_line.rsplit_once(' ').map_or("", |(target, _)| target);
+            let (target, query) = target.split_once('?').unwrap_or((target, ""));
+            received.lock().unwrap().push(Request {
+                target: target.to_string(),
+                query: query.to_string(),
+                headers,
+                body: String::from_utf8(request_body).unwrap(),
+            });
+
+            let mut headers = response.headers;
+            if !headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type")) {
+                headers.push(("Content-Type", String::from("application/json")));
+            }
+            write!(stream, "HTTP/1.1 {}\r\n", response.status).unwrap();
+            for (name, value) in headers {
+                write!(stream, "{}: {}\r\n", name, value).unwrap();
+            }
+            write!(
+                stream,
+                "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.body.len(), response.body
+            ).unwrap();
+        }
+    });
+
+    (url, requests)
+}
@@ -0,0 +1,163 @@
+use mpesa::{CommandId,Environment,IdentifierTypes,MpesaResult,ResponseType,ResultCode};
+use mpesa::payloads::ExpressQueryResponse;
+use std::collections::{HashMap,HashSet};
+use std::convert::TryFrom;
+
+#[test]
+fn identifier_types_display_test() {
+    assert_eq!(IdentifierTypes::Msisdn.to_string(), "1");
+    assert_eq!(IdentifierTypes::TillNumber.to_string(), "2");
+    assert_eq!(IdentifierTypes::Shortcode.to_string(), "4");
+    assert_eq!(IdentifierTypes::Reversal.to_string(), "11");
+}
+
+#[test]
+fn identifier_types_code_test() {
+    let identifier_types = [
+        (IdentifierTypes::Msisdn, "1"),
+        (IdentifierTypes::TillNumber, "2"),
+        (IdentifierTypes::Shortcode, "4"),
+        (IdentifierTypes::Reversal, "11"),
+    ];
+
+    for (identifier_type, code) in identifier_types.iter() {
+        assert_eq!(identifier_type.code(), *code);
+        assert_eq!(serde_json::to_value(identifier_type).unwrap(), *code);
+    }
+}
+
+#[test]
+fn identifier_types_from_code_test() {
+    for identifier_type in [IdentifierTypes::Msisdn, IdentifierTypes::TillNumber, IdentifierTypes::Shortcode, IdentifierTypes::Reversal].iter() {
+        assert_eq!(IdentifierTypes::from_code(identifier_type.code()), Some(*identifier_type));
+        assert_eq!(IdentifierTypes::try_from(identifier_type.code()).unwrap(), *identifier_type);
+    }
+
+    for code in ["0", "3", "", "Shortcode", " 4"].iter() {
+        assert_eq!(IdentifierTypes::from_code(code), None);
+        assert_eq!(
+            IdentifierTypes::try_from(*code).unwrap_err().to_string(),
+            format!("unknown identifier type code: {}", code)
+        );
+    }
+}
+
+#[test]
+fn command_id_round_trip_test() {
+    let command_ids = [
+        (CommandId::TransactionReversal, "TransactionReversal"),
+        (CommandId::SalaryPayment, "SalaryPayment"),
+        (CommandId::BusinessPayment, "BusinessPayment"),
+        (CommandId::PromotionPayment, "PromotionPayment"),
+        (CommandId::AccountBalance, "AccountBalance"),
+        (CommandId::CustomerPayBillOnline, "CustomerPayBillOnline"),
+        (CommandId::CustomerBuyGoodsOnline, "CustomerBuyGoodsOnline"),
+        (CommandId::TransactionStatusQuery, "TransactionStatusQuery"),
+        (CommandId::CheckIdentity, "CheckIdentity"),
+        (CommandId::BusinessPayBill, "BusinessPayBill"),
+        (CommandId::BusinessBuyGoods, "BusinessBuyGoods"),
+        (CommandId::DisburseFundsToBusiness, "DisburseFundsToBusiness"),
+        (CommandId::BusinessToBusinessTransfer, "BusinessToBusinessTransfer"),
+        (CommandId::BusinessTransferFromMMFToUtility, "BusinessTransferFromMMFToUtility"),
+        (CommandId::PayTaxToKRA, "PayTaxToKRA"),
+    ];
+
+    for (command_id, wire) in command_ids.iter() {
+        assert_eq!(command_id.to_string(), *wire);
+        assert_eq!(serde_json::to_value(command_id).unwrap(), *wire);
+        assert_eq!(serde_json::from_value::<CommandId>(serde_json::json!(wire)).unwrap(), *command_id);
+        assert_eq!(wire.parse::<CommandId>().unwrap(), *command_id);
+    }
+}
+
+#[test]
+fn command_id_unknown_test() {
+    assert!("businesspayment".parse::<CommandId>().is_err());
+    assert!(serde_json::from_str::<CommandId>("\"Unknown\"").is_err());
+}
+
+#[test]
+fn command_id_api_test() {
+    assert!(CommandId::SalaryPayment.is_b2c());
+    assert!(!CommandId::SalaryPayment.is_b2b());
+    assert!(CommandId::BusinessBuyGoods.is_b2b());
+    assert!(!CommandId::BusinessBuyGoods.is_c2b());
+    assert!(CommandId::CustomerBuyGoodsOnline.is_c2b());
+    assert!(!CommandId::AccountBalance.is_b2c());
+}
+
+#[test]
+fn result_code_test() {
+    let result_codes = [
+        (ResultCode::Success, "0"),
+        (ResultCode::InsufficientFunds, "1"),
+        (ResultCode::InternalFailure, "17"),
+        (ResultCode::TrafficBlocking, "26"),
+        (ResultCode::SubscriberLocked, "1001"),
+        (ResultCode::TransactionExpired, "1019"),
+        (ResultCode::CancelledByUser, "1032"),
+        (ResultCode::UserUnreachable, "1037"),
+        (ResultCode::InitiatorInformationInvalid, "2001"),
+    ];
+
+    for (result_code, code) in result_codes.iter() {
+        assert_eq!(ResultCode::from_code(code), *result_code);
+        assert_eq!(result_code.code(), *code);
+        assert_eq!(result_code.to_string(), *code);
+        assert_eq!(result_code.is_success(), *code == "0");
+    }
+
+    assert_eq!(ResultCode::from_code(" 00 "), ResultCode::Success);
+    assert_eq!(ResultCode::from("1032").description(), "Request cancelled by the user");
+
+    let unknown = ResultCode::from_code("500.001.1001");
+    assert_eq!(unknown, ResultCode::Unknown(String::from("500.001.1001")));
+    assert_eq!(unknown.code(), "500.001.1001");
+    assert!(!unknown.is_success());
+}
+
+#[test]
+fn response_result_code_test() {
+    let response: ExpressQueryResponse = serde_json::from_str(r#"{
+        "CheckoutRequestID": "ws_CO_191220191020363925",
+        "MerchantRequestID": "29115-34620561-1",
+        "ResponseCode": "0",
+        "ResponseDescription": "The service request has been accepted successsfully",
+        "ResultCode": "1032",
+        "ResultDesc": "Request cancelled by user"
+    }"#).unwrap();
+
+    assert_eq!(response.ResultCode, "1032");
+    assert_eq!(response.result_code(), ResultCode::CancelledByUser);
+}
+
+#[test]
+fn enums_as_map_keys_test() {
+    let mut limits = HashMap::new();
+    limits.insert(CommandId::BusinessPayment, 150_000);
+    limits.insert(CommandId::SalaryPayment, 250_000);
+    limits.insert(CommandId::BusinessPayment, 70_000);
+
+    assert_eq!(limits.len(), 2);
+    assert_eq!(limits[&CommandId::BusinessPayment], 70_000);
+    assert_eq!(limits.get(&CommandId::PromotionPayment), None);
+
+    let identifier_types: HashSet<_> = vec![IdentifierTypes::Msisdn, IdentifierTypes::Shortcode, IdentifierTypes::Msisdn]
+        .into_iter()
+        .collect();
+    assert_eq!(identifier_types.len(), 2);
+
+    let response_types: HashSet<_> = vec![ResponseType::Complete, ResponseType::Cancelled].into_iter().collect();
+    assert!(response_types.contains(&ResponseType::Cancelled));
+
+    let mut clients = HashMap::new();
+    clients.insert(Environment::Sandbox, "sandbox");
+    clients.insert(Environment::Custom(String::from("http://127.0.0.1:8080")), "mock");
+    assert_eq!(clients[&Environment::Sandbox], "sandbox");
+    assert_eq!(clients.get(&Environment::Production), None);
+
+    let mut counts = HashMap::new();
+    *counts.entry(ResultCode::from_code("1")).or_insert(0) += 1;
+    *counts.entry(ResultCode::from_code("1")).or_insert(0) += 1;
+    assert_eq!(counts[&ResultCode::InsufficientFunds], 2);
+}
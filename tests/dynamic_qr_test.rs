@@ -0,0 +1,37 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,serve_requests,TOKEN};
+use mpesa::{Amount,QrTransactionType};
+
+#[test]
+fn dynamic_qr_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"ResponseCode":"AG_20191219_000043fdf61864fe9ff5","RequestID":"16738-27456357-1","ResponseDescription":"QR Code Successfully Generated.","QRCode":"iVBORw0KGgo"}"#),
+    ]);
+
+    let client = client(url);
+
+    let qr_response = client.dynamic_qr(
+        "TEST SUPERMARKET",
+        "Invoice Test",
+        Amount::try_new(1).unwrap(),
+        QrTransactionType::BuyGoods,
+        "373132",
+        300,
+    ).unwrap();
+
+    assert_eq!(qr_response.QRCode, "iVBORw0KGgo");
+}
+
+#[test]
+fn qr_transaction_type_test() {
+    assert_eq!(QrTransactionType::BuyGoods.to_string(), "BG");
+    assert_eq!(QrTransactionType::WithdrawCash.to_string(), "WA");
+    assert_eq!(QrTransactionType::PayBill.to_string(), "PB");
+    assert_eq!(QrTransactionType::SendMoney.to_string(), "SM");
+    assert_eq!(QrTransactionType::SendToBusiness.to_string(), "SB");
+    assert_eq!(serde_json::to_value(QrTransactionType::PayBill).unwrap(), "PB");
+}
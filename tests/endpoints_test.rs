@@ -0,0 +1,59 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,serve_requests,TOKEN};
+use mpesa::endpoints::Endpoint;
+use mpesa::{Environment,ResponseType,ShortCode};
+use std::collections::HashSet;
+
+#[test]
+fn endpoints_url_test() {
+    let urls: Vec<_> = Endpoint::ALL
+        .iter()
+        .map(|endpoint| endpoint.url(Environment::Sandbox.base_url()))
+        .collect();
+
+    assert_eq!(urls, vec![
+        "https://sandbox.safaricom.co.ke/oauth/v1/generate",
+        "https://sandbox.safaricom.co.ke/mpesa/b2b/v1/paymentrequest",
+        "https://sandbox.safaricom.co.ke/mpesa/b2c/v1/paymentrequest",
+        "https://sandbox.safaricom.co.ke/mpesa/c2b/v1/registerurl",
+        "https://sandbox.safaricom.co.ke/mpesa/c2b/v1/simulate",
+        "https://sandbox.safaricom.co.ke/mpesa/accountbalance/v1/query",
+        "https://sandbox.safaricom.co.ke/mpesa/transactionstatus/v1/query",
+        "https://sandbox.safaricom.co.ke/mpesa/reversal/v1/request",
+        "https://sandbox.safaricom.co.ke/mpesa/stkpush/v1/processrequest",
+        "https://sandbox.safaricom.co.ke/mpesa/stkpushquery/v1/query",
+        "https://sandbox.safaricom.co.ke/v1/ussdpush/get-msisdn",
+        "https://sandbox.safaricom.co.ke/mpesa/qrcode/v1/generate",
+        "https://sandbox.safaricom.co.ke/mpesa/b2b/v1/remittax",
+        "https://sandbox.safaricom.co.ke/standingorder/v1/createStandingOrderExternal",
+        "https://sandbox.safaricom.co.ke/v1/billmanager-invoice/optin",
+        "https://sandbox.safaricom.co.ke/v1/billmanager-invoice/single-invoicing",
+        "https://sandbox.safaricom.co.ke/v1/billmanager-invoice/reconciliation",
+    ]);
+    assert_eq!(urls.iter().collect::<HashSet<_>>().len(), urls.len());
+    assert_eq!(Endpoint::B2c.url("http://127.0.0.1:8080/"), "http://127.0.0.1:8080/mpesa/b2c/v1/paymentrequest");
+}
+
+#[test]
+fn endpoints_request_target_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"OriginatorConversationID":"1","ConversationID":"2","ResponseCode":"0","ResponseDescription":"success"}"#),
+    ]);
+
+    let client = client(url);
+
+    client.c2b_register(
+        "https://muriuki.dev/api",
+        "https://muriuki.dev/verify",
+        ResponseType::Complete,
+        &ShortCode::new("600496").unwrap(),
+    ).unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0].target, format!("GET {}", Endpoint::Auth.path()));
+    assert_eq!(requests[1].target, format!("POST {}", Endpoint::C2bRegister.path()));
+}
@@ -0,0 +1,49 @@
+use mpesa::Environment;
+use mpesa::environment::{PRODUCTION_BASE_URL,PRODUCTION_CERTIFICATE,SANDBOX_BASE_URL,SANDBOX_CERTIFICATE};
+use openssl::x509::X509;
+
+#[test]
+fn environment_from_str_test() {
+    assert!(matches!("production".parse::<Environment>(), Ok(Environment::Production)));
+    assert!(matches!("Sandbox".parse::<Environment>(), Ok(Environment::Sandbox)));
+    assert!(matches!("PRODUCTION".parse::<Environment>(), Ok(Environment::Production)));
+    assert!("staging".parse::<Environment>().is_err());
+    assert!("".parse::<Environment>().is_err());
+}
+
+#[test]
+fn environment_display_test() {
+    assert_eq!(Environment::Production.to_string(), "production");
+    assert_eq!(Environment::Sandbox.to_string(), "sandbox");
+    assert!(matches!(Environment::Sandbox.to_string().parse::<Environment>(), Ok(Environment::Sandbox)));
+}
+
+#[test]
+fn environment_base_url_test() {
+    // A typo here breaks every call
+    assert_eq!(Environment::Production.base_url(), "https://api.safaricom.co.ke");
+    assert_eq!(Environment::Sandbox.base_url(), "https://sandbox.safaricom.co.ke");
+    assert_eq!(Environment::Production.base_url(), PRODUCTION_BASE_URL);
+    assert_eq!(Environment::Sandbox.base_url(), SANDBOX_BASE_URL);
+}
+
+#[test]
+fn environment_custom_base_url_test() {
+    let env = Environment::Custom(String::from("http://127.0.0.1:8080"));
+    assert_eq!(env.base_url(), "http://127.0.0.1:8080");
+    assert_eq!(env.certificate(), None);
+
+    let env = Environment::Custom(String::from("http://127.0.0.1:8080//"));
+    assert_eq!(env.base_url(), "http://127.0.0.1:8080");
+}
+
+#[test]
+fn environment_certificate_test() {
+    assert_eq!(Environment::Production.certificate(), Some(PRODUCTION_CERTIFICATE));
+    assert_eq!(Environment::Sandbox.certificate(), Some(SANDBOX_CERTIFICATE));
+    assert_ne!(PRODUCTION_CERTIFICATE, SANDBOX_CERTIFICATE);
+
+    for certificate in [PRODUCTION_CERTIFICATE, SANDBOX_CERTIFICATE].iter() {
+        assert!(X509::from_pem(certificate.as_bytes()).is_ok());
+    }
+}
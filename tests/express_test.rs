@@ -0,0 +1,113 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,serve_requests,TOKEN};
+use mpesa::{Mpesa,Environment,Amount,CommandId,MpesaError,PhoneNumber};
+use std::env;
+use std::time::Duration;
+
+const PENDING: &str = r#"{"requestId":"1","errorCode":"500.001.1001","errorMessage":"The transaction is being processed"}"#;
+const CANCELLED: &str = r#"{"ResponseCode":"0","ResponseDescription":"The service request has been accepted successsfully","MerchantRequestID":"1","CheckoutRequestID":"ws_CO_1","ResultCode":"1032","ResultDesc":"Request cancelled by user"}"#;
+
+#[test]
+fn express_request_test() {
+    dotenv::dotenv().ok();
+
+    let client = Mpesa::new(
+        env::var("CLIENT_KEY").unwrap(),
+        env::var("CLIENT_SECRET").unwrap(),
+        Environment::Sandbox, // or environment variable
+        env::var("INIT_PASSWORD").unwrap(),
+    );
+
+    let express_response = client.express_request(
+        "174379",
+        "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+        CommandId::CustomerPayBillOnline,
+        Amount::try_new(1).unwrap(),
+        &PhoneNumber::new("254708374149").unwrap(),
+        "174379",
+        &PhoneNumber::new("254708374149").unwrap(),
+        "https://muriuki.dev/api/callback",
+        "test",
+        "test",
+    ).unwrap();
+
+    println!("Express response -> {:#?}", express_response);
+
+    assert_eq!(express_response.ResponseCode, "0".to_string());
+}
+
+#[test]
+fn express_query_test() {
+    dotenv::dotenv().ok();
+
+    let client = Mpesa::new(
+        env::var("CLIENT_KEY").unwrap(),
+        env::var("CLIENT_SECRET").unwrap(),
+        Environment::Sandbox, // or environment variable
+        env::var("INIT_PASSWORD").unwrap(),
+    );
+
+    let express_response = client.express_request(
+        "174379",
+        "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+        CommandId::CustomerPayBillOnline,
+        Amount::try_new(1).unwrap(),
+        &PhoneNumber::new("254708374149").unwrap(),
+        "174379",
+        &PhoneNumber::new("254708374149").unwrap(),
+        "https://muriuki.dev/api/callback",
+        "test",
+        "test",
+    ).unwrap();
+
+    let express_query_response = client.express_query(
+        "174379",
+        "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+        &express_response.CheckoutRequestID,
+    );
+
+    println!("Express query response -> {:#?}", express_query_response);
+}
+
+#[test]
+fn express_wait_polls_until_final_result_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("500 Internal Server Error", PENDING),
+        ("500 Internal Server Error", PENDING),
+        ("200 OK", CANCELLED),
+    ]);
+
+    let express_query_response = client(url).express_wait(
+        "174379",
+        "passkey",
+        "ws_CO_1",
+        Duration::from_millis(1),
+        Duration::from_secs(5),
+    ).unwrap();
+
+    assert_eq!(express_query_response.ResultCode, "1032");
+    assert_eq!(requests.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn express_wait_timeout_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("500 Internal Server Error", PENDING),
+        ("500 Internal Server Error", PENDING),
+    ]);
+
+    let result = client(url).express_wait(
+        "174379",
+        "passkey",
+        "ws_CO_1",
+        Duration::from_millis(50),
+        Duration::from_millis(60),
+    );
+
+    assert!(matches!(result, Err(MpesaError::Timeout)));
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::client_with;
+use mpesa::{Amount,CommandId,Environment,MpesaError,PhoneNumber,ShortCode};
+use mpesa::ids::{is_valid_originator_id,new_originator_id,MAX_ORIGINATOR_ID_LEN};
+
+#[test]
+fn new_originator_id_test() {
+    for _ in 0..100 {
+        let id = new_originator_id();
+
+        // Hyphenated lowercase UUID: 8-4-4-4-12 hex digits
+        assert_eq!(id.len(), MAX_ORIGINATOR_ID_LEN);
+        let groups: Vec<_> = id.split('-').map(str::len).collect();
+        assert_eq!(groups, vec![8, 4, 4, 4, 12]);
+        assert!(id.chars().all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c)));
+        assert!(is_valid_originator_id(&id));
+    }
+}
+
+#[test]
+fn is_valid_originator_id_test() {
+    for id in &["16740-34861180-1", "b2c_1", "A", &"a".repeat(MAX_ORIGINATOR_ID_LEN)] {
+        assert!(is_valid_originator_id(id), "{} should be valid", id);
+    }
+    for id in &["", "order 1", "b2c/1", "ñ", &"a".repeat(MAX_ORIGINATOR_ID_LEN + 1)] {
+        assert!(!is_valid_originator_id(id), "{} should be invalid", id);
+    }
+}
+
+#[test]
+fn b2c_invalid_originator_id_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+    let party_a = ShortCode::new("600496").unwrap();
+    let party_b = PhoneNumber::new("254708374149").unwrap();
+
+    let error = client.b2c()
+        .originator_conversation_id("payout for order #1")
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&party_a)
+        .party_b(&party_b)
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .send()
+        .unwrap_err();
+
+    match error {
+        MpesaError::InvalidOriginatorId(id) => assert_eq!(id, "payout for order #1"),
+        e => panic!("unexpected error: {}", e),
+    }
+}
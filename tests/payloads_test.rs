@@ -0,0 +1,380 @@
+use mpesa::{Amount,CommandId,CommonResponse,IdentifierTypes,MpesaError,MpesaResult,ResponseType};
+use mpesa::payloads::{AccountBalancePayload,AccountBalanceResponse,B2bPayload,B2cPayload,B2cResponse,C2bRegisterPayload,C2bRegisterResponse};
+use mpesa::payloads::{B2cOutcome,ExpressQueryResponse,MpesaErrorResponse};
+use mpesa::payloads::{AuthResponse,B2bExpressCheckoutResponse,B2bResponse,C2bSimulateResponse,ExpressResponse};
+use mpesa::payloads::{QrResponse,StandingOrderResponse,TaxRemittanceResponse};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+#[test]
+fn b2c_payload_serialize_test() {
+    let payload = B2cPayload {
+        originator_conversation_id: String::from("b2c-1"),
+        initiator_name: "testapi496",
+        security_credentials: "credentials",
+        command_id: CommandId::BusinessPayment,
+        amount: Amount::try_new(1000).unwrap(),
+        party_a: "600496",
+        party_b: "254708374149",
+        remarks: Some("gg"),
+        queue_timeout_url: "https://muriuki.dev",
+        result_url: "https://muriuki.dev/blog",
+        occasion: Some("Test"),
+    };
+
+    assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+        "OriginatorConversationID": "b2c-1",
+        "InitiatorName": "testapi496",
+        "SecurityCredential": "credentials",
+        "CommandID": "BusinessPayment",
+        "Amount": 1000,
+        "PartyA": "600496",
+        "PartyB": "254708374149",
+        "Remarks": "gg",
+        "QueueTimeOutURL": "https://muriuki.dev",
+        "ResultURL": "https://muriuki.dev/blog",
+        "Occasion": "Test",
+    }));
+}
+
+#[test]
+fn b2c_payload_optional_fields_test() {
+    let payload = B2cPayload {
+        originator_conversation_id: String::from("b2c-1"),
+        initiator_name: "testapi496",
+        security_credentials: "credentials",
+        command_id: CommandId::BusinessPayment,
+        amount: Amount::try_new(1000).unwrap(),
+        party_a: "600496",
+        party_b: "254708374149",
+        remarks: None,
+        queue_timeout_url: "https://muriuki.dev",
+        result_url: "https://muriuki.dev/blog",
+        occasion: None,
+    };
+
+    let body = payload.to_json();
+    let body = body.as_object().unwrap();
+
+    assert!(!body.contains_key("Remarks"));
+    assert!(!body.contains_key("Occasion"));
+    assert_eq!(body.len(), 9);
+}
+
+#[test]
+fn b2b_payload_serialize_test() {
+    let payload = B2bPayload {
+        originator_conversation_id: String::from("b2b-1"),
+        initiator_name: "testapi496",
+        security_credentials: "credentials",
+        command_id: CommandId::BusinessToBusinessTransfer,
+        amount: Amount::try_new(1000).unwrap(),
+        party_a: "600496",
+        sender_id: IdentifierTypes::Shortcode,
+        party_b: "600000",
+        receiver_id: IdentifierTypes::TillNumber,
+        remarks: "gg",
+        queue_timeout_url: "https://muriuki.dev",
+        result_url: "https://muriuki.dev/blog",
+        account_ref: "254708374149",
+    };
+
+    assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+        "OriginatorConversationID": "b2b-1",
+        "Initiator": "testapi496",
+        "SecurityCredential": "credentials",
+        "CommandID": "BusinessToBusinessTransfer",
+        "Amount": 1000,
+        "PartyA": "600496",
+        "SenderIdentifierType": "4",
+        "PartyB": "600000",
+        "RecieverIdentifierType": "2",
+        "Remarks": "gg",
+        "QueueTimeOutURL": "https://muriuki.dev",
+        "ResultURL": "https://muriuki.dev/blog",
+        "AccountReference": "254708374149",
+    }));
+}
+
+#[test]
+fn account_balance_payload_serialize_test() {
+    let payload = AccountBalancePayload {
+        initiator_name: "collins",
+        security_credentials: "credentials",
+        command_id: CommandId::AccountBalance,
+        party_a: "600496",
+        identifier_type: IdentifierTypes::Shortcode,
+        remarks: "none",
+        queue_timeout_url: "https://hell.world/api",
+        result_url: "https://hello.world/api",
+    };
+
+    let value = serde_json::to_value(&payload).unwrap();
+
+    assert_eq!(value["CommandID"], "AccountBalance");
+    assert_eq!(value["IdentifierType"], "4");
+}
+
+#[test]
+fn account_balance_till_number_payload_serialize_test() {
+    let payload = AccountBalancePayload {
+        initiator_name: "collins",
+        security_credentials: "credentials",
+        command_id: CommandId::AccountBalance,
+        party_a: "5221023",
+        identifier_type: IdentifierTypes::TillNumber,
+        remarks: "none",
+        queue_timeout_url: "https://hell.world/api",
+        result_url: "https://hello.world/api",
+    };
+
+    let value = serde_json::to_value(&payload).unwrap();
+
+    assert_eq!(value["PartyA"], "5221023");
+    assert_eq!(value["IdentifierType"], "2");
+}
+
+#[test]
+fn c2b_register_payload_serialize_test() {
+    let payload = C2bRegisterPayload {
+        validation_url: "https://muriuki.dev/api",
+        confirmation_url: "https://muriuki.dev/verify",
+        response_type: ResponseType::Complete,
+        short_code: "600496",
+    };
+
+    assert_eq!(serde_json::to_value(&payload).unwrap(), json!({
+        "ValidationURL": "https://muriuki.dev/api",
+        "ConfirmationURL": "https://muriuki.dev/verify",
+        "ResponseType": "Completed",
+        "ShortCode": "600496",
+    }));
+}
+
+#[test]
+fn response_type_test() {
+    // A wrong value changes what M-Pesa does with payments it cannot validate
+    assert_eq!(serde_json::to_value(ResponseType::Complete).unwrap(), json!("Completed"));
+    assert_eq!(serde_json::to_value(ResponseType::Cancelled).unwrap(), json!("Cancelled"));
+
+    for response_type in &[ResponseType::Complete, ResponseType::Cancelled] {
+        assert_eq!(response_type.to_string().parse::<ResponseType>().unwrap(), *response_type);
+    }
+
+    assert_eq!(ResponseType::try_from("Completed").unwrap(), ResponseType::Complete);
+    assert!("Complete".parse::<ResponseType>().is_err());
+    assert_eq!(ResponseType::try_from("completed").unwrap_err().to_string(), "unknown response type: completed");
+}
+
+#[test]
+fn payload_to_json_test() {
+    let payload = C2bRegisterPayload {
+        validation_url: "https://muriuki.dev/api",
+        confirmation_url: "https://muriuki.dev/verify",
+        response_type: ResponseType::Cancelled,
+        short_code: "600496",
+    };
+
+    assert_eq!(payload.to_json(), serde_json::to_value(&payload).unwrap());
+    assert_eq!(payload.to_json()["ResponseType"], "Cancelled");
+}
+
+#[test]
+fn common_response_test() {
+    let c2b_register_response: C2bRegisterResponse = serde_json::from_value(json!({
+        "OriginatorCoversationID": "6e86-45dd-91ac-fd5d4178ab523408729",
+        "ResponseCode": "0",
+        "ResponseDescription": "Success",
+    })).unwrap();
+
+    assert_eq!(c2b_register_response.originator_conversation_id(), "6e86-45dd-91ac-fd5d4178ab523408729");
+    assert_eq!(c2b_register_response.conversation_id(), "");
+    assert_eq!(c2b_register_response.response_code(), "0");
+
+    let account_balance_response: AccountBalanceResponse = serde_json::from_value(json!({
+        "OriginatorConversationID": "1",
+        "ConversationID": "2",
+        "ResponseCode": "0",
+        "ResponseDescription": "Accept the service request successfully.",
+    })).unwrap();
+
+    assert_eq!(account_balance_response.conversation_id(), "2");
+    assert_eq!(account_balance_response.originator_conversation_id(), "1");
+    assert_eq!(account_balance_response.response_description(), "Accept the service request successfully.");
+}
+
+#[test]
+fn mpesa_result_success_test() {
+    let b2c_response: B2cResponse = serde_json::from_value(json!({
+        "ConversationID": "AG_20191219_00005797af5d7d75f652",
+        "OriginatorConversationID": "16740-34861180-1",
+        "ResponseCode": "0",
+        "ResponseDescription": "Accept the service request successfully.",
+    })).unwrap();
+
+    assert!(b2c_response.is_success());
+    assert_eq!(b2c_response.error(), None);
+}
+
+#[test]
+fn mpesa_result_failure_test() {
+    let express_query_response: ExpressQueryResponse = serde_json::from_value(json!({
+        "CheckoutRequestID": "ws_CO_260520211133524545",
+        "MerchantRequestID": "22205-34066-1",
+        "ResponseCode": "0",
+        "ResponseDescription": "The service request has been accepted successsfully",
+        "ResultCode": "1032",
+        "ResultDesc": "Request cancelled by user",
+    })).unwrap();
+
+    assert!(!express_query_response.is_success());
+    assert_eq!(express_query_response.error(), Some(("1032", "Request cancelled by user")));
+
+    let error_response: MpesaErrorResponse = serde_json::from_value(json!({
+        "requestId": "11728-2929992-1",
+        "errorCode": "401.002.01",
+        "errorMessage": "Error Occurred - Invalid Access Token",
+    })).unwrap();
+
+    assert!(!error_response.is_success());
+    assert_eq!(error_response.error(), Some(("401.002.01", "Error Occurred - Invalid Access Token")));
+}
+
+/// Deserializes `body`, serializes the response and checks that it deserializes back to the same value
+fn round_trip<T: Serialize + DeserializeOwned + Clone + PartialEq + Debug>(body: &str) {
+    let response: T = serde_json::from_str(body).unwrap();
+    let serialized = serde_json::to_string(&response.clone()).unwrap();
+
+    assert_eq!(serde_json::from_str::<T>(&serialized).unwrap(), response);
+}
+
+#[test]
+fn responses_round_trip_test() {
+    let common = r#"{"OriginatorConversationID":"1","ConversationID":"AG_1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#;
+
+    round_trip::<AccountBalanceResponse>(common);
+    round_trip::<B2bResponse>(common);
+    round_trip::<B2cResponse>(common);
+    round_trip::<C2bRegisterResponse>(common);
+    round_trip::<C2bSimulateResponse>(common);
+    round_trip::<TaxRemittanceResponse>(common);
+    round_trip::<AuthResponse>(r#"{"access_token":"token","expires_in":"3599"}"#);
+    round_trip::<B2bExpressCheckoutResponse>(r#"{"code":"0","status":"USSD Initiated Successfully"}"#);
+    round_trip::<ExpressResponse>(r#"{"MerchantRequestID":"1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"Success. Request accepted for processing","CustomerMessage":"Success. Request accepted for processing"}"#);
+    round_trip::<ExpressQueryResponse>(r#"{"MerchantRequestID":"1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"The service request has been accepted successsfully","ResultCode":"0","ResultDesc":"The service request is processed successfully."}"#);
+    round_trip::<QrResponse>(r#"{"ResponseCode":"AG_20191219_000043fdf61864fe9ff5","RequestID":"16738-27456357-1","ResponseDescription":"QR Code Successfully Generated.","QRCode":"iVBORw0KGgo"}"#);
+    round_trip::<StandingOrderResponse>(r#"{"ResponseHeader":{"responseRefID":"4dd9b5d9-d738-42ba-9326-2cc99e966000","responseCode":"200","responseDescription":"Request accepted for processing","ResultDesc":"The service request is processed successfully."},"ResponseBody":{"responseDescription":"Request accepted for processing","responseCode":"200"}}"#);
+    round_trip::<MpesaErrorResponse>(r#"{"requestId":"1","errorCode":"404.001.03","errorMessage":"Invalid Access Token"}"#);
+}
+
+#[test]
+fn numeric_codes_test() {
+    let string_codes: ExpressQueryResponse = serde_json::from_str(
+        r#"{"MerchantRequestID":"1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"Accepted","ResultCode":"1032","ResultDesc":"Request cancelled by user"}"#
+    ).unwrap();
+    let numeric_codes: ExpressQueryResponse = serde_json::from_str(
+        r#"{"MerchantRequestID":"1","CheckoutRequestID":"ws_CO_1","ResponseCode":0,"ResponseDescription":"Accepted","ResultCode":1032,"ResultDesc":"Request cancelled by user"}"#
+    ).unwrap();
+
+    assert_eq!(numeric_codes, string_codes);
+    assert_eq!(numeric_codes.ResponseCode, "0");
+    assert_eq!(numeric_codes.ResultCode, "1032");
+
+    let response: B2cResponse = serde_json::from_str(
+        r#"{"OriginatorConversationID":"1","ConversationID":"AG_1","ResponseCode":0,"ResponseDescription":"Accept the service request successfully."}"#
+    ).unwrap();
+    assert_eq!(response.ResponseCode, "0");
+    assert!(response.is_success());
+}
+
+#[test]
+fn response_display_test() {
+    let accepted: B2cResponse = serde_json::from_str(
+        r#"{"OriginatorConversationID":"16740-34861180-1","ConversationID":"AG_20191219_00005797af5d7d75f652","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#
+    ).unwrap();
+    assert_eq!(
+        accepted.to_string(),
+        "B2C accepted: ConversationID=AG_20191219_00005797af5d7d75f652 OriginatorConversationID=16740-34861180-1 code=0 (Accept the service request successfully.)",
+    );
+
+    let rejected: AccountBalanceResponse = serde_json::from_str(
+        r#"{"OriginatorConversationID":"1","ConversationID":"AG_1","ResponseCode":"1","ResponseDescription":"Rejected"}"#
+    ).unwrap();
+    assert_eq!(rejected.to_string(), "Account balance rejected: ConversationID=AG_1 OriginatorConversationID=1 code=1 (Rejected)");
+
+    let auth: AuthResponse = serde_json::from_str(r#"{"access_token":"secret-token","expires_in":"3599"}"#).unwrap();
+    assert_eq!(auth.to_string(), "Access token issued, expires in 3599s");
+    assert!(!auth.to_string().contains("secret-token"));
+
+    let qr: QrResponse = serde_json::from_str(
+        r#"{"ResponseCode":"00","RequestID":"16738-27456357-1","ResponseDescription":"QR Code Successfully Generated.","QRCode":"iVBORw0KGgo"}"#
+    ).unwrap();
+    assert!(!qr.to_string().contains("iVBORw0KGgo"));
+
+    let query: ExpressQueryResponse = serde_json::from_str(
+        r#"{"MerchantRequestID":"1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"Accepted","ResultCode":"1032","ResultDesc":"Request cancelled by user"}"#
+    ).unwrap();
+    assert_eq!(
+        query.to_string(),
+        "STK push query rejected: CheckoutRequestID=ws_CO_1 MerchantRequestID=1 code=0 result=1032 (Request cancelled by user)",
+    );
+
+    let error: MpesaErrorResponse = serde_json::from_str(r#"{"requestId":"1","errorCode":"404.001.03","errorMessage":"Invalid Access Token"}"#).unwrap();
+    assert_eq!(error.to_string(), "M-Pesa error: requestId=1 code=404.001.03 (Invalid Access Token)");
+}
+
+#[test]
+fn c2b_simulate_response_test() {
+    let accepted: C2bSimulateResponse = serde_json::from_str(
+        r#"{"OriginatorCoversationID":"53e3-4aa8-9fe0-8fb5e4092cdd3405976","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#
+    ).unwrap();
+    assert!(accepted.is_accepted());
+    assert_eq!(accepted.OriginatorConversationID, "53e3-4aa8-9fe0-8fb5e4092cdd3405976");
+    assert_eq!(accepted.ConversationID, "");
+
+    let rejected: C2bSimulateResponse = serde_json::from_str(
+        r#"{"OriginatorCoversationID":"1","ConversationID":"AG_1","ResponseCode":"1","ResponseDescription":"Short code not registered"}"#
+    ).unwrap();
+    assert!(!rejected.is_accepted());
+    assert_eq!(rejected.ResponseDescription, "Short code not registered");
+}
+
+#[test]
+fn b2c_outcome_test() {
+    let accepted: B2cOutcome = serde_json::from_str(
+        r#"{"ConversationID":"AG_20191219_00005797af5d7d75f652","OriginatorConversationID":"16740-34861180-1","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#
+    ).unwrap();
+    match &accepted {
+        B2cOutcome::Accepted(response) => assert_eq!(response.ConversationID, "AG_20191219_00005797af5d7d75f652"),
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+    assert_eq!(accepted.into_result().unwrap().OriginatorConversationID, "16740-34861180-1");
+
+    let rejected: B2cOutcome = serde_json::from_str(
+        r#"{"requestId":"11728-2929992-1","errorCode":"401.002.01","errorMessage":"Error Occurred - Invalid Access Token - BJGFGOXv5aZnw90KkA4TDtu4Xdyf"}"#
+    ).unwrap();
+    match &rejected {
+        B2cOutcome::Rejected(error) => assert_eq!(error.requestId, "11728-2929992-1"),
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+    match rejected.into_result() {
+        Err(MpesaError::ApiError { code, .. }) => assert_eq!(code, "401.002.01"),
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    let refused: B2cOutcome = serde_json::from_str(
+        r#"{"ConversationID":"AG_1","OriginatorConversationID":"1","ResponseCode":"2001","ResponseDescription":"The initiator information is invalid."}"#
+    ).unwrap();
+    match refused.into_result() {
+        Err(MpesaError::ApiError { code, message }) => {
+            assert_eq!(code, "2001");
+            assert_eq!(message, "The initiator information is invalid.");
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    assert!(serde_json::from_str::<B2cOutcome>(r#"{"ResponseCode":"0"}"#).is_err());
+}
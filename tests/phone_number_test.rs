@@ -0,0 +1,37 @@
+use mpesa::PhoneNumber;
+#[cfg(not(feature = "unmasked-pii"))]
+use mpesa::masked::Masked;
+use std::convert::TryFrom;
+
+#[test]
+fn phone_number_normalize_test() {
+    for number in &["254708374149", "+254708374149", "0708374149", "708374149", "0708 374 149", "0708-374-149"] {
+        assert_eq!(PhoneNumber::new(number).unwrap().as_str(), "254708374149");
+    }
+
+    assert_eq!(PhoneNumber::new("0110374149").unwrap().as_str(), "254110374149");
+    assert_eq!("254110374149".parse::<PhoneNumber>().unwrap().as_str(), "254110374149");
+    assert_eq!(PhoneNumber::try_from("0708374149").unwrap().as_str(), "254708374149");
+}
+
+#[test]
+fn phone_number_invalid_test() {
+    for number in &["", "0208374149", "25470837414", "2557083741490", "07083741ab", "+0708374149x"] {
+        assert!(PhoneNumber::new(number).is_err(), "{} should be invalid", number);
+    }
+}
+
+#[cfg(not(feature = "unmasked-pii"))]
+#[test]
+fn phone_number_masked_test() {
+    let number = PhoneNumber::new("0708374149").unwrap();
+
+    assert_eq!(number.to_string(), "2547****4149");
+    assert_eq!(format!("{:?}", number), r#"PhoneNumber("2547****4149")"#);
+    assert_eq!(Masked("INV-2041").to_string(), "IN****41");
+    assert_eq!(Masked("42").to_string(), "**");
+
+    let error = PhoneNumber::new("0208374149").unwrap_err();
+    assert_eq!(error.to_string(), "invalid phone number: 020****149");
+    assert_eq!(format!("{:?}", error), r#"PhoneNumberError("020****149")"#);
+}
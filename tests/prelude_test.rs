@@ -0,0 +1,25 @@
+#![cfg(feature = "client")]
+
+use mpesa::prelude::*;
+
+#[test]
+fn prelude_test() {
+    let client: Mpesa = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        .environment(Environment::Sandbox)
+        .initiator_password("initiator_password")
+        .build()
+        .unwrap();
+
+    let phone_number = PhoneNumber::new("254708374149").unwrap();
+    let _builder: B2cBuilder<'_, Mpesa> = client.b2c()
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_b(&phone_number);
+
+    let _response_type = ResponseType::Complete;
+    assert_eq!(ShortCode::new("600496").unwrap().as_str(), "600496");
+    assert_eq!(IdentifierTypes::Shortcode.code(), "4");
+    assert!(matches!(MpesaError::MissingField("client_key"), MpesaError::MissingField(_)));
+}
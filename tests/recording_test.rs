@@ -0,0 +1,106 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::client_with;
+use mpesa::{Amount,CommandId,Mpesa,Environment,IdentifierTypes,PhoneNumber,ShortCode};
+
+/// A client of the sandbox that records its requests instead of sending them
+fn recording_client() -> Mpesa {
+    client_with(Environment::Sandbox, |builder| builder.record_requests())
+}
+
+#[test]
+fn record_requests_test() {
+    let client = recording_client();
+
+    let response = client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::TillNumber,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    assert_eq!(response.ResponseCode, "0");
+
+    let request = client.last_request().unwrap();
+
+    assert_eq!(request.url, "https://sandbox.safaricom.co.ke/mpesa/accountbalance/v1/query");
+    assert_eq!(request.body["PartyA"], "600496");
+    assert_eq!(request.body["IdentifierType"], "2");
+    assert_eq!(request.body["CommandID"], "AccountBalance");
+    assert_eq!(client.recorded_requests().unwrap().len(), 1);
+}
+
+#[test]
+fn record_requests_disabled_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+
+    assert!(client.last_request().is_none());
+    assert!(client.recorded_requests().is_none());
+}
+
+#[test]
+fn post_json_test() {
+    let client = recording_client();
+
+    let response = client.post_json("/mpesa/custom/v1/query", serde_json::json!({ "ShortCode": "600496" })).unwrap();
+    assert_eq!(response["ResponseCode"], "0");
+
+    let request = client.last_request().unwrap();
+    assert_eq!(request.url, "https://sandbox.safaricom.co.ke/mpesa/custom/v1/query");
+    assert_eq!(request.body, serde_json::json!({ "ShortCode": "600496" }));
+}
+
+#[test]
+fn dry_run_test() {
+    let client = Mpesa::builder()
+        .client_key("client_key")
+        .client_secret("client_secret")
+        // Nothing listens here: any request would fail
+        .environment(Environment::Custom(String::from("http://127.0.0.1:1")))
+        .initiator_password("initiator_password")
+        .security_certificate(mpesa::environment::SANDBOX_CERTIFICATE)
+        .dry_run(true)
+        .build()
+        .unwrap();
+
+    let response = client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap();
+
+    assert_eq!(response.ResponseCode, "0");
+    assert!(response.ConversationID.starts_with("dry-run-"));
+    assert_ne!(response.ConversationID, client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    ).unwrap().ConversationID);
+
+    let b2c_response = client.b2c()
+        .originator_conversation_id("b2c-1")
+        .initiator_name("testapi496")
+        .command_id(CommandId::BusinessPayment)
+        .amount(Amount::try_new(1000).unwrap())
+        .party_a(&ShortCode::new("600496").unwrap())
+        .party_b(&PhoneNumber::new("254708374149").unwrap())
+        .queue_timeout_url("https://muriuki.dev")
+        .result_url("https://muriuki.dev/blog")
+        .send()
+        .unwrap();
+    assert_eq!(b2c_response.OriginatorConversationID, "b2c-1");
+
+    // Dry runs are not recorded
+    assert!(client.last_request().is_none());
+    assert!(client.validate_credentials().is_ok());
+}
@@ -0,0 +1,183 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client_with,serve_requests,Response,BALANCE,TOKEN};
+use mpesa::{Mpesa,Environment,IdentifierTypes,MpesaError,ShortCode};
+use mpesa::RequestMetrics;
+use std::sync::{Arc,Mutex};
+use std::time::Duration;
+
+/// A client of the stub server at `url` retrying failed requests `max_retries` times
+fn retrying_client(url: String, max_retries: u32) -> Mpesa {
+    client_with(Environment::Custom(url), |builder| {
+        builder
+            .max_retries(max_retries)
+            .base_backoff(Duration::from_millis(1))
+    })
+}
+
+fn account_balance(client: &Mpesa) -> Result<mpesa::payloads::AccountBalanceResponse, mpesa::MpesaError> {
+    client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        "https://hello.world/api"
+    )
+}
+
+#[test]
+fn retry_server_error_test() {
+    let (url, requests) = serve_requests(vec![
+        ("503 Service Unavailable", "{}"),
+        ("200 OK", TOKEN),
+        ("500 Internal Server Error", "{}"),
+        ("200 OK", BALANCE),
+    ]);
+
+    let response = account_balance(&retrying_client(url, 2)).unwrap();
+
+    assert_eq!(response.ResponseCode, "0");
+    assert_eq!(requests.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn request_metrics_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("500 Internal Server Error", "{}"),
+        ("200 OK", BALANCE),
+        ("503 Service Unavailable", "{}"),
+        ("503 Service Unavailable", "{}"),
+    ]);
+    let metrics = Arc::new(Mutex::new(Vec::<RequestMetrics>::new()));
+    let reported = metrics.clone();
+
+    let client = client_with(Environment::Custom(url), |builder| {
+        builder
+            .max_retries(1)
+            .base_backoff(Duration::from_millis(1))
+            .on_request_complete(move |request| reported.lock().unwrap().push(request.clone()))
+    });
+
+    account_balance(&client).unwrap();
+    assert!(account_balance(&client).is_err());
+
+    let metrics = metrics.lock().unwrap();
+    assert_eq!(metrics.len(), 3);
+    assert_eq!(metrics[0].endpoint, "/oauth/v1/generate");
+    assert_eq!((metrics[0].status, metrics[0].success, metrics[0].retries), (Some(200), true, 0));
+    assert_eq!(metrics[1].endpoint, "/mpesa/accountbalance/v1/query");
+    assert_eq!((metrics[1].status, metrics[1].success, metrics[1].retries), (Some(200), true, 1));
+    assert_eq!((metrics[2].status, metrics[2].success, metrics[2].retries), (Some(503), false, 1));
+}
+
+#[test]
+fn retry_exhausted_test() {
+    let (url, requests) = serve_requests(vec![
+        ("503 Service Unavailable", "{}"),
+        ("503 Service Unavailable", "{}"),
+    ]);
+
+    assert!(account_balance(&retrying_client(url, 1)).is_err());
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn no_retry_by_default_test() {
+    let (url, requests) = serve_requests(vec![
+        ("503 Service Unavailable", "{}"),
+        ("200 OK", TOKEN),
+    ]);
+
+    assert!(account_balance(&retrying_client(url, 0)).is_err());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn unexpected_response_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("503 Service Unavailable", "<html><body>Service Unavailable</body></html>"),
+    ]);
+
+    match account_balance(&retrying_client(url, 0)) {
+        Err(MpesaError::UnexpectedResponse { status, body }) => {
+            assert_eq!(status, 503);
+            assert_eq!(body, "<html><body>Service Unavailable</body></html>");
+        },
+        result => panic!("expected an unexpected response error, got {:?}", result),
+    }
+}
+
+#[test]
+fn non_json_content_type_test() {
+    let page = format!("<html><body>{}</body></html>", "Scheduled maintenance. ".repeat(40));
+    let (url, _) = serve_requests(vec![
+        Response::from(("200 OK", TOKEN)),
+        Response::new("200 OK", page.clone()).header("Content-Type", "text/html; charset=utf-8"),
+    ]);
+
+    match account_balance(&retrying_client(url, 0)) {
+        Err(MpesaError::UnexpectedResponse { status, body }) => {
+            assert_eq!(status, 200);
+            assert_eq!(body, format!("{}...", &page[..512]));
+        },
+        result => panic!("expected an unexpected response error, got {:?}", result),
+    }
+}
+
+#[test]
+fn accept_json_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN), ("200 OK", BALANCE)]);
+
+    account_balance(&retrying_client(url, 0)).unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|request| request.header("accept") == Some("application/json")));
+}
+
+#[test]
+fn rate_limited_test() {
+    let (url, requests) = serve_requests(vec![
+        Response::from(("200 OK", TOKEN)),
+        Response::new("429 Too Many Requests", r#"{"fault":"quota exceeded"}"#).header("Retry-After", "7"),
+    ]);
+
+    let error = account_balance(&retrying_client(url, 0)).unwrap_err();
+
+    assert!(matches!(error, MpesaError::RateLimited { retry_after: Some(delay) } if delay == Duration::from_secs(7)));
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn rate_limited_retry_test() {
+    let (url, requests) = serve_requests(vec![
+        Response::from(("200 OK", TOKEN)),
+        Response::new("429 Too Many Requests", "{}").header("Retry-After", "0"),
+        Response::from(("200 OK", BALANCE)),
+    ]);
+
+    let response = account_balance(&retrying_client(url, 1)).unwrap();
+
+    assert_eq!(response.ResponseCode, "0");
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn rate_limited_long_retry_after_test() {
+    let (url, requests) = serve_requests(vec![
+        Response::from(("200 OK", TOKEN)),
+        Response::new("429 Too Many Requests", "{}").header("Retry-After", "86400"),
+        Response::from(("200 OK", BALANCE)),
+    ]);
+
+    // A day is not waited for, the caller gets the delay back
+    let error = account_balance(&retrying_client(url, 3)).unwrap_err();
+
+    assert!(matches!(error, MpesaError::RateLimited { retry_after: Some(delay) } if delay == Duration::from_secs(86400)));
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
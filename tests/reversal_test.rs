@@ -0,0 +1,137 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,client_with,serve_requests,TOKEN};
+use mpesa::{MpesaError,Environment,Amount,ShortCode};
+use mpesa::callbacks::TransactionStatusCallback;
+
+const REVERSAL: &str = r#"{"OriginatorConversationID":"f1e2-4b95-a71d-b30d3cdbb7a7942864","ConversationID":"AG_20210706_20106e9209f64bebd05b","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#;
+
+/// A transaction status result with `parameters` as its result parameters
+fn status(result_code: i32, parameters: &str) -> TransactionStatusCallback {
+    serde_json::from_str(&format!(r#"{{
+        "Result": {{
+            "ResultType": 0,
+            "ResultCode": {},
+            "ResultDesc": "The service request is processed successfully.",
+            "OriginatorConversationID": "1236-7134259-1",
+            "ConversationID": "AG_20210709_1234409f86436c583e3f",
+            "TransactionID": "OEI2AK4Q16",
+            "ResultParameters": {{ "ResultParameter": [{}] }}
+        }}
+    }}"#, result_code, parameters)).unwrap()
+}
+
+const COMPLETED: &str = r#"
+    { "Key": "CreditPartyName", "Value": "600496 - Safaricom496" },
+    { "Key": "TransactionStatus", "Value": "Completed" },
+    { "Key": "ReceiptNo", "Value": "OEI2AK4Q16" },
+    { "Key": "Amount", "Value": 300 }
+"#;
+
+#[test]
+fn reversal_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN), ("200 OK", REVERSAL)]);
+
+    let reversal_response = client(url).reversal(
+        "testapi496",
+        "OEI2AK4Q16",
+        Amount::try_new(300).unwrap(),
+        &ShortCode::new("600496").unwrap(),
+        "Paid in error",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap();
+
+    assert_eq!(reversal_response.ConversationID, "AG_20210706_20106e9209f64bebd05b");
+    assert_eq!(reversal_response.ResponseCode, "0");
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[1].target, "POST /mpesa/reversal/v1/request");
+    let body = requests[1].json();
+    assert_eq!(body["CommandID"], "TransactionReversal");
+    assert_eq!(body["TransactionID"], "OEI2AK4Q16");
+    assert_eq!(body["Amount"], 300);
+    assert_eq!(body["ReceiverParty"], "600496");
+    assert_eq!(body["RecieverIdentifierType"], "11");
+}
+
+#[test]
+fn reverse_by_receipt_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN), ("200 OK", REVERSAL)]);
+
+    let reversed = client(url).reverse_by_receipt(
+        "testapi496",
+        status(0, COMPLETED),
+        &ShortCode::new("600496").unwrap(),
+        "Paid in error",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap();
+
+    assert_eq!(reversed.reversal.ResponseCode, "0");
+    assert_eq!(reversed.status.ReceiptNo.as_deref(), Some("OEI2AK4Q16"));
+
+    let body = requests.lock().unwrap()[1].json();
+    assert_eq!(body["TransactionID"], "OEI2AK4Q16");
+    assert_eq!(body["Amount"], 300);
+    assert_eq!(body["ReceiverParty"], "600496");
+}
+
+#[test]
+fn reverse_by_receipt_not_reversible_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+    let client = client(url);
+    let receiver_party = ShortCode::new("600496").unwrap();
+    let reverse = |status: TransactionStatusCallback| client.reverse_by_receipt(
+        "testapi496",
+        status,
+        &receiver_party,
+        "Paid in error",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    );
+
+    let statuses = [
+        status(2001, ""),
+        status(0, &COMPLETED.replace("Completed", "Reversed")),
+        status(0, &COMPLETED.replace("600496 - Safaricom496", "600000 - ACME LTD")),
+        status(0, &COMPLETED.replace("300", "300.5")),
+        status(0, &COMPLETED.replace("OEI2AK4Q16", "")),
+    ];
+
+    for status in statuses {
+        match reverse(status) {
+            Err(MpesaError::NotReversible(_)) => {},
+            result => panic!("expected a NotReversible error, got {:?}", result),
+        }
+    }
+
+    // No reversal was sent
+    assert!(requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn reversal_missing_transaction_id_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+
+    let error = client.reversal(
+        "testapi496",
+        "",
+        Amount::try_new(300).unwrap(),
+        &ShortCode::new("600496").unwrap(),
+        "Paid in error",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap_err();
+
+    match error {
+        MpesaError::MissingField(field) => assert_eq!(field, "transaction_id"),
+        e => panic!("unexpected error: {}", e),
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::{FixedOffset,TimeZone,Utc};
+use mpesa::security::{stk_password,timestamp,timestamp_at};
+
+#[test]
+fn stk_password_test() {
+    let password = stk_password(
+        "174379",
+        "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919",
+        "20160216165627",
+    );
+    assert_eq!(
+        password,
+        "MTc0Mzc5YmZiMjc5ZjlhYTliZGJjZjE1OGU5N2RkNzFhNDY3Y2QyZTBjODkzMDU5YjEwZjc4ZTZiNzJhZGExZWQyYzkxOTIwMTYwMjE2MTY1NjI3"
+    );
+}
+
+#[test]
+fn timestamp_test() {
+    assert_eq!(timestamp_at(Utc.with_ymd_and_hms(2016, 2, 16, 16, 56, 27).unwrap()), "20160216165627");
+
+    // The timestamp is formatted in the offset of the given time
+    let nairobi = FixedOffset::east_opt(3 * 3600).unwrap();
+    assert_eq!(timestamp_at(Utc.with_ymd_and_hms(2016, 2, 16, 21, 0, 0).unwrap().with_timezone(&nairobi)), "20160217000000");
+
+    let now = timestamp();
+    assert_eq!(now.len(), 14);
+    assert!(now.chars().all(|c| c.is_ascii_digit()));
+}
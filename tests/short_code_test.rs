@@ -0,0 +1,22 @@
+use mpesa::{MpesaError,ShortCode};
+use std::convert::TryFrom;
+
+#[test]
+fn short_code_valid_test() {
+    for code in &["60049", "600496", "1234567", " 600496 "] {
+        assert_eq!(ShortCode::new(code).unwrap().as_str(), code.trim());
+    }
+
+    assert_eq!("174379".parse::<ShortCode>().unwrap().to_string(), "174379");
+    assert_eq!(ShortCode::try_from("600000").unwrap().as_str(), "600000");
+}
+
+#[test]
+fn short_code_invalid_test() {
+    for code in &["", "6004", "12345678", "6004a6", "600 496", "-60049"] {
+        assert!(ShortCode::new(code).is_err(), "{} should be invalid", code);
+    }
+
+    let error: MpesaError = ShortCode::new("abc").unwrap_err().into();
+    assert!(matches!(error, MpesaError::InvalidShortCode(_)));
+}
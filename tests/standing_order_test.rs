@@ -0,0 +1,43 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{client,serve_requests,TOKEN};
+use mpesa::{Amount,Frequency,IdentifierTypes,MpesaResult,PhoneNumber,StandingOrderTransactionType};
+
+#[test]
+fn standing_order_test() {
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"ResponseHeader":{"responseRefID":"4dd9b5d9-d738-42ba-9326-2cc99e966000","responseCode":"200","responseDescription":"Request accepted for processing","ResultDesc":"The service request is processed successfully."},"ResponseBody":{"responseDescription":"Request accepted for processing","responseCode":"200"}}"#),
+    ]);
+
+    let client = client(url);
+
+    let standing_order_response = client.standing_order(
+        "Test Standing Order",
+        NaiveDate::from_ymd_opt(2024, 9, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 9, 5).unwrap(),
+        "174379",
+        Amount::try_new(4500).unwrap(),
+        &PhoneNumber::new("0708374149").unwrap(),
+        Frequency::Monthly,
+        StandingOrderTransactionType::PayBill,
+        IdentifierTypes::Shortcode,
+        "Test",
+        "Test",
+        "https://muriuki.dev/api/callback",
+    ).unwrap();
+
+    assert!(standing_order_response.is_success());
+    assert_eq!(standing_order_response.ResponseHeader.responseRefID, "4dd9b5d9-d738-42ba-9326-2cc99e966000");
+
+    let body: serde_json::Value = requests.lock().unwrap()[1].json();
+
+    assert_eq!(body["StartDate"], "20240905");
+    assert_eq!(body["Frequency"], "4");
+    assert_eq!(body["TransactionType"], "Standing Order Customer Pay Bill");
+    assert_eq!(body["ReceiverPartyIdentifierType"], "4");
+    assert_eq!(body["PartyA"], "254708374149");
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,client_with,serve_requests,TOKEN};
+use mpesa::{MpesaError,Environment,Amount};
+
+#[test]
+fn tax_remittance_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"OriginatorConversationID":"5118-111210482-1","ConversationID":"AG_20230420_2010759fd5662ef6d054","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#),
+    ]);
+
+    let client = client(url);
+
+    let tax_remittance_response = client.tax_remittance(
+        "testapi496",
+        Amount::try_new(239).unwrap(),
+        "600496",
+        "572572",
+        "Tax remittance",
+        "353353",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap();
+
+    assert_eq!(tax_remittance_response.ConversationID, "AG_20230420_2010759fd5662ef6d054");
+    assert_eq!(tax_remittance_response.ResponseCode, "0");
+}
+
+#[test]
+fn tax_remittance_missing_prn_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+
+    let error = client.tax_remittance(
+        "testapi496",
+        Amount::try_new(239).unwrap(),
+        "600496",
+        "572572",
+        "Tax remittance",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap_err();
+
+    match error {
+        MpesaError::MissingField(field) => assert_eq!(field, "account_reference"),
+        e => panic!("unexpected error: {}", e),
+    }
+}
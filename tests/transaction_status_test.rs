@@ -0,0 +1,51 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::{client,client_with,serve_requests,TOKEN};
+use mpesa::{MpesaError,Environment,IdentifierTypes};
+
+#[test]
+fn transaction_status_test() {
+    let (url, _) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", r#"{"OriginatorConversationID":"1236-7134259-1","ConversationID":"AG_20210709_1234409f86436c583e3f","ResponseCode":"0","ResponseDescription":"Accept the service request successfully."}"#),
+    ]);
+
+    let client = client(url);
+
+    let transaction_status_response = client.transaction_status(
+        "testapi496",
+        "OEI2AK4Q16",
+        "600496",
+        IdentifierTypes::Shortcode,
+        "Confirm payee",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap();
+
+    assert_eq!(transaction_status_response.ConversationID, "AG_20210709_1234409f86436c583e3f");
+    assert_eq!(transaction_status_response.ResponseCode, "0");
+}
+
+#[test]
+fn transaction_status_missing_transaction_id_test() {
+    let client = client_with(Environment::Sandbox, |builder| builder);
+
+    let error = client.transaction_status(
+        "testapi496",
+        "",
+        "600496",
+        IdentifierTypes::Shortcode,
+        "Confirm payee",
+        "",
+        "https://muriuki.dev/api/a",
+        "https://muriuki.dev/api/b",
+    ).unwrap_err();
+
+    match error {
+        MpesaError::MissingField(field) => assert_eq!(field, "transaction_id"),
+        e => panic!("unexpected error: {}", e),
+    }
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use common::client_with;
+use mpesa::{Amount,CommandId,Mpesa,Environment,IdentifierTypes,MpesaError,PhoneNumber,ShortCode};
+
+/// A client of `environment` that records its requests instead of sending them
+fn recording_client(environment: Environment) -> Mpesa {
+    client_with(environment, |builder| builder.record_requests())
+}
+
+fn account_balance(client: &Mpesa, result_url: &str) -> Result<mpesa::payloads::AccountBalanceResponse, MpesaError> {
+    client.account_balance(
+        &ShortCode::new("600496").unwrap(),
+        IdentifierTypes::Shortcode,
+        "none",
+        "collins",
+        "https://hell.world/api",
+        result_url,
+    )
+}
+
+#[test]
+fn http_callback_url_production_test() {
+    match account_balance(&recording_client(Environment::Production), "http://hello.world/api") {
+        Err(MpesaError::InvalidUrl(_)) => {},
+        _ => panic!("expected an InvalidUrl"),
+    }
+}
+
+#[test]
+fn http_callback_url_sandbox_test() {
+    assert!(account_balance(&recording_client(Environment::Sandbox), "http://hello.world/api").is_ok());
+}
+
+#[test]
+fn invalid_callback_url_test() {
+    match account_balance(&recording_client(Environment::Sandbox), "hello.world/api") {
+        Err(MpesaError::InvalidUrl(_)) => {},
+        _ => panic!("expected an InvalidUrl"),
+    }
+}
+
+#[test]
+fn c2b_simulate_production_test() {
+    let simulate = |client: &Mpesa| client.c2b_simulate(
+        CommandId::CustomerPayBillOnline,
+        Amount::try_new(1).unwrap(),
+        &PhoneNumber::new("254705583540").unwrap(),
+        "123abc",
+        &ShortCode::new("600496").unwrap(),
+    );
+
+    match simulate(&recording_client(Environment::Production)) {
+        Err(MpesaError::SandboxOnly(api)) => assert_eq!(api, "c2b_simulate"),
+        _ => panic!("expected a SandboxOnly"),
+    }
+    assert!(simulate(&recording_client(Environment::Sandbox)).is_ok());
+}
@@ -0,0 +1,455 @@
+#![cfg(feature = "client")]
+
+mod common;
+
+use chrono::{Local,NaiveDate,TimeZone};
+use common::{client,client_with,serve_requests,Request,BALANCE,TOKEN};
+use mpesa::{Amount,CommandId,Environment,IdentifierTypes,Mpesa,PhoneNumber,QrTransactionType,ResponseType,ShortCode};
+use mpesa::{Frequency,StandingOrderTransactionType};
+use mpesa::clock::FixedClock;
+use mpesa::security::stk_password;
+use serde_json::{json,Value};
+
+// Wire format of every API call, checked against a local stub server
+
+const PASSKEY: &str = "bfb279f9aa9bdbcf158e97dd71a467cd2e0c893059b10f78e6b72ada1ed2c919";
+
+/// Serves a token and then `response`, returning the API request sent by `call`
+fn exchange<F>(response: &'static str, call: F) -> Request
+where
+    F: FnOnce(&Mpesa),
+{
+    let (url, requests) = serve_requests(vec![
+        ("200 OK", TOKEN),
+        ("200 OK", response),
+    ]);
+
+    call(&client(url));
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].target, "GET /oauth/v1/generate");
+
+    let request = requests[1].clone();
+    assert_eq!(request.header("authorization"), Some("bearer token"));
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    request
+}
+
+/// Checks that the body holds security credentials and removes them, as they are randomly padded
+fn take_security_credential(body: &mut Value) {
+    let credential = body.as_object_mut().unwrap().remove("SecurityCredential").unwrap();
+    assert!(!credential.as_str().unwrap().is_empty());
+}
+
+#[test]
+fn auth_wire_test() {
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN)]);
+
+    assert_eq!(client(url).auth().unwrap().token(), "token");
+
+    let request = requests.lock().unwrap()[0].clone();
+    let credentials = base64::encode("client_key:client_secret").to_lowercase();
+    assert_eq!(request.target, "GET /oauth/v1/generate");
+    assert_eq!(request.header("authorization"), Some(format!("basic {}", credentials).as_str()));
+    assert!(request.body.is_empty());
+}
+
+#[test]
+fn b2c_wire_test() {
+    let request = exchange(BALANCE, |client| {
+        let response = client.b2c()
+            .originator_conversation_id("b2c-1")
+            .initiator_name("testapi496")
+            .command_id(CommandId::BusinessPayment)
+            .amount(Amount::try_new(1000).unwrap())
+            .party_a(&ShortCode::new("600496").unwrap())
+            .party_b(&PhoneNumber::new("0708374149").unwrap())
+            .remarks("gg")
+            .queue_timeout_url("https://muriuki.dev/api/a")
+            .result_url("https://muriuki.dev/api/b")
+            .occasion("Test")
+            .send()
+            .unwrap();
+
+        assert_eq!(response.ConversationID, "2");
+    });
+
+    let mut body = request.json();
+    take_security_credential(&mut body);
+
+    assert_eq!(request.target, "POST /mpesa/b2c/v1/paymentrequest");
+    assert_eq!(body, json!({
+        "OriginatorConversationID": "b2c-1",
+        "InitiatorName": "testapi496",
+        "CommandID": "BusinessPayment",
+        "Amount": 1000,
+        "PartyA": "600496",
+        "PartyB": "254708374149",
+        "Remarks": "gg",
+        "QueueTimeOutURL": "https://muriuki.dev/api/a",
+        "ResultURL": "https://muriuki.dev/api/b",
+        "Occasion": "Test",
+    }));
+}
+
+#[test]
+fn b2b_wire_test() {
+    let request = exchange(BALANCE, |client| {
+        let response = client.b2b(
+            "testapi496",
+            CommandId::BusinessPayBill,
+            Amount::try_new(1000).unwrap(),
+            "600496",
+            IdentifierTypes::Shortcode,
+            "600000",
+            IdentifierTypes::Shortcode,
+            "gg",
+            "https://muriuki.dev/api/a",
+            "https://muriuki.dev/api/b",
+            "353353",
+            Some("b2b-1"),
+        ).unwrap();
+
+        assert_eq!(response.OriginatorConversationID, "1");
+    });
+
+    let mut body = request.json();
+    take_security_credential(&mut body);
+
+    assert_eq!(request.target, "POST /mpesa/b2b/v1/paymentrequest");
+    assert_eq!(body, json!({
+        "OriginatorConversationID": "b2b-1",
+        "Initiator": "testapi496",
+        "CommandID": "BusinessPayBill",
+        "Amount": 1000,
+        "PartyA": "600496",
+        "SenderIdentifierType": "4",
+        "PartyB": "600000",
+        "RecieverIdentifierType": "4",
+        "Remarks": "gg",
+        "QueueTimeOutURL": "https://muriuki.dev/api/a",
+        "ResultURL": "https://muriuki.dev/api/b",
+        "AccountReference": "353353",
+    }));
+}
+
+#[test]
+fn c2b_register_wire_test() {
+    let request = exchange(r#"{"OriginatorCoversationID":"6602-1","ResponseCode":"0","ResponseDescription":"success"}"#, |client| {
+        let response = client.c2b_register(
+            "https://muriuki.dev/api/validate",
+            "https://muriuki.dev/api/confirm",
+            ResponseType::Complete,
+            &ShortCode::new("600496").unwrap(),
+        ).unwrap();
+
+        assert_eq!(response.OriginatorConversationID, "6602-1");
+    });
+
+    assert_eq!(request.target, "POST /mpesa/c2b/v1/registerurl");
+    assert_eq!(request.json(), json!({
+        "ValidationURL": "https://muriuki.dev/api/validate",
+        "ConfirmationURL": "https://muriuki.dev/api/confirm",
+        "ResponseType": "Completed",
+        "ShortCode": "600496",
+    }));
+}
+
+#[test]
+fn c2b_simulate_wire_test() {
+    let request = exchange(BALANCE, |client| {
+        client.c2b_simulate(
+            CommandId::CustomerPayBillOnline,
+            Amount::try_new(10).unwrap(),
+            &PhoneNumber::new("254708374149").unwrap(),
+            "account",
+            &ShortCode::new("600496").unwrap(),
+        ).unwrap();
+    });
+
+    assert_eq!(request.target, "POST /mpesa/c2b/v1/simulate");
+    assert_eq!(request.json(), json!({
+        "CommandID": "CustomerPayBillOnline",
+        "Amount": 10,
+        "Msisdn": "254708374149",
+        "BillRefNumber": "account",
+        "ShortCode": "600496",
+    }));
+}
+
+#[test]
+fn account_balance_wire_test() {
+    let request = exchange(BALANCE, |client| {
+        let response = client.account_balance(
+            &ShortCode::new("600496").unwrap(),
+            IdentifierTypes::Shortcode,
+            "none",
+            "testapi496",
+            "https://muriuki.dev/api/a",
+            "https://muriuki.dev/api/b",
+        ).unwrap();
+
+        assert_eq!(response.ResponseDescription, "Accept the service request successfully.");
+    });
+
+    let mut body = request.json();
+    take_security_credential(&mut body);
+
+    assert_eq!(request.target, "POST /mpesa/accountbalance/v1/query");
+    assert_eq!(body, json!({
+        "Initiator": "testapi496",
+        "CommandID": "AccountBalance",
+        "PartyA": "600496",
+        "IdentifierType": "4",
+        "Remarks": "none",
+        "QueueTimeOutURL": "https://muriuki.dev/api/a",
+        "ResultURL": "https://muriuki.dev/api/b",
+    }));
+}
+
+#[test]
+fn express_request_wire_test() {
+    let response = r#"{"MerchantRequestID":"29115-1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"Success. Request accepted for processing","CustomerMessage":"Success. Request accepted for processing"}"#;
+    let request = exchange(response, |client| {
+        let response = client.express_request(
+            "174379",
+            PASSKEY,
+            CommandId::CustomerPayBillOnline,
+            Amount::try_new(1).unwrap(),
+            &PhoneNumber::new("254708374149").unwrap(),
+            "174379",
+            &PhoneNumber::new("254708374149").unwrap(),
+            "https://muriuki.dev/api/callback",
+            "Test",
+            "Payment",
+        ).unwrap();
+
+        assert_eq!(response.CheckoutRequestID, "ws_CO_1");
+    });
+
+    let mut body = request.json();
+    let body = body.as_object_mut().unwrap();
+    let timestamp = body.remove("Timestamp").unwrap();
+    let timestamp = timestamp.as_str().unwrap();
+    assert_eq!(timestamp.len(), 14);
+    assert_eq!(body.remove("Password").unwrap(), stk_password("174379", PASSKEY, timestamp));
+
+    assert_eq!(request.target, "POST /mpesa/stkpush/v1/processrequest");
+    assert_eq!(Value::Object(body.clone()), json!({
+        "BusinessShortCode": "174379",
+        "TransactionType": "CustomerPayBillOnline",
+        "Amount": 1,
+        "PartyA": "254708374149",
+        "PartyB": "174379",
+        "PhoneNumber": "254708374149",
+        "CallBackURL": "https://muriuki.dev/api/callback",
+        "AccountReference": "Test",
+        "TransactionDesc": "Payment",
+    }));
+}
+
+#[test]
+fn express_request_fixed_clock_wire_test() {
+    let response = r#"{"MerchantRequestID":"29115-1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"Success. Request accepted for processing","CustomerMessage":"Success. Request accepted for processing"}"#;
+    let (url, requests) = serve_requests(vec![("200 OK", TOKEN), ("200 OK", response)]);
+    let client = client_with(Environment::Custom(url), |builder| builder.clock(FixedClock::new(Local.with_ymd_and_hms(2016, 2, 16, 16, 56, 27).unwrap())));
+
+    client.express_request(
+        "174379",
+        PASSKEY,
+        CommandId::CustomerPayBillOnline,
+        Amount::try_new(1).unwrap(),
+        &PhoneNumber::new("254708374149").unwrap(),
+        "174379",
+        &PhoneNumber::new("254708374149").unwrap(),
+        "https://muriuki.dev/api/callback",
+        "Test",
+        "Payment",
+    ).unwrap();
+
+    let body = requests.lock().unwrap()[1].json();
+    assert_eq!(body["Timestamp"], "20160216165627");
+    assert_eq!(
+        body["Password"],
+        "MTc0Mzc5YmZiMjc5ZjlhYTliZGJjZjE1OGU5N2RkNzFhNDY3Y2QyZTBjODkzMDU5YjEwZjc4ZTZiNzJhZGExZWQyYzkxOTIwMTYwMjE2MTY1NjI3",
+    );
+}
+
+#[test]
+fn express_query_wire_test() {
+    let response = r#"{"MerchantRequestID":"29115-1","CheckoutRequestID":"ws_CO_1","ResponseCode":"0","ResponseDescription":"The service request has been accepted successsfully","ResultCode":"0","ResultDesc":"The service request is processed successfully."}"#;
+    let request = exchange(response, |client| {
+        let response = client.express_query("174379", PASSKEY, "ws_CO_1").unwrap();
+        assert_eq!(response.ResultCode, "0");
+    });
+
+    let body = request.json();
+    let timestamp = body["Timestamp"].as_str().unwrap();
+
+    assert_eq!(request.target, "POST /mpesa/stkpushquery/v1/query");
+    assert_eq!(body, json!({
+        "BusinessShortCode": "174379",
+        "Password": stk_password("174379", PASSKEY, timestamp),
+        "Timestamp": timestamp,
+        "CheckoutRequestID": "ws_CO_1",
+    }));
+}
+
+#[test]
+fn b2b_express_checkout_wire_test() {
+    let request = exchange(r#"{"code":"0","status":"USSD Initiated Successfully"}"#, |client| {
+        let response = client.b2b_express_checkout(
+            "000001",
+            "000002",
+            Amount::try_new(100).unwrap(),
+            "paymentRef",
+            "https://muriuki.dev/api/callback",
+            "Vendor",
+            "5d8b4b1c-0b9e-4f6a-9f5e-2a1d8c4b7e10",
+        ).unwrap();
+
+        assert_eq!(response.status, "USSD Initiated Successfully");
+    });
+
+    assert_eq!(request.target, "POST /v1/ussdpush/get-msisdn");
+    assert_eq!(request.json(), json!({
+        "primaryShortCode": "000001",
+        "receiverShortCode": "000002",
+        "amount": "100",
+        "paymentRef": "paymentRef",
+        "callbackUrl": "https://muriuki.dev/api/callback",
+        "partnerName": "Vendor",
+        "RequestRefID": "5d8b4b1c-0b9e-4f6a-9f5e-2a1d8c4b7e10",
+    }));
+}
+
+#[test]
+fn dynamic_qr_wire_test() {
+    let response = r#"{"ResponseCode":"00","RequestID":"16738-27456357-1","ResponseDescription":"QR Code Successfully Generated.","QRCode":"iVBORw0KGgo"}"#;
+    let request = exchange(response, |client| {
+        let response = client.dynamic_qr(
+            "TEST SUPERMARKET",
+            "Invoice Test",
+            Amount::try_new(1).unwrap(),
+            QrTransactionType::BuyGoods,
+            "373132",
+            300,
+        ).unwrap();
+
+        assert_eq!(response.QRCode, "iVBORw0KGgo");
+    });
+
+    assert_eq!(request.target, "POST /mpesa/qrcode/v1/generate");
+    assert_eq!(request.json(), json!({
+        "MerchantName": "TEST SUPERMARKET",
+        "RefNo": "Invoice Test",
+        "Amount": 1,
+        "TrxCode": "BG",
+        "CPI": "373132",
+        "Size": "300",
+    }));
+}
+
+#[test]
+fn tax_remittance_wire_test() {
+    let request = exchange(BALANCE, |client| {
+        client.tax_remittance(
+            "testapi496",
+            Amount::try_new(239).unwrap(),
+            "600496",
+            "572572",
+            "Tax remittance",
+            "353353",
+            "https://muriuki.dev/api/a",
+            "https://muriuki.dev/api/b",
+        ).unwrap();
+    });
+
+    let mut body = request.json();
+    take_security_credential(&mut body);
+
+    assert_eq!(request.target, "POST /mpesa/b2b/v1/remittax");
+    assert_eq!(body, json!({
+        "Initiator": "testapi496",
+        "CommandID": "PayTaxToKRA",
+        "SenderIdentifierType": "4",
+        "RecieverIdentifierType": "4",
+        "Amount": 239,
+        "PartyA": "600496",
+        "PartyB": "572572",
+        "AccountReference": "353353",
+        "Remarks": "Tax remittance",
+        "QueueTimeOutURL": "https://muriuki.dev/api/a",
+        "ResultURL": "https://muriuki.dev/api/b",
+    }));
+}
+
+#[test]
+fn transaction_status_wire_test() {
+    let request = exchange(BALANCE, |client| {
+        client.transaction_status(
+            "testapi496",
+            "OEI2AK4Q16",
+            "600496",
+            IdentifierTypes::Shortcode,
+            "Confirm payee",
+            "",
+            "https://muriuki.dev/api/a",
+            "https://muriuki.dev/api/b",
+        ).unwrap();
+    });
+
+    let mut body = request.json();
+    take_security_credential(&mut body);
+
+    assert_eq!(request.target, "POST /mpesa/transactionstatus/v1/query");
+    assert_eq!(body, json!({
+        "Initiator": "testapi496",
+        "CommandID": "TransactionStatusQuery",
+        "TransactionID": "OEI2AK4Q16",
+        "PartyA": "600496",
+        "IdentifierType": "4",
+        "Remarks": "Confirm payee",
+        "Occasion": "",
+        "QueueTimeOutURL": "https://muriuki.dev/api/a",
+        "ResultURL": "https://muriuki.dev/api/b",
+    }));
+}
+
+#[test]
+fn standing_order_wire_test() {
+    let response = r#"{"ResponseHeader":{"responseRefID":"4dd9b5d9-1","responseCode":"200","responseDescription":"Request accepted for processing","ResultDesc":"The service request is processed successfully."},"ResponseBody":{"responseDescription":"Request accepted for processing","responseCode":"200"}}"#;
+    let request = exchange(response, |client| {
+        let response = client.standing_order(
+            "Test Standing Order",
+            NaiveDate::from_ymd_opt(2024, 9, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 9, 5).unwrap(),
+            "174379",
+            Amount::try_new(4500).unwrap(),
+            &PhoneNumber::new("0708374149").unwrap(),
+            Frequency::Monthly,
+            StandingOrderTransactionType::PayBill,
+            IdentifierTypes::Shortcode,
+            "Test",
+            "Electric Bike",
+            "https://muriuki.dev/api/callback",
+        ).unwrap();
+
+        assert_eq!(response.ResponseHeader.responseCode, "200");
+    });
+
+    let body = request.json();
+
+    assert_eq!(request.target, "POST /standingorder/v1/createStandingOrderExternal");
+    assert_eq!(body["StandingOrderName"], "Test Standing Order");
+    assert_eq!(body["StartDate"], "20240905");
+    assert_eq!(body["EndDate"], "20250905");
+    assert_eq!(body["BusinessShortCode"], "174379");
+    assert_eq!(body["TransactionType"], "Standing Order Customer Pay Bill");
+    assert_eq!(body["ReceiverPartyIdentifierType"], "4");
+    assert_eq!(body["Amount"], "4500");
+    assert_eq!(body["PartyA"], "254708374149");
+    assert_eq!(body["Frequency"], "4");
+    assert_eq!(body["CallBackURL"], "https://muriuki.dev/api/callback");
+}